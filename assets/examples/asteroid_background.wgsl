@@ -1,4 +1,4 @@
-#import noisy_bevy::fbm_simplex_2d_seeded
+#import noisy_bevy::fbm::fbm_simplex_2d_seeded
 #import bevy_sprite::mesh2d_functions::{
     get_world_from_local,
     mesh2d_position_local_to_clip,
@@ -2,7 +2,7 @@
     get_world_from_local,
     mesh2d_position_local_to_clip,
 }
-#import noisy_bevy::worley_2d
+#import noisy_bevy::worley::worley_2d
 
 struct NoiseMaterial {
     frequency_scale: f32,
@@ -1,404 +1,155 @@
 //  MIT License. © Ian McEwan, Stefan Gustavson, Munrocket, Johan Helsing
 
+// Umbrella module forwarding to the simplex, fbm, warp and worley sub-modules,
+// kept so existing `#import noisy_bevy` shaders keep working.
+//
+// Importing a sub-module directly pulls in less code and keeps helpers out of
+// your namespace. The virtual warp noise functions live in the warp module,
+// so that's where they need to be overridden.
+
 #define_import_path noisy_bevy
 
-fn permute_3_(x: vec3<f32>) -> vec3<f32> {
-    return (((x * 34.) + 1.) * x) % vec3(289.);
+#import noisy_bevy::simplex
+#import noisy_bevy::fbm
+#import noisy_bevy::warp
+#import noisy_bevy::worley
+
+fn simplex_noise_1d(v: f32) -> f32 {
+    return simplex::simplex_noise_1d(v);
 }
 
-fn step_3(edge: vec3<f32>, x: vec3<f32>) -> vec3<f32> {
-    let b = vec3(edge.x < x.x, edge.y <= x.y, edge.z <= x.z);
-    return select(vec3(0.), vec3(1.), b);
+fn simplex_noise_1d_seeded(v: f32, seed: f32) -> f32 {
+    return simplex::simplex_noise_1d_seeded(v, seed);
 }
 
 fn simplex_noise_2d(v: vec2<f32>) -> f32 {
-    let C = vec4(
-        0.211324865405187, // (3.0 - sqrt(3.0)) / 6.0
-        0.366025403784439, // 0.5 * (sqrt(3.0) - 1.0)
-        -0.577350269189626, // -1.0 + 2.0 * C.x
-        0.024390243902439 // 1.0 / 41.0
-    );
-
-    // first corner
-    var i = floor(v + dot(v, C.yy));
-    let x0 = v - i + dot(i, C.xx);
-
-    // other corners
-    var i1 = select(vec2(0., 1.), vec2(1., 0.), x0.x > x0.y);
-    var x12 = x0.xyxy + C.xxzz - vec4(i1, 0., 0.);
-
-    // permutations
-    i = i % vec2(289.);
-
-    let p = permute_3_(permute_3_(i.y + vec3(0., i1.y, 1.)) + i.x + vec3(0., i1.x, 1.));
-    var m = max(0.5 - vec3(dot(x0, x0), dot(x12.xy, x12.xy), dot(x12.zw, x12.zw)), vec3(0.));
-    m *= m;
-    m *= m;
-
-    // gradients: 41 points uniformly over a line, mapped onto a diamond
-    // the ring size, 17*17 = 289, is close to a multiple of 41 (41*7 = 287)
-    let x = 2. * fract(p * C.www) - 1.;
-    let h = abs(x) - 0.5;
-    let ox = floor(x + 0.5);
-    let a0 = x - ox;
-
-    // normalize gradients implicitly by scaling m
-    // approximation of: m *= inversesqrt(a0 * a0 + h * h);
-    m = m * (1.79284291400159 - 0.85373472095314 * (a0 * a0 + h * h));
-
-    // compute final noise value at P
-    let g = vec3(a0.x * x0.x + h.x * x0.y, a0.yz * x12.xz + h.yz * x12.yw);
-    return 130. * dot(m, g);
+    return simplex::simplex_noise_2d(v);
 }
 
 fn simplex_noise_2d_seeded(v: vec2<f32>, seed: f32) -> f32 {
-    let C = vec4(
-        0.211324865405187, // (3.0 - sqrt(3.0)) / 6.0
-        0.366025403784439, // 0.5 * (sqrt(3.0) - 1.0)
-        -0.577350269189626, // -1.0 + 2.0 * C.x
-        0.024390243902439 // 1.0 / 41.0
-    );
-
-    // first corner
-    var i = floor(v + dot(v, C.yy));
-    let x0 = v - i + dot(i, C.xx);
-
-    // other corners
-    var i1 = select(vec2(0., 1.), vec2(1., 0.), x0.x > x0.y);
-    var x12 = x0.xyxy + C.xxzz - vec4(i1, 0., 0.);
-
-    // permutations
-    i = i % vec2(289.);
-
-    var p = permute_3_(permute_3_(i.y + vec3(0., i1.y, 1.)) + i.x + vec3(0., i1.x, 1.));
-    p = permute_3_(p + vec3(seed));
-    var m = max(0.5 - vec3(dot(x0, x0), dot(x12.xy, x12.xy), dot(x12.zw, x12.zw)), vec3(0.));
-    m *= m;
-    m *= m;
-
-    // gradients: 41 points uniformly over a line, mapped onto a diamond
-    // the ring size, 17*17 = 289, is close to a multiple of 41 (41*7 = 287)
-    let x = 2. * fract(p * C.www) - 1.;
-    let h = abs(x) - 0.5;
-    let ox = floor(x + 0.5);
-    let a0 = x - ox;
-
-    // normalize gradients implicitly by scaling m
-    // approximation of: m *= inversesqrt(a0 * a0 + h * h);
-    m = m * (1.79284291400159 - 0.85373472095314 * (a0 * a0 + h * h));
-
-    // compute final noise value at P
-    let g = vec3(a0.x * x0.x + h.x * x0.y, a0.yz * x12.xz + h.yz * x12.yw);
-    return 130. * dot(m, g);
+    return simplex::simplex_noise_2d_seeded(v, seed);
 }
 
-fn permute_4_(x: vec4<f32>) -> vec4<f32> {
-    return ((x * 34. + 1.) * x) % vec4<f32>(289.);
+fn simplex_noise_3d(v: vec3<f32>) -> f32 {
+    return simplex::simplex_noise_3d(v);
 }
 
-fn taylor_inv_sqrt_4_(r: vec4<f32>) -> vec4<f32> {
-    return 1.79284291400159 - 0.85373472095314 * r;
+fn simplex_noise_3d_seeded(v: vec3<f32>, seed: vec3<f32>) -> f32 {
+    return simplex::simplex_noise_3d_seeded(v, seed);
 }
 
-fn simplex_noise_3d(v: vec3<f32>) -> f32 {
-    let C = vec2(1. / 6., 1. / 3.);
-    let D = vec4(0., 0.5, 1., 2.);
-
-    // first corner
-    var i = floor(v + dot(v, C.yyy));
-    let x0 = v - i + dot(i, C.xxx);
-
-    // other corners
-    let g = step_3(x0.yzx, x0.xyz);
-    let l = 1. - g;
-    let i1 = min(g.xyz, l.zxy);
-    let i2 = max(g.xyz, l.zxy);
-
-    // x0 = x0 - 0. + 0. * C
-    let x1 = x0 - i1 + 1. * C.xxx;
-    let x2 = x0 - i2 + 2. * C.xxx;
-    let x3 = x0 - 1. + 3. * C.xxx;
-
-    // permutations
-    i = i % vec3(289.);
-    let p = permute_4_(permute_4_(permute_4_(
-        i.z + vec4(0., i1.z, i2.z, 1.)) +
-        i.y + vec4(0., i1.y, i2.y, 1.)) +
-        i.x + vec4(0., i1.x, i2.x, 1.)
-    );
-
-    // gradients (NxN points uniformly over a square, mapped onto an octahedron)
-    let n_ = 1. / 7.; // N=7
-    let ns = n_ * D.wyz - D.xzx;
-
-    let j = p - 49. * floor(p * ns.z * ns.z); // mod(p, N*N)
-
-    let x_ = floor(j * ns.z);
-    let y_ = floor(j - 7. * x_); // mod(j, N)
-
-    let x = x_ * ns.x + ns.yyyy;
-    let y = y_ * ns.x + ns.yyyy;
-    let h = 1. - abs(x) - abs(y);
-
-    let b0 = vec4(x.xy, y.xy);
-    let b1 = vec4(x.zw, y.zw);
-
-    let s0 = floor(b0) * 2. + 1.;
-    let s1 = floor(b1) * 2. + 1.;
-    let sh = -step(h, vec4(0.));
-
-    let a0 = b0.xzyw + s0.xzyw * sh.xxyy;
-    let a1 = b1.xzyw + s1.xzyw * sh.zzww;
-
-    var p0 = vec3(a0.xy, h.x);
-    var p1 = vec3(a0.zw, h.y);
-    var p2 = vec3(a1.xy, h.z);
-    var p3 = vec3(a1.zw, h.w);
-
-    // normalize gradients
-    let norm = taylor_inv_sqrt_4_(vec4(dot(p0, p0), dot(p1, p1), dot(p2, p2), dot(p3, p3)));
-    p0 = p0 * norm.x;
-    p1 = p1 * norm.y;
-    p2 = p2 * norm.z;
-    p3 = p3 * norm.w;
-
-    // mix final noise value
-    var m = 0.5 - vec4(dot(x0, x0), dot(x1, x1), dot(x2, x2), dot(x3, x3));
-    m = max(m, vec4(0.));
-    m *= m;
-    return 105. * dot(m * m, vec4(dot(p0, x0), dot(p1, x1), dot(p2, x2), dot(p3, x3)));
+fn simplex_noise_2d_flow(v: vec2<f32>, rotation: f32) -> f32 {
+    return simplex::simplex_noise_2d_flow(v, rotation);
 }
 
-fn simplex_noise_3d_seeded(v: vec3<f32>, seed: vec3<f32>) -> f32 {
-    let C = vec2(1. / 6., 1. / 3.);
-    let D = vec4(0., 0.5, 1., 2.);
-
-    // first corner
-    var i = floor(v + dot(v, C.yyy));
-    let x0 = v - i + dot(i, C.xxx);
-
-    // other corners
-    let g = step_3(x0.yzx, x0.xyz);
-    let l = 1. - g;
-    let i1 = min(g.xyz, l.zxy);
-    let i2 = max(g.xyz, l.zxy);
-
-    // x0 = x0 - 0. + 0. * C
-    let x1 = x0 - i1 + 1. * C.xxx;
-    let x2 = x0 - i2 + 2. * C.xxx;
-    let x3 = x0 - 1. + 3. * C.xxx;
-
-    // permutations
-    i = i % vec3(289.);
-    let s = floor(seed + vec3(0.5));
-    let p = permute_4_(permute_4_(permute_4_(
-        i.z + vec4(0., i1.z, i2.z, 1.) + s.z) +
-        i.y + vec4(0., i1.y, i2.y, 1.) + s.y) +
-        i.x + vec4(0., i1.x, i2.x, 1.) + s.x
-    );
-
-    // gradients (NxN points uniformly over a square, mapped onto an octahedron)
-    let n_ = 1. / 7.; // N=7
-    let ns = n_ * D.wyz - D.xzx;
-
-    let j = p - 49. * floor(p * ns.z * ns.z); // mod(p, N*N)
-
-    let x_ = floor(j * ns.z);
-    let y_ = floor(j - 7. * x_); // mod(j, N)
-
-    let x = x_ * ns.x + ns.yyyy;
-    let y = y_ * ns.x + ns.yyyy;
-    let h = 1. - abs(x) - abs(y);
-
-    let b0 = vec4(x.xy, y.xy);
-    let b1 = vec4(x.zw, y.zw);
-
-    let s0 = floor(b0) * 2. + 1.;
-    let s1 = floor(b1) * 2. + 1.;
-    let sh = -step(h, vec4(0.));
-
-    let a0 = b0.xzyw + s0.xzyw * sh.xxyy;
-    let a1 = b1.xzyw + s1.xzyw * sh.zzww;
-
-    var p0 = vec3(a0.xy, h.x);
-    var p1 = vec3(a0.zw, h.y);
-    var p2 = vec3(a1.xy, h.z);
-    var p3 = vec3(a1.zw, h.w);
-
-    // normalize gradients
-    let norm = taylor_inv_sqrt_4_(vec4(dot(p0, p0), dot(p1, p1), dot(p2, p2), dot(p3, p3)));
-    p0 = p0 * norm.x;
-    p1 = p1 * norm.y;
-    p2 = p2 * norm.z;
-    p3 = p3 * norm.w;
-
-    // mix final noise value
-    var m = 0.6 - vec4(dot(x0, x0), dot(x1, x1), dot(x2, x2), dot(x3, x3));
-    m = max(m, vec4(0.));
-    m *= m;
-    return 42. * dot(m * m, vec4(dot(p0, x0), dot(p1, x1), dot(p2, x2), dot(p3, x3)));
+fn simplex_noise_3d_flow(v: vec3<f32>, rotation: f32) -> f32 {
+    return simplex::simplex_noise_3d_flow(v, rotation);
 }
 
-// higher level concepts:
+fn simplex_noise_4d(v: vec4<f32>) -> f32 {
+    return simplex::simplex_noise_4d(v);
+}
 
-/// Fractional brownian motion (fbm) based on 2d simplex noise
-fn fbm_simplex_2d(pos: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
-    var sum = 0.;
-    var amplitude = 1.;
-    var frequency = 1.;
+fn fbm_simplex_1d(pos: f32, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    return fbm::fbm_simplex_1d(pos, octaves, lacunarity, gain);
+}
 
-    for (var i = 0; i < octaves; i+= 1) {
-        sum += simplex_noise_2d(pos * frequency) * amplitude;
-        amplitude *= gain;
-        frequency *= lacunarity;
-    }
+fn fbm_simplex_1d_seeded(pos: f32, octaves: i32, lacunarity: f32, gain: f32, seed: f32) -> f32 {
+    return fbm::fbm_simplex_1d_seeded(pos, octaves, lacunarity, gain, seed);
+}
 
-    return sum;
+fn fbm_simplex_2d(pos: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    return fbm::fbm_simplex_2d(pos, octaves, lacunarity, gain);
 }
 
-/// Fractional brownian motion (fbm) based on seeded 2d simplex noise
 fn fbm_simplex_2d_seeded(pos: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: f32) -> f32 {
-    var sum = 0.;
-    var amplitude = 1.;
-    var frequency = 1.;
-
-    for (var i = 0; i < octaves; i+= 1) {
-        sum += simplex_noise_2d_seeded(pos * frequency, seed) * amplitude;
-        amplitude *= gain;
-        frequency *= lacunarity;
-    }
-
-    return sum;
+    return fbm::fbm_simplex_2d_seeded(pos, octaves, lacunarity, gain, seed);
 }
 
-const max_warp_iterations = 4; // Warping has diminishing returns due to the falloff param, so we don't need many iterations. Faloff makes it look more natural.
+fn fbm_simplex_3d(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    return fbm::fbm_simplex_3d(pos, octaves, lacunarity, gain);
+}
 
-struct WarpResult {
-    noise_value: f32,
-    // The history of warped coordinates, where positions[0] is the last iteration, positions[1] is second to last, etc.
-    // Can be useful for mixing colors.
-    positions: array<vec2f, max_warp_iterations>
+fn fbm_simplex_3d_seeded(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: vec3<f32>) -> f32 {
+    return fbm::fbm_simplex_3d_seeded(pos, octaves, lacunarity, gain, seed);
 }
 
-/// A technique that distorts the position before feeding it to the noise
-/// inspired by https://iquilezles.org/articles/warp/
-fn fbm_simplex_2d_warp_seeded(pos_initial: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: f32, warp_iterations: i32, warp_scale: vec2<f32>, falloff: f32) -> WarpResult {
-    var scale = 1.0;
-    var positions = array<vec2f, max_warp_iterations>();
-    var pos = pos_initial;
+fn fbm_simplex_4d(pos: vec4<f32>, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    return fbm::fbm_simplex_4d(pos, octaves, lacunarity, gain);
+}
 
-    // Clamp warp_iterations to max_warp_iterations
-    let iterations = min(warp_iterations, i32(max_warp_iterations));
+fn fbm_simplex_2d_flow(pos: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32, rotation: f32, rotation_gain: f32) -> f32 {
+    return fbm::fbm_simplex_2d_flow(pos, octaves, lacunarity, gain, rotation, rotation_gain);
+}
 
-    for (var i: i32 = 0; i < iterations; i++) {
-        pos.x += scale * warp_scale.x * fbm_simplex_2d_seeded(pos, octaves, lacunarity, gain, seed);
-        pos.y += scale * warp_scale.y * fbm_simplex_2d_seeded(pos, octaves, lacunarity, gain, seed);
+fn fbm_simplex_3d_flow(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32, rotation: f32, rotation_gain: f32) -> f32 {
+    return fbm::fbm_simplex_3d_flow(pos, octaves, lacunarity, gain, rotation, rotation_gain);
+}
 
-        // Store positions in reverse order for easier user access (last iteration at index 0)
-        let index = max_warp_iterations - 1 - i;
-        if (index < max_warp_iterations) {
-            positions[index] = pos;
-        }
+// Type aliases can't be imported, so the warp structs are redeclared here and
+// the results copied over.
 
-        scale *= falloff;
-    }
+const max_warp_iterations: i32 = warp::max_warp_iterations;
 
-    let noise_value = fbm_simplex_2d_seeded(positions[0], octaves, lacunarity, gain, seed);
+struct WarpResult {
+    noise_value: f32,
+    positions: array<vec2f, max_warp_iterations>
+}
 
-    return WarpResult(noise_value, positions);
+struct WarpResult3d {
+    noise_value: f32,
+    positions: array<vec3f, max_warp_iterations>
 }
 
-/// Fractional brownian motion (fbm) based on 3d simplex noise
-fn fbm_simplex_3d(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
-    var sum = 0.;
-    var amplitude = 1.;
-    var frequency = 1.;
+struct DomainWarp2d {
+    octaves: i32,
+    lacunarity: f32,
+    gain: f32,
+    seed: f32,
+    iterations: i32,
+    scale: vec2<f32>,
+    falloff: f32,
+    axis_seed_offsets: vec2<f32>,
+    frequency_step: f32,
+    octave_step: i32,
+}
 
-    for (var i = 0; i < octaves; i+= 1) {
-        sum += simplex_noise_3d(pos * frequency) * amplitude;
-        amplitude *= gain;
-        frequency *= lacunarity;
-    }
+fn fbm_simplex_2d_warp_seeded(pos_initial: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: f32, warp_iterations: i32, warp_scale: vec2<f32>, falloff: f32) -> WarpResult {
+    let result = warp::fbm_simplex_2d_warp_seeded(pos_initial, octaves, lacunarity, gain, seed, warp_iterations, warp_scale, falloff);
+    return WarpResult(result.noise_value, result.positions);
+}
 
-    return sum;
+fn fbm_simplex_2d_warp(pos_initial: vec2<f32>, settings: DomainWarp2d) -> WarpResult {
+    let result = warp::fbm_simplex_2d_warp(pos_initial, warp::DomainWarp2d(
+        settings.octaves,
+        settings.lacunarity,
+        settings.gain,
+        settings.seed,
+        settings.iterations,
+        settings.scale,
+        settings.falloff,
+        settings.axis_seed_offsets,
+        settings.frequency_step,
+        settings.octave_step,
+    ));
+    return WarpResult(result.noise_value, result.positions);
 }
 
-/// Fractional brownian motion (fbm) based on seeded 3d simplex noise
-fn fbm_simplex_3d_seeded(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: vec3<f32>) -> f32 {
-    var sum = 0.;
-    var amplitude = 1.;
-    var frequency = 1.;
+fn warp_2d(pos_initial: vec2<f32>, warp_iterations: i32, warp_scale: vec2<f32>, falloff: f32) -> WarpResult {
+    let result = warp::warp_2d(pos_initial, warp_iterations, warp_scale, falloff);
+    return WarpResult(result.noise_value, result.positions);
+}
 
-    for (var i = 0; i < octaves; i+= 1) {
-        sum += simplex_noise_3d_seeded(pos * frequency, seed) * amplitude;
-        amplitude *= gain;
-        frequency *= lacunarity;
-    }
+fn fbm_simplex_3d_warp_seeded(pos_initial: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: vec3<f32>, warp_iterations: i32, warp_scale: vec3<f32>, falloff: f32) -> WarpResult3d {
+    let result = warp::fbm_simplex_3d_warp_seeded(pos_initial, octaves, lacunarity, gain, seed, warp_iterations, warp_scale, falloff);
+    return WarpResult3d(result.noise_value, result.positions);
+}
 
-    return sum;
+fn warp_3d(pos_initial: vec3<f32>, warp_iterations: i32, warp_scale: vec3<f32>, falloff: f32) -> WarpResult3d {
+    let result = warp::warp_3d(pos_initial, warp_iterations, warp_scale, falloff);
+    return WarpResult3d(result.noise_value, result.positions);
 }
 
-// MIT license, ported from https://github.com/bevy-interstellar/wgsl_noise
-/// Cellular noise, lower jitter makes the patern more regular
-/// The x component (F1) of the returned result represents the distance to the nearest feature point from the input position
-/// The y component (F2) represents the distance to the second nearest feature point from the input position
 fn worley_2d(pos: vec2<f32>, jitter: f32) -> vec2<f32> {
-    let k = 0.142857142857; // 1/7
-    let ko = 0.428571428571; // 3/7
-
-    // Determine the grid cell and fractional position
-    let pi = floor(pos);
-    let pf = fract(pos);
-
-    // Define offset indices for neighboring grid cells
-    let oi = vec3(-1.0, 0.0, 1.0);
-    let of_ = vec3(-0.5, 0.5, 1.5);
-
-    // Permute the grid cell indices to get unique values for each cell
-    let px = permute_3_(pi.x + oi);
-    var p = permute_3_(px.x + pi.y + oi);  // p11, p12, p13
-
-    var ox = fract(p * k) - ko;
-    var oy = (floor(p * k) % 7.0) * k - ko;
-    var dx = pf.x + 0.5 + jitter * ox;
-    var dy = pf.y - of_ + jitter * oy;
-    var d1 = dx * dx + dy * dy;  // d11, d12, d13, squared
-
-    p = permute_3_(px.y + pi.y + oi); // p21, p22, p23
-    ox = fract(p * k) - ko;
-    oy = (floor(p * k) % 7.0) * k - ko;
-    dx = pf.x - 0.5 + jitter * ox;
-    dy = pf.y - of_ + jitter * oy;
-    var d2 = dx * dx + dy * dy; // d21, d22, d23, squared
-
-    p = permute_3_(px.z + pi.y + oi); // p31, p32, p33
-    ox = fract(p * k) - ko;
-    oy = (floor(p * k) % 7.0) * k - ko;
-    dx = pf.x - 1.5 + jitter * ox;
-    dy = pf.y - of_ + jitter * oy;
-    let d3 = dx * dx + dy * dy; // d31, d32, d33, squared
-
-    // Sort out the two smallest distances (F1, F2)
-    let d1a = min(d1, d2);
-    d2 = max(d1, d2);               // Swap to keep candidates for F2
-    d2 = min(d2, d3);               // neither F1 nor F2 are now in d3
-    d1 = min(d1a, d2);              // F1 is now in d1
-    d2 = max(d1a, d2);              // Swap to keep candidates for F2
-
-    if d1.x > d1.y {                // Swap if smaller
-        let tmp = d1.x;
-        d1.x = d1.y;
-        d1.y = tmp;
-    }
-    if d1.x > d1.z {                // F1 is in d1.x
-        let tmp = d1.x;
-        d1.x = d1.z;
-        d1.z = tmp;
-    }
-
-    d1.y = min(d1.y, d2.y);         // F2 is now not in d2.yz
-    d1.z = min(d1.z, d2.z);
-    d1.y = min(d1.y, d1.z);         // nor in  d1.z
-    d1.y = min(d1.y, d2.x);         // F2 is in d1.y, we're done.
-    return sqrt(d1.xy);
+    return worley::worley_2d(pos, jitter);
 }
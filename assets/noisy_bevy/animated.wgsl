@@ -0,0 +1,30 @@
+// Noise fields evolving over time, see `AnimatedNoise` in the noisy_bevy crate
+
+#define_import_path noisy_bevy::animated
+
+#import noisy_bevy::fbm::{fbm_simplex_3d_seeded, fbm_simplex_4d}
+
+// Must match `AnimatedNoiseUniform` in src/animated.rs
+struct AnimatedNoise {
+    frequency: f32,
+    speed: f32,
+    octaves: i32,
+    lacunarity: f32,
+    gain: f32,
+    seed: f32,
+}
+
+// Must match `SEED_OFFSET` in src/animated.rs
+const animated_seed_offset: vec3<f32> = vec3(1.731, 1.317, 1.593);
+
+// Samples the 2d field at `time` seconds, usually `globals.time`
+fn animated_noise_2d(noise: AnimatedNoise, pos: vec2<f32>, time: f32) -> f32 {
+    let p = vec3(pos * noise.frequency, time * noise.speed);
+    return fbm_simplex_3d_seeded(p, noise.octaves, noise.lacunarity, noise.gain, vec3(noise.seed));
+}
+
+// Samples the 3d field at `time` seconds, usually `globals.time`
+fn animated_noise_3d(noise: AnimatedNoise, pos: vec3<f32>, time: f32) -> f32 {
+    let p = vec4(pos * noise.frequency + noise.seed * animated_seed_offset, time * noise.speed);
+    return fbm_simplex_4d(p, noise.octaves, noise.lacunarity, noise.gain);
+}
@@ -0,0 +1,111 @@
+// Raymarched cloud layers, see `CloudLayer` in the noisy_bevy crate
+
+#define_import_path noisy_bevy::cloud
+
+#import noisy_bevy::fbm::fbm_simplex_3d_seeded
+
+// Must match `CloudLayerUniform` in src/cloud.rs
+struct CloudLayer {
+    wind: vec3<f32>,
+    bottom: f32,
+    top: f32,
+    coverage: f32,
+    density: f32,
+    frequency: f32,
+    octaves: i32,
+    lacunarity: f32,
+    gain: f32,
+    seed: f32,
+}
+
+// Extinction per world unit at a world position, 0 outside the clouds
+fn cloud_density(layer: CloudLayer, pos: vec3<f32>, time: f32) -> f32 {
+    if pos.y <= layer.bottom || pos.y >= layer.top || layer.coverage <= 0.0 {
+        return 0.0;
+    }
+    // round off the bottom and top of the clouds
+    let h = (pos.y - layer.bottom) / (layer.top - layer.bottom);
+    let shape = 4.0 * h * (1.0 - h);
+    let p = (pos - layer.wind * time) * layer.frequency;
+    let noise = fbm_simplex_3d_seeded(p, layer.octaves, layer.lacunarity, layer.gain, vec3(layer.seed)) * 0.5 + 0.5;
+    let cloud = (noise * shape + layer.coverage - 1.0) / layer.coverage;
+    return clamp(cloud, 0.0, 1.0) * layer.density;
+}
+
+// Distances along the ray where it enters and leaves the layer, x >= y if it
+// misses
+fn cloud_ray_range(layer: CloudLayer, origin: vec3<f32>, direction: vec3<f32>, max_distance: f32) -> vec2<f32> {
+    if direction.y == 0.0 {
+        if origin.y <= layer.bottom || origin.y >= layer.top {
+            return vec2(1.0, 0.0);
+        }
+        return vec2(0.0, max_distance);
+    }
+    let a = (layer.bottom - origin.y) / direction.y;
+    let b = (layer.top - origin.y) / direction.y;
+    return vec2(max(0.0, min(a, b)), min(max_distance, max(a, b)));
+}
+
+// How much light gets through the clouds between two world positions, sampling
+// `steps` points like `CloudLayer::transmittance`
+fn cloud_transmittance(layer: CloudLayer, start: vec3<f32>, end: vec3<f32>, time: f32, steps: u32) -> f32 {
+    let length = distance(start, end);
+    if length == 0.0 {
+        return 1.0;
+    }
+    let direction = (end - start) / length;
+    let range = cloud_ray_range(layer, start, direction, length);
+    if range.x >= range.y {
+        return 1.0;
+    }
+    let n = max(steps, 1u);
+    let step = (range.y - range.x) / f32(n);
+    var optical_depth = 0.0;
+    for (var i = 0u; i < n; i++) {
+        let pos = start + direction * (range.x + step * (f32(i) + 0.5));
+        optical_depth += cloud_density(layer, pos, time) * step;
+    }
+    return exp(-optical_depth);
+}
+
+// Marches a ray through the layer, lighting the clouds with `light` from a sun
+// shining along `sun_direction` and filling the shadows with `ambient`.
+// Returns the premultiplied cloud color and its opacity.
+fn march_clouds(
+    layer: CloudLayer,
+    origin: vec3<f32>,
+    direction: vec3<f32>,
+    max_distance: f32,
+    time: f32,
+    steps: u32,
+    sun_direction: vec3<f32>,
+    light: vec3<f32>,
+    ambient: vec3<f32>,
+) -> vec4<f32> {
+    let range = cloud_ray_range(layer, origin, direction, max_distance);
+    if range.x >= range.y {
+        return vec4(0.0);
+    }
+    let n = max(steps, 1u);
+    let step = (range.y - range.x) / f32(n);
+    let to_sun = -normalize(sun_direction);
+    let thickness = layer.top - layer.bottom;
+    var color = vec3(0.0);
+    var transmittance = 1.0;
+    for (var i = 0u; i < n; i++) {
+        let pos = origin + direction * (range.x + step * (f32(i) + 0.5));
+        let density = cloud_density(layer, pos, time);
+        if density <= 0.0 {
+            continue;
+        }
+        // a few samples towards the sun for self shadowing
+        let sun = cloud_transmittance(layer, pos, pos + to_sun * thickness, time, 4u);
+        let absorbed = 1.0 - exp(-density * step);
+        color += transmittance * absorbed * (light * sun + ambient);
+        transmittance *= 1.0 - absorbed;
+        if transmittance < 0.01 {
+            break;
+        }
+    }
+    return vec4(color, 1.0 - transmittance);
+}
@@ -0,0 +1,41 @@
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_view_bindings::{globals, view},
+}
+#import noisy_bevy::cloud::{CloudLayer, march_clouds}
+
+// Must match `CloudMaterialUniform` in src/cloud_material.rs
+struct CloudMaterial {
+    layer: CloudLayer,
+    zenith: vec4<f32>,
+    horizon: vec4<f32>,
+    sun_direction: vec3<f32>,
+    steps: u32,
+    sun_color: vec3<f32>,
+    max_distance: f32,
+    ambient: vec3<f32>,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: CloudMaterial;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    let origin = view.world_position;
+    let direction = normalize(mesh.world_position.xyz - origin);
+    let sky = mix(material.horizon, material.zenith, sqrt(clamp(direction.y, 0.0, 1.0)));
+    let clouds = march_clouds(
+        material.layer,
+        origin,
+        direction,
+        material.max_distance,
+        globals.time,
+        material.steps,
+        material.sun_direction,
+        material.sun_color,
+        material.ambient,
+    );
+    // premultiplied clouds over the sky
+    let alpha = clouds.a + sky.a * (1.0 - clouds.a);
+    let color = clouds.rgb + sky.rgb * sky.a * (1.0 - clouds.a);
+    return vec4(color / max(alpha, 1e-4), alpha);
+}
@@ -0,0 +1,156 @@
+//  MIT License. © Ian McEwan, Stefan Gustavson, Munrocket, Johan Helsing
+
+#define_import_path noisy_bevy::fbm
+
+#import noisy_bevy::simplex::{
+    simplex_noise_1d,
+    simplex_noise_1d_seeded,
+    simplex_noise_2d,
+    simplex_noise_2d_seeded,
+    simplex_noise_2d_flow,
+    simplex_noise_3d,
+    simplex_noise_3d_seeded,
+    simplex_noise_3d_flow,
+    simplex_noise_4d,
+}
+
+/// Fractional brownian motion (fbm) based on 1d simplex noise
+fn fbm_simplex_1d(pos: f32, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    var sum = 0.;
+    var amplitude = 1.;
+    var frequency = 1.;
+
+    for (var i = 0; i < octaves; i+= 1) {
+        sum += simplex_noise_1d(pos * frequency) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+    }
+
+    return sum;
+}
+
+/// Fractional brownian motion (fbm) based on seeded 1d simplex noise
+fn fbm_simplex_1d_seeded(pos: f32, octaves: i32, lacunarity: f32, gain: f32, seed: f32) -> f32 {
+    var sum = 0.;
+    var amplitude = 1.;
+    var frequency = 1.;
+
+    for (var i = 0; i < octaves; i+= 1) {
+        sum += simplex_noise_1d_seeded(pos * frequency, seed) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+    }
+
+    return sum;
+}
+
+/// Fractional brownian motion (fbm) based on 2d simplex noise
+fn fbm_simplex_2d(pos: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    var sum = 0.;
+    var amplitude = 1.;
+    var frequency = 1.;
+
+    for (var i = 0; i < octaves; i+= 1) {
+        sum += simplex_noise_2d(pos * frequency) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+    }
+
+    return sum;
+}
+
+/// Fractional brownian motion (fbm) based on seeded 2d simplex noise
+fn fbm_simplex_2d_seeded(pos: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: f32) -> f32 {
+    var sum = 0.;
+    var amplitude = 1.;
+    var frequency = 1.;
+
+    for (var i = 0; i < octaves; i+= 1) {
+        sum += simplex_noise_2d_seeded(pos * frequency, seed) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+    }
+
+    return sum;
+}
+
+/// Fractional brownian motion (fbm) based on 3d simplex noise
+fn fbm_simplex_3d(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    var sum = 0.;
+    var amplitude = 1.;
+    var frequency = 1.;
+
+    for (var i = 0; i < octaves; i+= 1) {
+        sum += simplex_noise_3d(pos * frequency) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+    }
+
+    return sum;
+}
+
+/// Fractional brownian motion (fbm) based on seeded 3d simplex noise
+fn fbm_simplex_3d_seeded(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: vec3<f32>) -> f32 {
+    var sum = 0.;
+    var amplitude = 1.;
+    var frequency = 1.;
+
+    for (var i = 0; i < octaves; i+= 1) {
+        sum += simplex_noise_3d_seeded(pos * frequency, seed) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+    }
+
+    return sum;
+}
+
+/// Fractional brownian motion (fbm) based on 4d simplex noise
+fn fbm_simplex_4d(pos: vec4<f32>, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    var sum = 0.;
+    var amplitude = 1.;
+    var frequency = 1.;
+
+    for (var i = 0; i < octaves; i+= 1) {
+        sum += simplex_noise_4d(pos * frequency) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+    }
+
+    return sum;
+}
+
+/// Fractional brownian motion (fbm) based on 2d flow noise, each octave
+/// rotated `rotation_gain` times as much as the one before it
+fn fbm_simplex_2d_flow(pos: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32, rotation: f32, rotation_gain: f32) -> f32 {
+    var sum = 0.;
+    var amplitude = 1.;
+    var frequency = 1.;
+    var angle = rotation;
+
+    for (var i = 0; i < octaves; i+= 1) {
+        sum += simplex_noise_2d_flow(pos * frequency, angle) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+        angle *= rotation_gain;
+    }
+
+    return sum;
+}
+
+/// Fractional brownian motion (fbm) based on 3d flow noise, each octave
+/// rotated `rotation_gain` times as much as the one before it
+fn fbm_simplex_3d_flow(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32, rotation: f32, rotation_gain: f32) -> f32 {
+    var sum = 0.;
+    var amplitude = 1.;
+    var frequency = 1.;
+    var angle = rotation;
+
+    for (var i = 0; i < octaves; i+= 1) {
+        sum += simplex_noise_3d_flow(pos * frequency, angle) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+        angle *= rotation_gain;
+    }
+
+    return sum;
+}
@@ -0,0 +1,84 @@
+// Sparse convolution Gabor noise, see `GaborNoise` in the noisy_bevy crate
+
+#define_import_path noisy_bevy::gabor
+
+#import noisy_bevy::hash::{hash_u32_, hash_cell_2d_}
+
+// Must match `GaborNoiseUniform` in src/gabor.rs
+struct GaborNoise {
+    orientation: f32,
+    frequency: f32,
+    bandwidth: f32,
+    impulse_density: f32,
+    seed: u32,
+}
+
+const gabor_pi: f32 = 3.14159265358979;
+// Must match `MIN_GABOR_BANDWIDTH` and `MAX_GABOR_IMPULSE_DENSITY` in
+// src/gabor.rs
+const gabor_min_bandwidth: f32 = 0.001;
+const gabor_max_impulse_density: f32 = 64.;
+
+// Next random number in [0, 1) from the generator state, like `Rng` in the
+// noisy_bevy crate
+fn gabor_next_f32(state: ptr<function, u32>) -> f32 {
+    *state = *state * 747796405u + 2891336453u;
+    return f32(hash_u32_(*state) >> 8u) / 16777216.;
+}
+
+// Sum of the kernels of the impulses in `cell`, `pos` being relative to the
+// corner of the cell
+fn gabor_cell(noise: GaborNoise, cell: vec2<i32>, pos: vec2<f32>, direction: vec2<f32>) -> f32 {
+    let radius = 1. / noise.bandwidth;
+    var state = hash_u32_(hash_cell_2d_(cell, noise.seed));
+    // round randomly, so fractional densities average out
+    let density = noise.impulse_density;
+    let extra = gabor_next_f32(&state) < fract(density);
+    let impulses = u32(density) + u32(extra);
+
+    var sum = 0.;
+    for (var i = 0u; i < impulses; i++) {
+        let x = gabor_next_f32(&state);
+        let y = gabor_next_f32(&state);
+        let impulse = vec2(x, y) * radius;
+        let weight = select(1., -1., gabor_next_f32(&state) < 0.5);
+        let d = pos - impulse;
+        let r2 = dot(d, d);
+        if r2 < radius * radius {
+            let envelope = exp(-gabor_pi * noise.bandwidth * noise.bandwidth * r2);
+            let wave = cos(2. * gabor_pi * noise.frequency * dot(d, direction));
+            sum += weight * envelope * wave;
+        }
+    }
+    return sum;
+}
+
+// Samples the noise, roughly in the range -1..1
+fn gabor_noise_2d(settings: GaborNoise, pos: vec2<f32>) -> f32 {
+    var noise = settings;
+    noise.bandwidth = max(noise.bandwidth, gabor_min_bandwidth);
+    noise.impulse_density = clamp(noise.impulse_density, 0., gabor_max_impulse_density);
+
+    let radius = 1. / noise.bandwidth;
+    let direction = vec2(cos(noise.orientation), sin(noise.orientation));
+    let cell_pos = pos * noise.bandwidth;
+    let cell_floor = floor(cell_pos);
+    let offset = cell_pos - cell_floor;
+    let cell = vec2<i32>(cell_floor);
+
+    var sum = 0.;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let neighbor = vec2(x, y);
+            sum += gabor_cell(noise, cell + neighbor, (offset - vec2<f32>(neighbor)) * radius, direction);
+        }
+    }
+
+    // three standard deviations, see section 3.3 of Lagae et al.
+    let ratio = noise.frequency / noise.bandwidth;
+    let variance = noise.impulse_density / 4. * (1. + exp(-2. * gabor_pi * ratio * ratio));
+    if variance <= 0. {
+        return 0.;
+    }
+    return sum / (3. * sqrt(variance));
+}
@@ -0,0 +1,47 @@
+// Color gradients for mapping noise values to colors, see NoiseGradient
+
+#define_import_path noisy_bevy::gradient
+
+const MAX_GRADIENT_STOPS: u32 = 8u;
+
+const GRADIENT_INTERPOLATION_LINEAR: u32 = 0u;
+const GRADIENT_INTERPOLATION_STEP: u32 = 1u;
+
+// Must match `NoiseGradientUniform` in src/gradient.rs
+struct NoiseGradient {
+    colors: array<vec4<f32>, MAX_GRADIENT_STOPS>,
+    // positions of the stops, packed four to a vector
+    positions: array<vec4<f32>, MAX_GRADIENT_STOPS / 4u>,
+    count: u32,
+    interpolation: u32,
+}
+
+fn gradient_position(gradient: NoiseGradient, i: u32) -> f32 {
+    return gradient.positions[i / 4u][i % 4u];
+}
+
+// The color at t, clamped to the first and last stop
+fn sample_gradient(gradient: NoiseGradient, t: f32) -> vec4<f32> {
+    if gradient.count == 0u {
+        return vec4(0.0);
+    }
+    if t <= gradient_position(gradient, 0u) {
+        return gradient.colors[0];
+    }
+    for (var i = 1u; i < min(gradient.count, MAX_GRADIENT_STOPS); i++) {
+        let b = gradient_position(gradient, i);
+        if t < b {
+            if gradient.interpolation == GRADIENT_INTERPOLATION_STEP {
+                return gradient.colors[i - 1u];
+            }
+            let a = gradient_position(gradient, i - 1u);
+            return mix(gradient.colors[i - 1u], gradient.colors[i], (t - a) / (b - a));
+        }
+    }
+    return gradient.colors[min(gradient.count, MAX_GRADIENT_STOPS) - 1u];
+}
+
+// Samples a gradient baked with NoiseGradient::to_image
+fn sample_gradient_texture(gradient: texture_1d<f32>, gradient_sampler: sampler, t: f32) -> vec4<f32> {
+    return textureSample(gradient, gradient_sampler, t);
+}
@@ -0,0 +1,39 @@
+//  MIT License. © Ian McEwan, Stefan Gustavson, Munrocket, Johan Helsing
+
+#define_import_path noisy_bevy::hash
+
+// Permutation polynomial hashes, (34x² + x) mod 289
+
+fn permute_1_(x: f32) -> f32 {
+    return ((x * 34. + 1.) * x) % 289.;
+}
+
+fn permute_2_(x: vec2<f32>) -> vec2<f32> {
+    return (((x * 34.) + 1.) * x) % vec2(289.);
+}
+
+fn permute_3_(x: vec3<f32>) -> vec3<f32> {
+    return (((x * 34.) + 1.) * x) % vec3(289.);
+}
+
+fn permute_4_(x: vec4<f32>) -> vec4<f32> {
+    return ((x * 34. + 1.) * x) % vec4<f32>(289.);
+}
+
+// Integer hashes, matching the ones the noisy_bevy crate uses on the CPU
+
+// Mixes the bits of `x`, see https://nullprogram.com/blog/2018/07/31/
+fn hash_u32_(x: u32) -> u32 {
+    var h = x;
+    h ^= h >> 16u;
+    h *= 0x7feb352du;
+    h ^= h >> 15u;
+    h *= 0x846ca68bu;
+    h ^= h >> 16u;
+    return h;
+}
+
+// A hash of a grid cell, different for each seed
+fn hash_cell_2d_(cell: vec2<i32>, seed: u32) -> u32 {
+    return hash_u32_(hash_u32_(hash_u32_(seed) ^ u32(cell.x)) ^ u32(cell.y));
+}
@@ -0,0 +1,83 @@
+// Sampling and coloring used by the NoiseMaterial, NoiseMaterial2d and
+// NoiseExtension fragment shaders, exposed so custom materials can reuse the
+// same settings.
+
+#define_import_path noisy_bevy::material
+
+#import noisy_bevy::simplex::{simplex_noise_2d_seeded, simplex_noise_3d_seeded}
+#import noisy_bevy::fbm::{fbm_simplex_2d_seeded, fbm_simplex_3d_seeded}
+#import noisy_bevy::warp::{fbm_simplex_2d_warp_seeded, fbm_simplex_3d_warp_seeded}
+#import noisy_bevy::worley::worley_2d
+#import noisy_bevy::gradient::{NoiseGradient, sample_gradient}
+#import noisy_bevy::projection::triplanar_weights
+
+const NOISE_TYPE_SIMPLEX: u32 = 0u;
+const NOISE_TYPE_FBM: u32 = 1u;
+const NOISE_TYPE_WORLEY: u32 = 2u;
+const NOISE_TYPE_WARP: u32 = 3u;
+
+// Must match `NoiseParamsUniform` in src/material.rs
+struct NoiseParams {
+    gradient: NoiseGradient,
+    offset: vec3<f32>,
+    frequency: f32,
+    scroll: vec3<f32>,
+    seed: f32,
+    noise_type: u32,
+    octaves: i32,
+    lacunarity: f32,
+    gain: f32,
+}
+
+// Noise value in roughly -1..1 at a world position, `time` in seconds
+fn sample_noise(params: NoiseParams, pos: vec3<f32>, time: f32) -> f32 {
+    let p = pos * params.frequency + params.offset + params.scroll * time;
+    let seed = vec3(params.seed);
+    switch params.noise_type {
+        case NOISE_TYPE_FBM: {
+            return fbm_simplex_3d_seeded(p, params.octaves, params.lacunarity, params.gain, seed);
+        }
+        case NOISE_TYPE_WORLEY: {
+            return worley_2d(p.xy + seed.xy, 1.0).x * 2.0 - 1.0;
+        }
+        case NOISE_TYPE_WARP: {
+            return fbm_simplex_3d_warp_seeded(p, params.octaves, params.lacunarity, params.gain, seed, 2, vec3(0.4), 0.5).noise_value;
+        }
+        default: {
+            return simplex_noise_3d_seeded(p, seed);
+        }
+    }
+}
+
+// 2d noise value at an already scaled and offset position
+fn sample_noise_2d(params: NoiseParams, p: vec2<f32>) -> f32 {
+    switch params.noise_type {
+        case NOISE_TYPE_FBM: {
+            return fbm_simplex_2d_seeded(p, params.octaves, params.lacunarity, params.gain, params.seed);
+        }
+        case NOISE_TYPE_WORLEY: {
+            return worley_2d(p + vec2(params.seed), 1.0).x * 2.0 - 1.0;
+        }
+        case NOISE_TYPE_WARP: {
+            return fbm_simplex_2d_warp_seeded(p, params.octaves, params.lacunarity, params.gain, params.seed, 2, vec2(0.4), 0.5).noise_value;
+        }
+        default: {
+            return simplex_noise_2d_seeded(p, params.seed);
+        }
+    }
+}
+
+// Blends 2d noise projected along the x, y and z axes by how much the unit
+// `normal` faces each axis. Higher `sharpness` gives narrower transitions.
+fn sample_noise_triplanar(params: NoiseParams, pos: vec3<f32>, normal: vec3<f32>, sharpness: f32, time: f32) -> f32 {
+    let p = pos * params.frequency + params.offset + params.scroll * time;
+    let weights = triplanar_weights(normal, sharpness);
+    return weights.x * sample_noise_2d(params, p.yz)
+        + weights.y * sample_noise_2d(params, p.zx)
+        + weights.z * sample_noise_2d(params, p.xy);
+}
+
+// Maps a noise value in -1..1 onto the color gradient
+fn noise_color(params: NoiseParams, value: f32) -> vec4<f32> {
+    return sample_gradient(params.gradient, value * 0.5 + 0.5);
+}
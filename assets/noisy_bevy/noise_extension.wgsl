@@ -0,0 +1,84 @@
+#import bevy_pbr::{
+    mesh_view_bindings::globals,
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::alpha_discard,
+}
+#import noisy_bevy::material::{NoiseParams, sample_noise_triplanar, noise_color}
+
+#ifdef PREPASS_PIPELINE
+#import bevy_pbr::{
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_deferred_functions::deferred_output,
+}
+#else
+#import bevy_pbr::{
+    forward_io::{VertexOutput, FragmentOutput},
+    pbr_functions::{apply_pbr_lighting, main_pass_post_lighting_processing},
+}
+#endif
+
+// Must match `NoiseExtensionUniform` in src/extension.rs
+struct NoiseExtension {
+    noise: NoiseParams,
+    albedo: f32,
+    roughness: f32,
+    normal: f32,
+    triplanar_sharpness: f32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(100) var<uniform> extension: NoiseExtension;
+
+fn sample(pos: vec3<f32>, normal: vec3<f32>) -> f32 {
+    return sample_noise_triplanar(extension.noise, pos, normal, extension.triplanar_sharpness, globals.time);
+}
+
+// Tilts the normal against the noise gradient, estimated with central
+// differences a small step in noise space apart
+fn bump_normal(pos: vec3<f32>, normal: vec3<f32>) -> vec3<f32> {
+    // noise without frequency is flat, and the step would be infinite
+    if extension.noise.frequency == 0.0 {
+        return normal;
+    }
+    let e = 0.05 / extension.noise.frequency;
+    let gradient = vec3(
+        sample(pos + vec3(e, 0.0, 0.0), normal) - sample(pos - vec3(e, 0.0, 0.0), normal),
+        sample(pos + vec3(0.0, e, 0.0), normal) - sample(pos - vec3(0.0, e, 0.0), normal),
+        sample(pos + vec3(0.0, 0.0, e), normal) - sample(pos - vec3(0.0, 0.0, e), normal),
+    ) / (2.0 * e * extension.noise.frequency);
+    let tangential = gradient - dot(gradient, normal) * normal;
+    return normalize(normal - extension.normal * tangential);
+}
+
+@fragment
+fn fragment(in: VertexOutput, @builtin(front_facing) is_front: bool) -> FragmentOutput {
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+
+    let pos = pbr_input.world_position.xyz;
+    let value = sample(pos, pbr_input.world_normal);
+
+    let color = noise_color(extension.noise, value);
+    let base_color = pbr_input.material.base_color;
+    pbr_input.material.base_color = vec4(mix(base_color.rgb, color.rgb, extension.albedo), base_color.a);
+
+    pbr_input.material.perceptual_roughness = clamp(
+        pbr_input.material.perceptual_roughness + value * extension.roughness,
+        0.089,
+        1.0,
+    );
+
+    if extension.normal != 0.0 {
+        pbr_input.N = bump_normal(pos, pbr_input.N);
+    }
+
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+#ifdef PREPASS_PIPELINE
+    let out = deferred_output(in, pbr_input);
+#else
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+#endif
+
+    return out;
+}
@@ -0,0 +1,14 @@
+#import bevy_sprite::{
+    mesh2d_vertex_output::VertexOutput,
+    mesh2d_view_bindings::globals,
+}
+#import noisy_bevy::material::{NoiseParams, sample_noise, noise_color}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: NoiseParams;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    // ignore z, it's only used for sorting in 2d
+    let value = sample_noise(material, vec3(mesh.world_position.xy, 0.0), globals.time);
+    return noise_color(material, value);
+}
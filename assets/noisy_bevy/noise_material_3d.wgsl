@@ -0,0 +1,13 @@
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_view_bindings::globals,
+}
+#import noisy_bevy::material::{NoiseParams, sample_noise, noise_color}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: NoiseParams;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    let value = sample_noise(material, mesh.world_position.xyz, globals.time);
+    return noise_color(material, value);
+}
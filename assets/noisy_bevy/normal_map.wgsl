@@ -0,0 +1,35 @@
+// Tangent space normals from height slopes, see `normal_map` in the noisy_bevy
+// crate
+
+#define_import_path noisy_bevy::normal_map
+
+// The tangent space normal of a surface with the given height slope, in height
+// units per texel along the image x and y axes. Uses the OpenGL convention bevy
+// expects, with +y pointing up in the image, since image rows go down.
+fn normal_from_gradient(gradient: vec2<f32>, strength: f32) -> vec3<f32> {
+    return normalize(vec3(-gradient.x * strength, gradient.y * strength, 1.0));
+}
+
+// Packs a unit normal into a color for an rgba8unorm normal map
+fn encode_normal(normal: vec3<f32>) -> vec4<f32> {
+    return vec4(normal * 0.5 + 0.5, 1.0);
+}
+
+// The height slope at a texel of a height texture, estimated with central
+// differences. Edge texels clamp to the texture, or wrap around when tileable.
+fn height_gradient(heights: texture_2d<f32>, texel: vec2<i32>, tileable: bool) -> vec2<f32> {
+    let size = vec2<i32>(textureDimensions(heights));
+    let left = height_at(heights, texel - vec2(1, 0), size, tileable);
+    let right = height_at(heights, texel + vec2(1, 0), size, tileable);
+    let up = height_at(heights, texel - vec2(0, 1), size, tileable);
+    let down = height_at(heights, texel + vec2(0, 1), size, tileable);
+    return vec2(right - left, down - up) * 0.5;
+}
+
+fn height_at(heights: texture_2d<f32>, texel: vec2<i32>, size: vec2<i32>, tileable: bool) -> f32 {
+    var wrapped = clamp(texel, vec2(0), size - 1);
+    if tileable {
+        wrapped = ((texel % size) + size) % size;
+    }
+    return textureLoad(heights, wrapped, 0).r;
+}
@@ -0,0 +1,25 @@
+// Bakes the red channel of a height texture into a normal map on the GPU, see
+// `NORMAL_MAP_COMPUTE_SHADER_HANDLE` in the noisy_bevy crate
+
+#import noisy_bevy::normal_map::{encode_normal, height_gradient, normal_from_gradient}
+
+// Must match `NormalMapComputeSettings` in src/normal_map.rs
+struct NormalMapComputeSettings {
+    strength: f32,
+    tileable: u32,
+}
+
+@group(0) @binding(0) var heights: texture_2d<f32>;
+@group(0) @binding(1) var normals: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(2) var<uniform> settings: NormalMapComputeSettings;
+
+@compute @workgroup_size(8, 8, 1)
+fn bake_normal_map(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = textureDimensions(normals);
+    if any(id.xy >= size) {
+        return;
+    }
+    let texel = vec2<i32>(id.xy);
+    let gradient = height_gradient(heights, texel, settings.tileable != 0u);
+    textureStore(normals, texel, encode_normal(normal_from_gradient(gradient, settings.strength)));
+}
@@ -0,0 +1,55 @@
+// Triplanar and spherical projections, for meshes without good UVs and for
+// planets
+
+#define_import_path noisy_bevy::projection
+
+#import noisy_bevy::simplex::{simplex_noise_2d, simplex_noise_3d}
+#import noisy_bevy::fbm::{fbm_simplex_2d, fbm_simplex_3d}
+
+// How much each of the x, y and z projections contribute to triplanar sampling
+// at a surface with the given unit normal, summing to 1. Higher sharpness gives
+// narrower transitions between the projections. A zero normal weights all
+// projections equally.
+fn triplanar_weights(normal: vec3<f32>, sharpness: f32) -> vec3<f32> {
+    let weights = pow(abs(normal), vec3(sharpness));
+    let sum = weights.x + weights.y + weights.z;
+    if sum == 0. {
+        return vec3(1. / 3.);
+    }
+    return weights / sum;
+}
+
+// Triplanar simplex_noise_2d, sampling the yz, zx and xy planes
+fn triplanar_simplex_2d(pos: vec3<f32>, normal: vec3<f32>, sharpness: f32) -> f32 {
+    let weights = triplanar_weights(normal, sharpness);
+    return weights.x * simplex_noise_2d(pos.yz)
+        + weights.y * simplex_noise_2d(pos.zx)
+        + weights.z * simplex_noise_2d(pos.xy);
+}
+
+// Triplanar fbm_simplex_2d, sampling the yz, zx and xy planes
+fn triplanar_fbm_2d(pos: vec3<f32>, normal: vec3<f32>, sharpness: f32, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    let weights = triplanar_weights(normal, sharpness);
+    return weights.x * fbm_simplex_2d(pos.yz, octaves, lacunarity, gain)
+        + weights.y * fbm_simplex_2d(pos.zx, octaves, lacunarity, gain)
+        + weights.z * fbm_simplex_2d(pos.xy, octaves, lacunarity, gain);
+}
+
+// The point on a sphere at a latitude (x) and longitude (y) in radians.
+// Latitude goes from -π/2 at -y to π/2 at +y, longitude 0 is +x and π/2 is +z.
+fn sphere_position(lat_long: vec2<f32>, radius: f32) -> vec3<f32> {
+    let lat_cos = cos(lat_long.x);
+    return radius * vec3(lat_cos * cos(lat_long.y), sin(lat_long.x), lat_cos * sin(lat_long.y));
+}
+
+// simplex_noise_3d on the surface of a sphere, without a seam where the
+// longitude wraps around
+fn sphere_simplex_3d(lat_long: vec2<f32>, radius: f32) -> f32 {
+    return simplex_noise_3d(sphere_position(lat_long, radius));
+}
+
+// fbm_simplex_3d on the surface of a sphere, without a seam where the
+// longitude wraps around
+fn sphere_fbm_3d(lat_long: vec2<f32>, radius: f32, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    return fbm_simplex_3d(sphere_position(lat_long, radius), octaves, lacunarity, gain);
+}
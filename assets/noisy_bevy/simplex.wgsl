@@ -0,0 +1,471 @@
+//  MIT License. © Ian McEwan, Stefan Gustavson, Munrocket, Johan Helsing
+
+#define_import_path noisy_bevy::simplex
+
+#import noisy_bevy::hash::{permute_1_, permute_2_, permute_3_, permute_4_}
+
+// Scales gradients with squared length r to unit length. Uses a Taylor
+// approximation of inverseSqrt unless NOISY_HIGH_QUALITY_GRADIENTS is defined.
+fn gradient_norm_3_(r: vec3<f32>) -> vec3<f32> {
+#ifdef NOISY_HIGH_QUALITY_GRADIENTS
+    return inverseSqrt(r);
+#else
+    return 1.79284291400159 - 0.85373472095314 * r;
+#endif
+}
+
+// Exact normalization gives longer gradients, so the 2d output is scaled down
+// to stay within -1..1.
+#ifdef NOISY_HIGH_QUALITY_GRADIENTS
+const simplex_2d_scale: f32 = 99.;
+#else
+const simplex_2d_scale: f32 = 130.;
+#endif
+
+fn step_3(edge: vec3<f32>, x: vec3<f32>) -> vec3<f32> {
+    let b = vec3(edge.x < x.x, edge.y <= x.y, edge.z <= x.z);
+    return select(vec3(0.), vec3(1.), b);
+}
+
+fn simplex_noise_1d(v: f32) -> f32 {
+    // first corner
+    var i = floor(v);
+    let x0 = v - i;
+    let x1 = x0 - 1.;
+
+    // permutations
+    i = i % 289.;
+    let p = permute_2_(i + vec2(0., 1.));
+
+    // gradients: 41 points uniformly over a line
+    let g = 2. * fract(p * 0.024390243902439) - 1.;
+
+    var t = max(1. - vec2(x0 * x0, x1 * x1), vec2(0.));
+    t *= t;
+    t *= t;
+
+    // compute final noise value at P
+    return 3.16 * dot(t, g * vec2(x0, x1));
+}
+
+fn simplex_noise_1d_seeded(v: f32, seed: f32) -> f32 {
+    // first corner
+    var i = floor(v);
+    let x0 = v - i;
+    let x1 = x0 - 1.;
+
+    // permutations
+    i = i % 289.;
+    var p = permute_2_(i + vec2(0., 1.));
+    p = permute_2_(p + vec2(seed));
+
+    // gradients: 41 points uniformly over a line
+    let g = 2. * fract(p * 0.024390243902439) - 1.;
+
+    var t = max(1. - vec2(x0 * x0, x1 * x1), vec2(0.));
+    t *= t;
+    t *= t;
+
+    // compute final noise value at P
+    return 3.16 * dot(t, g * vec2(x0, x1));
+}
+
+fn simplex_noise_2d(v: vec2<f32>) -> f32 {
+    let C = vec4(
+        0.211324865405187, // (3.0 - sqrt(3.0)) / 6.0
+        0.366025403784439, // 0.5 * (sqrt(3.0) - 1.0)
+        -0.577350269189626, // -1.0 + 2.0 * C.x
+        0.024390243902439 // 1.0 / 41.0
+    );
+
+    // first corner
+    var i = floor(v + dot(v, C.yy));
+    let x0 = v - i + dot(i, C.xx);
+
+    // other corners
+    var i1 = select(vec2(0., 1.), vec2(1., 0.), x0.x > x0.y);
+    var x12 = x0.xyxy + C.xxzz - vec4(i1, 0., 0.);
+
+    // permutations
+    i = i % vec2(289.);
+
+    let p = permute_3_(permute_3_(i.y + vec3(0., i1.y, 1.)) + i.x + vec3(0., i1.x, 1.));
+    var m = max(0.5 - vec3(dot(x0, x0), dot(x12.xy, x12.xy), dot(x12.zw, x12.zw)), vec3(0.));
+    m *= m;
+    m *= m;
+
+    // gradients: 41 points uniformly over a line, mapped onto a diamond
+    // the ring size, 17*17 = 289, is close to a multiple of 41 (41*7 = 287)
+    let x = 2. * fract(p * C.www) - 1.;
+    let h = abs(x) - 0.5;
+    let ox = floor(x + 0.5);
+    let a0 = x - ox;
+
+    // normalize gradients implicitly by scaling m
+    // approximation of: m *= inversesqrt(a0 * a0 + h * h);
+    m = m * gradient_norm_3_(a0 * a0 + h * h);
+
+    // compute final noise value at P
+    let g = vec3(a0.x * x0.x + h.x * x0.y, a0.yz * x12.xz + h.yz * x12.yw);
+    return simplex_2d_scale * dot(m, g);
+}
+
+fn simplex_noise_2d_seeded(v: vec2<f32>, seed: f32) -> f32 {
+    let C = vec4(
+        0.211324865405187, // (3.0 - sqrt(3.0)) / 6.0
+        0.366025403784439, // 0.5 * (sqrt(3.0) - 1.0)
+        -0.577350269189626, // -1.0 + 2.0 * C.x
+        0.024390243902439 // 1.0 / 41.0
+    );
+
+    // first corner
+    var i = floor(v + dot(v, C.yy));
+    let x0 = v - i + dot(i, C.xx);
+
+    // other corners
+    var i1 = select(vec2(0., 1.), vec2(1., 0.), x0.x > x0.y);
+    var x12 = x0.xyxy + C.xxzz - vec4(i1, 0., 0.);
+
+    // permutations
+    i = i % vec2(289.);
+
+    var p = permute_3_(permute_3_(i.y + vec3(0., i1.y, 1.)) + i.x + vec3(0., i1.x, 1.));
+    p = permute_3_(p + vec3(seed));
+    var m = max(0.5 - vec3(dot(x0, x0), dot(x12.xy, x12.xy), dot(x12.zw, x12.zw)), vec3(0.));
+    m *= m;
+    m *= m;
+
+    // gradients: 41 points uniformly over a line, mapped onto a diamond
+    // the ring size, 17*17 = 289, is close to a multiple of 41 (41*7 = 287)
+    let x = 2. * fract(p * C.www) - 1.;
+    let h = abs(x) - 0.5;
+    let ox = floor(x + 0.5);
+    let a0 = x - ox;
+
+    // normalize gradients implicitly by scaling m
+    // approximation of: m *= inversesqrt(a0 * a0 + h * h);
+    m = m * gradient_norm_3_(a0 * a0 + h * h);
+
+    // compute final noise value at P
+    let g = vec3(a0.x * x0.x + h.x * x0.y, a0.yz * x12.xz + h.yz * x12.yw);
+    return simplex_2d_scale * dot(m, g);
+}
+
+fn gradient_norm_4_(r: vec4<f32>) -> vec4<f32> {
+#ifdef NOISY_HIGH_QUALITY_GRADIENTS
+    return inverseSqrt(r);
+#else
+    return 1.79284291400159 - 0.85373472095314 * r;
+#endif
+}
+
+fn simplex_noise_3d(v: vec3<f32>) -> f32 {
+    let C = vec2(1. / 6., 1. / 3.);
+    let D = vec4(0., 0.5, 1., 2.);
+
+    // first corner
+    var i = floor(v + dot(v, C.yyy));
+    let x0 = v - i + dot(i, C.xxx);
+
+    // other corners
+    let g = step_3(x0.yzx, x0.xyz);
+    let l = 1. - g;
+    let i1 = min(g.xyz, l.zxy);
+    let i2 = max(g.xyz, l.zxy);
+
+    // x0 = x0 - 0. + 0. * C
+    let x1 = x0 - i1 + 1. * C.xxx;
+    let x2 = x0 - i2 + 2. * C.xxx;
+    let x3 = x0 - 1. + 3. * C.xxx;
+
+    // permutations
+    i = i % vec3(289.);
+    let p = permute_4_(permute_4_(permute_4_(
+        i.z + vec4(0., i1.z, i2.z, 1.)) +
+        i.y + vec4(0., i1.y, i2.y, 1.)) +
+        i.x + vec4(0., i1.x, i2.x, 1.)
+    );
+
+    // gradients (NxN points uniformly over a square, mapped onto an octahedron)
+    let n_ = 1. / 7.; // N=7
+    let ns = n_ * D.wyz - D.xzx;
+
+    let j = p - 49. * floor(p * ns.z * ns.z); // mod(p, N*N)
+
+    let x_ = floor(j * ns.z);
+    let y_ = floor(j - 7. * x_); // mod(j, N)
+
+    let x = x_ * ns.x + ns.yyyy;
+    let y = y_ * ns.x + ns.yyyy;
+    let h = 1. - abs(x) - abs(y);
+
+    let b0 = vec4(x.xy, y.xy);
+    let b1 = vec4(x.zw, y.zw);
+
+    let s0 = floor(b0) * 2. + 1.;
+    let s1 = floor(b1) * 2. + 1.;
+    let sh = -step(h, vec4(0.));
+
+    let a0 = b0.xzyw + s0.xzyw * sh.xxyy;
+    let a1 = b1.xzyw + s1.xzyw * sh.zzww;
+
+    var p0 = vec3(a0.xy, h.x);
+    var p1 = vec3(a0.zw, h.y);
+    var p2 = vec3(a1.xy, h.z);
+    var p3 = vec3(a1.zw, h.w);
+
+    // normalize gradients
+    let norm = gradient_norm_4_(vec4(dot(p0, p0), dot(p1, p1), dot(p2, p2), dot(p3, p3)));
+    p0 = p0 * norm.x;
+    p1 = p1 * norm.y;
+    p2 = p2 * norm.z;
+    p3 = p3 * norm.w;
+
+    // mix final noise value
+    var m = 0.5 - vec4(dot(x0, x0), dot(x1, x1), dot(x2, x2), dot(x3, x3));
+    m = max(m, vec4(0.));
+    m *= m;
+    return 105. * dot(m * m, vec4(dot(p0, x0), dot(p1, x1), dot(p2, x2), dot(p3, x3)));
+}
+
+fn simplex_noise_3d_seeded(v: vec3<f32>, seed: vec3<f32>) -> f32 {
+    let C = vec2(1. / 6., 1. / 3.);
+    let D = vec4(0., 0.5, 1., 2.);
+
+    // first corner
+    var i = floor(v + dot(v, C.yyy));
+    let x0 = v - i + dot(i, C.xxx);
+
+    // other corners
+    let g = step_3(x0.yzx, x0.xyz);
+    let l = 1. - g;
+    let i1 = min(g.xyz, l.zxy);
+    let i2 = max(g.xyz, l.zxy);
+
+    // x0 = x0 - 0. + 0. * C
+    let x1 = x0 - i1 + 1. * C.xxx;
+    let x2 = x0 - i2 + 2. * C.xxx;
+    let x3 = x0 - 1. + 3. * C.xxx;
+
+    // permutations
+    i = i % vec3(289.);
+    let s = floor(seed + vec3(0.5));
+    let p = permute_4_(permute_4_(permute_4_(
+        i.z + vec4(0., i1.z, i2.z, 1.) + s.z) +
+        i.y + vec4(0., i1.y, i2.y, 1.) + s.y) +
+        i.x + vec4(0., i1.x, i2.x, 1.) + s.x
+    );
+
+    // gradients (NxN points uniformly over a square, mapped onto an octahedron)
+    let n_ = 1. / 7.; // N=7
+    let ns = n_ * D.wyz - D.xzx;
+
+    let j = p - 49. * floor(p * ns.z * ns.z); // mod(p, N*N)
+
+    let x_ = floor(j * ns.z);
+    let y_ = floor(j - 7. * x_); // mod(j, N)
+
+    let x = x_ * ns.x + ns.yyyy;
+    let y = y_ * ns.x + ns.yyyy;
+    let h = 1. - abs(x) - abs(y);
+
+    let b0 = vec4(x.xy, y.xy);
+    let b1 = vec4(x.zw, y.zw);
+
+    let s0 = floor(b0) * 2. + 1.;
+    let s1 = floor(b1) * 2. + 1.;
+    let sh = -step(h, vec4(0.));
+
+    let a0 = b0.xzyw + s0.xzyw * sh.xxyy;
+    let a1 = b1.xzyw + s1.xzyw * sh.zzww;
+
+    var p0 = vec3(a0.xy, h.x);
+    var p1 = vec3(a0.zw, h.y);
+    var p2 = vec3(a1.xy, h.z);
+    var p3 = vec3(a1.zw, h.w);
+
+    // normalize gradients
+    let norm = gradient_norm_4_(vec4(dot(p0, p0), dot(p1, p1), dot(p2, p2), dot(p3, p3)));
+    p0 = p0 * norm.x;
+    p1 = p1 * norm.y;
+    p2 = p2 * norm.z;
+    p3 = p3 * norm.w;
+
+    // mix final noise value
+    var m = 0.5 - vec4(dot(x0, x0), dot(x1, x1), dot(x2, x2), dot(x3, x3));
+    m = max(m, vec4(0.));
+    m *= m;
+    return 105. * dot(m * m, vec4(dot(p0, x0), dot(p1, x1), dot(p2, x2), dot(p3, x3)));
+}
+
+// One of the gradients spread over the surface of a 4d cross polytope
+fn gradient_4_(j: f32, ip: vec4<f32>) -> vec4<f32> {
+    let xyz = floor(fract(vec3(j) * ip.xyz) * 7.) * ip.z - 1.;
+    let p = vec4(xyz, 1.5 - dot(abs(xyz), vec3(1.)));
+    let s = select(vec4(0.), vec4(1.), p < vec4(0.));
+    return vec4(p.xyz + (s.xyz * 2. - 1.) * s.w, p.w);
+}
+
+fn simplex_noise_4d(v: vec4<f32>) -> f32 {
+    // (sqrt(5) - 1) / 4
+    let F4 = 0.309016994374947451;
+    // (5 - sqrt(5)) / 20 times 1, 2 and 3, and 4 * that - 1
+    let C = vec4(0.138196601125011, 0.276393202250021, 0.414589803375032, -0.447213595499958);
+
+    // first corner
+    var i = floor(v + dot(v, vec4(F4)));
+    let x0 = v - i + dot(i, C.xxxx);
+
+    // other corners, ranking the coordinates by magnitude
+    let is_x = step(x0.yzw, x0.xxx);
+    let is_yz = step(x0.zww, x0.yyz);
+    var i0 = vec4(is_x.x + is_x.y + is_x.z, 1. - is_x);
+    i0.y += is_yz.x + is_yz.y;
+    i0.z += 1. - is_yz.x;
+    i0.w += 1. - is_yz.y;
+    i0.z += is_yz.z;
+    i0.w += 1. - is_yz.z;
+
+    let i3 = clamp(i0, vec4(0.), vec4(1.));
+    let i2 = clamp(i0 - 1., vec4(0.), vec4(1.));
+    let i1 = clamp(i0 - 2., vec4(0.), vec4(1.));
+
+    let x1 = x0 - i1 + C.xxxx;
+    let x2 = x0 - i2 + C.yyyy;
+    let x3 = x0 - i3 + C.zzzz;
+    let x4 = x0 + C.wwww;
+
+    // permutations
+    i = i % vec4(289.);
+    let j0 = permute_1_(permute_1_(permute_1_(permute_1_(i.w) + i.z) + i.y) + i.x);
+    let j1 = permute_4_(permute_4_(permute_4_(permute_4_(
+        i.w + vec4(i1.w, i2.w, i3.w, 1.)) +
+        i.z + vec4(i1.z, i2.z, i3.z, 1.)) +
+        i.y + vec4(i1.y, i2.y, i3.y, 1.)) +
+        i.x + vec4(i1.x, i2.x, i3.x, 1.)
+    );
+
+    // gradients (7x7x6 points over a cube, mapped onto a 4d cross polytope)
+    let ip = vec4(1. / 294., 1. / 49., 1. / 7., 0.);
+    var p0 = gradient_4_(j0, ip);
+    var p1 = gradient_4_(j1.x, ip);
+    var p2 = gradient_4_(j1.y, ip);
+    var p3 = gradient_4_(j1.z, ip);
+    var p4 = gradient_4_(j1.w, ip);
+
+    // normalize gradients
+    let norm = gradient_norm_4_(vec4(dot(p0, p0), dot(p1, p1), dot(p2, p2), dot(p3, p3)));
+    p0 = p0 * norm.x;
+    p1 = p1 * norm.y;
+    p2 = p2 * norm.z;
+    p3 = p3 * norm.w;
+    p4 = p4 * gradient_norm_4_(vec4(dot(p4, p4))).x;
+
+    // mix final noise value
+    var m0 = max(0.6 - vec3(dot(x0, x0), dot(x1, x1), dot(x2, x2)), vec3(0.));
+    var m1 = max(0.6 - vec2(dot(x3, x3), dot(x4, x4)), vec2(0.));
+    m0 *= m0;
+    m1 *= m1;
+    return 44. * (dot(m0 * m0, vec3(dot(p0, x0), dot(p1, x1), dot(p2, x2)))
+        + dot(m1 * m1, vec2(dot(p3, x3), dot(p4, x4))));
+}
+
+// Flow noise, simplex noise with all gradients rotated by `rotation` radians.
+// A full turn gives the same noise again.
+fn simplex_noise_2d_flow(v: vec2<f32>, rotation: f32) -> f32 {
+    let C = vec2(
+        0.211324865405187, // (3.0 - sqrt(3.0)) / 6.0
+        0.366025403784439, // 0.5 * (sqrt(3.0) - 1.0)
+    );
+
+    // first corner
+    var i = floor(v + dot(v, C.yy));
+    let x0 = v - i + dot(i, C.xx);
+
+    // other corners
+    let i1 = select(vec2(0., 1.), vec2(1., 0.), x0.x > x0.y);
+    let x1 = x0 - i1 + C.x;
+    let x2 = x0 - 1. + 2. * C.x;
+
+    // permutations
+    i = i % vec2(289.);
+    let p = permute_3_(permute_3_(i.y + vec3(0., i1.y, 1.)) + i.x + vec3(0., i1.x, 1.));
+
+    // gradients: unit vectors at hashed angles, all turned by the rotation
+    let psi = p * 0.07482 + rotation;
+    let g = vec3(
+        dot(vec2(cos(psi.x), sin(psi.x)), x0),
+        dot(vec2(cos(psi.y), sin(psi.y)), x1),
+        dot(vec2(cos(psi.z), sin(psi.z)), x2),
+    );
+
+    var m = max(0.5 - vec3(dot(x0, x0), dot(x1, x1), dot(x2, x2)), vec3(0.));
+    m *= m;
+    m *= m;
+
+    // compute final noise value at P, scaled to about -1..1 for unit gradients
+    return 99. * dot(m, g);
+}
+
+// Unit gradient for a 3d flow noise corner, on a fibonacci spiral over the
+// sphere, rotated towards a hashed perpendicular direction
+fn flow_gradient_3_(hash: f32, sin_rotation: f32, cos_rotation: f32) -> vec3<f32> {
+    let theta = hash * 3.883222077;
+    let st = sin(theta);
+    let ct = cos(theta);
+    let sz = hash * -0.006920415 + 0.996539792;
+    let sz_prime = sqrt(1. - sz * sz);
+    let p = vec3(ct * sz_prime, st * sz_prime, sz);
+
+    let psi = hash * 0.108705628;
+    let sp = sin(psi);
+    let cp = cos(psi);
+    let ctp = st * sp - ct * cp;
+    let q = vec3(
+        ctp * st + (sp - ctp * st) * sz,
+        -ctp * ct + (cp + ctp * ct) * sz,
+        -(p.y * cp + p.x * sp),
+    );
+
+    return p * cos_rotation + q * sin_rotation;
+}
+
+fn simplex_noise_3d_flow(v: vec3<f32>, rotation: f32) -> f32 {
+    let C = vec2(1. / 6., 1. / 3.);
+
+    // first corner
+    var i = floor(v + dot(v, C.yyy));
+    let x0 = v - i + dot(i, C.xxx);
+
+    // other corners
+    let g = step_3(x0.yzx, x0.xyz);
+    let l = 1. - g;
+    let i1 = min(g.xyz, l.zxy);
+    let i2 = max(g.xyz, l.zxy);
+
+    let x1 = x0 - i1 + 1. * C.xxx;
+    let x2 = x0 - i2 + 2. * C.xxx;
+    let x3 = x0 - 1. + 3. * C.xxx;
+
+    // permutations
+    i = i % vec3(289.);
+    let p = permute_4_(permute_4_(permute_4_(
+        i.z + vec4(0., i1.z, i2.z, 1.)) +
+        i.y + vec4(0., i1.y, i2.y, 1.)) +
+        i.x + vec4(0., i1.x, i2.x, 1.)
+    );
+
+    // gradients
+    let sa = sin(rotation);
+    let ca = cos(rotation);
+    let p0 = flow_gradient_3_(p.x, sa, ca);
+    let p1 = flow_gradient_3_(p.y, sa, ca);
+    let p2 = flow_gradient_3_(p.z, sa, ca);
+    let p3 = flow_gradient_3_(p.w, sa, ca);
+
+    // mix final noise value
+    var m = 0.5 - vec4(dot(x0, x0), dot(x1, x1), dot(x2, x2), dot(x3, x3));
+    m = max(m, vec4(0.));
+    m *= m;
+    return 105. * dot(m * m, vec4(dot(p0, x0), dot(p1, x1), dot(p2, x2), dot(p3, x3)));
+}
@@ -0,0 +1,222 @@
+//  MIT License. © Ian McEwan, Stefan Gustavson, Munrocket, Johan Helsing
+
+#define_import_path noisy_bevy::warp
+
+#import noisy_bevy::fbm::{fbm_simplex_2d, fbm_simplex_2d_seeded, fbm_simplex_3d, fbm_simplex_3d_seeded}
+
+// Warping has diminishing returns due to the falloff param, so we don't need many iterations. Faloff makes it look more natural.
+// Can be raised with the NOISY_MAX_WARP_ITERATIONS shader def, requesting more iterations than this is clamped.
+#ifdef NOISY_MAX_WARP_ITERATIONS
+const max_warp_iterations: i32 = #{NOISY_MAX_WARP_ITERATIONS};
+#else
+const max_warp_iterations: i32 = 4;
+#endif
+
+struct WarpResult {
+    // The final noise value, sampled at positions[0]
+    noise_value: f32,
+    // The history of warped coordinates, where positions[0] is the last iteration, positions[1] is second to last, etc.
+    // Entries from before the first iteration hold the initial position.
+    // Can be useful for mixing colors.
+    positions: array<vec2f, max_warp_iterations>
+}
+
+// Store positions in reverse order for easier user access (last iteration at index 0)
+fn push_warp_position_2d_(positions: ptr<function, array<vec2f, max_warp_iterations>>, pos: vec2<f32>) {
+    for (var j = max_warp_iterations - 1; j > 0; j--) {
+        (*positions)[j] = (*positions)[j - 1];
+    }
+    (*positions)[0] = pos;
+}
+
+/// A technique that distorts the position before feeding it to the noise
+/// inspired by https://iquilezles.org/articles/warp/
+fn fbm_simplex_2d_warp_seeded(pos_initial: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: f32, warp_iterations: i32, warp_scale: vec2<f32>, falloff: f32) -> WarpResult {
+    var scale = 1.0;
+    var positions = array<vec2f, max_warp_iterations>();
+    for (var j = 0; j < max_warp_iterations; j++) {
+        positions[j] = pos_initial;
+    }
+    var pos = pos_initial;
+
+    let iterations = min(warp_iterations, max_warp_iterations);
+
+    for (var i: i32 = 0; i < iterations; i++) {
+        pos.x += scale * warp_scale.x * fbm_simplex_2d_seeded(pos, octaves, lacunarity, gain, seed);
+        pos.y += scale * warp_scale.y * fbm_simplex_2d_seeded(pos, octaves, lacunarity, gain, seed);
+
+        push_warp_position_2d_(&positions, pos);
+
+        scale *= falloff;
+    }
+
+    let noise_value = fbm_simplex_2d_seeded(pos, octaves, lacunarity, gain, seed);
+
+    return WarpResult(noise_value, positions);
+}
+
+struct DomainWarp2d {
+    octaves: i32,
+    lacunarity: f32,
+    gain: f32,
+    // Seed of the final noise and the offset field
+    seed: f32,
+    // Number of warp iterations, clamped to max_warp_iterations
+    iterations: i32,
+    // How far positions are displaced in the first iteration
+    scale: vec2<f32>,
+    // Scale multiplier between iterations
+    falloff: f32,
+    // Added to the seed of the x and y displacement respectively
+    axis_seed_offsets: vec2<f32>,
+    // Frequency multiplier of the offset field between iterations
+    frequency_step: f32,
+    // Octaves added to the offset field each iteration, may be negative
+    octave_step: i32,
+}
+
+/// Domain warping using seeded 2d simplex fbm, with independent offsets for
+/// the x and y displacement and per-iteration frequency and octaves
+fn fbm_simplex_2d_warp(pos_initial: vec2<f32>, warp: DomainWarp2d) -> WarpResult {
+    var scale = 1.0;
+    var frequency = 1.0;
+    var octaves = warp.octaves;
+    var positions = array<vec2f, max_warp_iterations>();
+    for (var j = 0; j < max_warp_iterations; j++) {
+        positions[j] = pos_initial;
+    }
+    var pos = pos_initial;
+
+    let iterations = min(warp.iterations, max_warp_iterations);
+    let seeds = warp.seed + warp.axis_seed_offsets;
+
+    for (var i: i32 = 0; i < iterations; i++) {
+        let o = max(octaves, 1);
+        pos.x += scale * warp.scale.x * fbm_simplex_2d_seeded(pos * frequency, o, warp.lacunarity, warp.gain, seeds.x);
+        pos.y += scale * warp.scale.y * fbm_simplex_2d_seeded(pos * frequency, o, warp.lacunarity, warp.gain, seeds.y);
+
+        push_warp_position_2d_(&positions, pos);
+
+        scale *= warp.falloff;
+        frequency *= warp.frequency_step;
+        octaves += warp.octave_step;
+    }
+
+    let noise_value = fbm_simplex_2d_seeded(pos, warp.octaves, warp.lacunarity, warp.gain, warp.seed);
+
+    return WarpResult(noise_value, positions);
+}
+
+virtual fn warp_offset_noise_2d(pos: vec2<f32>) -> f32 {
+    // Noise used for the offset field of `warp_2d`
+    // can be overridden from your own shader to plug in a different noise function
+    // (not a doc comment, naga_oil's virtual fn handling would swallow the declaration)
+    return fbm_simplex_2d(pos, 4, 2., 0.5);
+}
+
+virtual fn warp_noise_2d(pos: vec2<f32>) -> f32 {
+    // Noise sampled at the final warped position of `warp_2d`
+    // can be overridden from your own shader to plug in a different noise function
+    return fbm_simplex_2d(pos, 4, 2., 0.5);
+}
+
+/// Domain warping with pluggable noise functions, see `warp_offset_noise_2d` and `warp_noise_2d`
+fn warp_2d(pos_initial: vec2<f32>, warp_iterations: i32, warp_scale: vec2<f32>, falloff: f32) -> WarpResult {
+    var scale = 1.0;
+    var positions = array<vec2f, max_warp_iterations>();
+    for (var j = 0; j < max_warp_iterations; j++) {
+        positions[j] = pos_initial;
+    }
+    var pos = pos_initial;
+
+    let iterations = min(warp_iterations, max_warp_iterations);
+
+    for (var i: i32 = 0; i < iterations; i++) {
+        pos.x += scale * warp_scale.x * warp_offset_noise_2d(pos);
+        pos.y += scale * warp_scale.y * warp_offset_noise_2d(pos);
+
+        push_warp_position_2d_(&positions, pos);
+
+        scale *= falloff;
+    }
+
+    return WarpResult(warp_noise_2d(pos), positions);
+}
+
+struct WarpResult3d {
+    // The final noise value, sampled at positions[0]
+    noise_value: f32,
+    // The history of warped coordinates, where positions[0] is the last iteration, positions[1] is second to last, etc.
+    // Entries from before the first iteration hold the initial position.
+    // Can be useful for mixing colors.
+    positions: array<vec3f, max_warp_iterations>
+}
+
+fn push_warp_position_3d_(positions: ptr<function, array<vec3f, max_warp_iterations>>, pos: vec3<f32>) {
+    for (var j = max_warp_iterations - 1; j > 0; j--) {
+        (*positions)[j] = (*positions)[j - 1];
+    }
+    (*positions)[0] = pos;
+}
+
+/// Domain warping in three dimensions using seeded 3d simplex fbm
+fn fbm_simplex_3d_warp_seeded(pos_initial: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: vec3<f32>, warp_iterations: i32, warp_scale: vec3<f32>, falloff: f32) -> WarpResult3d {
+    var scale = 1.0;
+    var positions = array<vec3f, max_warp_iterations>();
+    for (var j = 0; j < max_warp_iterations; j++) {
+        positions[j] = pos_initial;
+    }
+    var pos = pos_initial;
+
+    let iterations = min(warp_iterations, max_warp_iterations);
+
+    for (var i: i32 = 0; i < iterations; i++) {
+        pos.x += scale * warp_scale.x * fbm_simplex_3d_seeded(pos, octaves, lacunarity, gain, seed);
+        pos.y += scale * warp_scale.y * fbm_simplex_3d_seeded(pos, octaves, lacunarity, gain, seed);
+        pos.z += scale * warp_scale.z * fbm_simplex_3d_seeded(pos, octaves, lacunarity, gain, seed);
+
+        push_warp_position_3d_(&positions, pos);
+
+        scale *= falloff;
+    }
+
+    let noise_value = fbm_simplex_3d_seeded(pos, octaves, lacunarity, gain, seed);
+
+    return WarpResult3d(noise_value, positions);
+}
+
+virtual fn warp_offset_noise_3d(pos: vec3<f32>) -> f32 {
+    // Noise used for the offset field of `warp_3d`
+    // can be overridden from your own shader to plug in a different noise function
+    return fbm_simplex_3d(pos, 4, 2., 0.5);
+}
+
+virtual fn warp_noise_3d(pos: vec3<f32>) -> f32 {
+    // Noise sampled at the final warped position of `warp_3d`
+    // can be overridden from your own shader to plug in a different noise function
+    return fbm_simplex_3d(pos, 4, 2., 0.5);
+}
+
+/// Domain warping in three dimensions with pluggable noise functions, see `warp_offset_noise_3d` and `warp_noise_3d`
+fn warp_3d(pos_initial: vec3<f32>, warp_iterations: i32, warp_scale: vec3<f32>, falloff: f32) -> WarpResult3d {
+    var scale = 1.0;
+    var positions = array<vec3f, max_warp_iterations>();
+    for (var j = 0; j < max_warp_iterations; j++) {
+        positions[j] = pos_initial;
+    }
+    var pos = pos_initial;
+
+    let iterations = min(warp_iterations, max_warp_iterations);
+
+    for (var i: i32 = 0; i < iterations; i++) {
+        pos.x += scale * warp_scale.x * warp_offset_noise_3d(pos);
+        pos.y += scale * warp_scale.y * warp_offset_noise_3d(pos);
+        pos.z += scale * warp_scale.z * warp_offset_noise_3d(pos);
+
+        push_warp_position_3d_(&positions, pos);
+
+        scale *= falloff;
+    }
+
+    return WarpResult3d(warp_noise_3d(pos), positions);
+}
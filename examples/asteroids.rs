@@ -15,7 +15,7 @@ fn main() {
         .insert_resource(ClearColor(Color::BLACK))
         .add_plugins((
             DefaultPlugins,
-            NoisyShaderPlugin,
+            NoisyShaderPlugin::default(),
             PanCamPlugin,
             Material2dPlugin::<AsteroidBackgroundMaterial>::default(),
             // WorldInspectorPlugin::new()
@@ -0,0 +1,83 @@
+//! A raymarched sky with `CloudMaterial` on a sphere around the camera.
+//!
+//! The sunlight on the ground is dimmed with `CloudLayer::transmittance` on the
+//! CPU, so it darkens when a cloud drifts in front of the sun on screen.
+
+use bevy::prelude::*;
+use noisy_bevy::{CloudLayer, CloudMaterial, NoisyShaderPlugin};
+
+const SUN_DIRECTION: Vec3 = Vec3::new(-0.4, -0.6, -0.7);
+const SUN_ILLUMINANCE: f32 = 10_000.0;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            NoisyShaderPlugin {
+                materials: true,
+                ..default()
+            },
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (look_around, shade_sun))
+        .run();
+}
+
+fn clouds() -> CloudLayer {
+    CloudLayer {
+        coverage: 0.55,
+        wind: Vec3::new(40.0, 0.0, 15.0),
+        ..default()
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut sky_materials: ResMut<Assets<CloudMaterial>>,
+) {
+    // the sky follows the camera, within its far plane
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 2.0, 0.0).looking_to(Vec3::new(-0.5, 0.3, -1.0), Vec3::Y),
+        children![(
+            Mesh3d(meshes.add(Sphere::new(900.0))),
+            MeshMaterial3d(sky_materials.add(CloudMaterial {
+                layer: clouds(),
+                sun_direction: SUN_DIRECTION,
+                ..default()
+            })),
+        )],
+    ));
+
+    commands.spawn((
+        DirectionalLight {
+            illuminance: SUN_ILLUMINANCE,
+            ..default()
+        },
+        Transform::default().looking_to(SUN_DIRECTION, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(200.0, 200.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.5, 0.3))),
+    ));
+}
+
+fn look_around(time: Res<Time>, mut camera: Single<&mut Transform, With<Camera3d>>) {
+    camera.rotate_y(time.delta_secs() * 0.05);
+}
+
+fn shade_sun(
+    time: Res<Time>,
+    camera: Single<&GlobalTransform, With<Camera3d>>,
+    mut sun: Single<&mut DirectionalLight>,
+) {
+    let clouds = clouds();
+    let from = camera.translation();
+    // far enough to cross the whole layer
+    let to = from - SUN_DIRECTION.normalize() * clouds.top * 4.0;
+    let visibility = clouds.transmittance(from, to, time.elapsed_secs_wrapped(), 32);
+    sun.illuminance = SUN_ILLUMINANCE * (0.2 + 0.8 * visibility);
+}
@@ -0,0 +1,53 @@
+//! Colors a 2d mesh with the ready-made `NoiseMaterial2d`, no WGSL needed.
+//!
+//! The noise is sampled in world space and scrolls over time.
+
+use bevy::{camera::ScalingMode, color::palettes::css, prelude::*};
+use noisy_bevy::{NoiseGradient, NoiseMaterial2d, NoiseParams, NoiseType, NoisyShaderPlugin};
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::BLACK))
+        .add_plugins((
+            DefaultPlugins,
+            NoisyShaderPlugin {
+                materials: true,
+                ..default()
+            },
+        ))
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<NoiseMaterial2d>>,
+) {
+    commands.spawn((
+        Camera2d,
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical {
+                viewport_height: 230.0,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+    ));
+
+    let material = materials.add(NoiseMaterial2d {
+        noise: NoiseParams {
+            noise_type: NoiseType::Warp,
+            frequency: 0.02,
+            octaves: 5,
+            scroll: Vec3::new(0.1, 0.0, 0.05),
+            gradient: NoiseGradient::linear([(0.0, css::MIDNIGHT_BLUE), (1.0, css::LIGHT_CYAN)]),
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::from_size(Vec2::splat(200.0)))),
+        MeshMaterial2d(material),
+    ));
+}
@@ -0,0 +1,93 @@
+//! Colors 3d meshes with the ready-made `NoiseMaterial`, and perturbs a lit
+//! `StandardMaterial` with `NoiseExtension`, no WGSL needed.
+//!
+//! The noise is sampled in world space, so the sphere and the plane share the
+//! same noise field.
+
+use bevy::{color::palettes::css, pbr::ExtendedMaterial, prelude::*};
+use noisy_bevy::{
+    NoiseExtension, NoiseGradient, NoiseMaterial, NoiseParams, NoiseStandardMaterial, NoiseType,
+    NoisyShaderPlugin,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            NoisyShaderPlugin {
+                materials: true,
+                ..default()
+            },
+        ))
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<NoiseMaterial>>,
+    mut standard_materials: ResMut<Assets<NoiseStandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 3.0, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    let material = materials.add(NoiseMaterial {
+        noise: NoiseParams {
+            noise_type: NoiseType::Fbm,
+            frequency: 1.5,
+            scroll: Vec3::new(0.0, 0.2, 0.0),
+            gradient: NoiseGradient::step([
+                (0.0, css::DARK_GREEN),
+                (0.4, css::OLIVE_DRAB),
+                (0.55, css::DARK_KHAKI),
+                (0.7, css::KHAKI),
+            ]),
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(1.0))),
+        MeshMaterial3d(material.clone()),
+        Transform::from_xyz(0.0, 1.0, 0.0),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(8.0, 8.0))),
+        MeshMaterial3d(material),
+    ));
+
+    // unlit noise materials don't need light, the rock does
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    let rock = standard_materials.add(ExtendedMaterial {
+        base: StandardMaterial {
+            base_color: css::GRAY.into(),
+            perceptual_roughness: 0.7,
+            ..default()
+        },
+        extension: NoiseExtension {
+            noise: NoiseParams {
+                noise_type: NoiseType::Worley,
+                frequency: 4.0,
+                gradient: NoiseGradient::linear([(0.0, css::DIM_GRAY), (1.0, css::SILVER)]),
+                ..default()
+            },
+            albedo: 1.0,
+            roughness: 0.3,
+            normal: 0.4,
+            ..default()
+        },
+    });
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(0.7))),
+        MeshMaterial3d(rock),
+        Transform::from_xyz(2.2, 0.7, 0.0),
+    ));
+}
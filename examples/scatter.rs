@@ -0,0 +1,108 @@
+//! Scatters trees and rocks around the camera with [`NoiseScatter`], streaming
+//! chunks in and out as the camera moves. Move with the arrow keys.
+
+use bevy::{camera::ScalingMode, ecs::entity_disabling::Disabled, prelude::*};
+use noisy_bevy::{NoiseLayer, NoiseScatter, NoiseScatterPlugin, ScatterPlane};
+
+const VIEW_HEIGHT: f32 = 60.0;
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::srgb(0.25, 0.4, 0.2)))
+        .add_plugins((DefaultPlugins, NoiseScatterPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (move_camera, follow_camera).chain())
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Camera2d,
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical {
+                viewport_height: VIEW_HEIGHT,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+    ));
+
+    let sprite = |color: Color, size: f32| {
+        (
+            Sprite {
+                color,
+                custom_size: Some(Vec2::splat(size)),
+                ..default()
+            },
+            Disabled,
+        )
+    };
+    let trees = [
+        commands.spawn(sprite(Color::srgb(0.1, 0.3, 0.1), 1.6)).id(),
+        commands
+            .spawn(sprite(Color::srgb(0.15, 0.35, 0.1), 1.2))
+            .id(),
+    ];
+    let rock = commands
+        .spawn(sprite(Color::srgb(0.5, 0.5, 0.45), 0.8))
+        .id();
+
+    // forests where the density noise is high
+    commands.spawn(NoiseScatter {
+        plane: ScatterPlane::Xy,
+        chunk_size: 16.0,
+        min_spacing: 1.5,
+        density: NoiseLayer::new(0.03)
+            .with_fbm(3, 2.0, 0.5)
+            .with_range(-0.6, 1.4),
+        instances: trees.to_vec(),
+        seed: 1,
+        ..default()
+    });
+
+    // rocks on the hillsides of an invisible heightfield
+    commands.spawn(NoiseScatter {
+        plane: ScatterPlane::Xy,
+        chunk_size: 16.0,
+        min_spacing: 2.0,
+        height: NoiseLayer::new(0.02).with_range(0.0, 40.0),
+        slope_range: 0.8..f32::INFINITY,
+        instances: vec![rock],
+        seed: 2,
+        ..default()
+    });
+}
+
+fn move_camera(
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut camera: Single<&mut Transform, With<Camera2d>>,
+) {
+    let mut direction = Vec2::ZERO;
+    for (key, dir) in [
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, Vec2::X),
+        (KeyCode::ArrowDown, Vec2::NEG_Y),
+        (KeyCode::ArrowUp, Vec2::Y),
+    ] {
+        if keys.pressed(key) {
+            direction += dir;
+        }
+    }
+    camera.translation += (direction.normalize_or_zero() * 30.0 * time.delta_secs()).extend(0.0);
+}
+
+fn follow_camera(
+    camera: Single<&Transform, With<Camera2d>>,
+    mut scatters: Query<&mut NoiseScatter>,
+) {
+    let center = camera.translation.truncate();
+    let area = Rect::from_center_size(center, Vec2::splat(VIEW_HEIGHT * 2.0));
+    for mut scatter in &mut scatters {
+        // only touch the component when the area changes, so the scatter
+        // isn't updated every frame
+        scatter.set_if_neq(NoiseScatter {
+            area,
+            ..scatter.clone()
+        });
+    }
+}
@@ -0,0 +1,49 @@
+//! Shakes the camera with [`NoiseShake`] when space is pressed.
+
+use bevy::{camera::ScalingMode, prelude::*};
+use noisy_bevy::{NoiseShake, NoiseShakePlugin};
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::BLACK))
+        .add_plugins((DefaultPlugins, NoiseShakePlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, add_trauma)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Camera2d,
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical {
+                viewport_height: 20.0,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+        NoiseShake::default(),
+    ));
+
+    for x in -10..=10 {
+        for y in -10..=10 {
+            if (x + y) % 2 == 0 {
+                commands.spawn((
+                    Sprite {
+                        color: Color::WHITE.with_luminance(0.3),
+                        custom_size: Some(Vec2::splat(1.)),
+                        ..default()
+                    },
+                    Transform::from_xyz(x as f32, y as f32, 0.),
+                ));
+            }
+        }
+    }
+}
+
+fn add_trauma(keys: Res<ButtonInput<KeyCode>>, mut shakes: Query<&mut NoiseShake>) {
+    if keys.just_pressed(KeyCode::Space) {
+        for mut shake in &mut shakes {
+            shake.add_trauma(0.5);
+        }
+    }
+}
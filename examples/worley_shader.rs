@@ -11,7 +11,7 @@ fn main() {
         .insert_resource(ClearColor(Color::BLACK))
         .add_plugins((
             DefaultPlugins,
-            NoisyShaderPlugin,
+            NoisyShaderPlugin::default(),
             Material2dPlugin::<NoiseMaterial>::default(),
         ))
         .add_systems(Startup, setup)
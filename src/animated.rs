@@ -0,0 +1,200 @@
+use bevy::{
+    math::{Vec2, Vec3},
+    reflect::Reflect,
+    time::Time,
+};
+
+use crate::{fbm_simplex_3d_seeded, fbm_simplex_4d};
+
+/// A 2d or 3d fbm field that evolves over time, by sampling noise with one
+/// more dimension and using time as the extra axis
+///
+/// The `noisy_bevy::animated` shader module samples the same field, and bevy's
+/// global shader time is wrapped like
+/// [`Time::elapsed_secs_wrapped`], so use [`AnimatedNoise::sample_2d_time`]
+/// and [`AnimatedNoise::sample_3d_time`] to read the field gameplay systems
+/// see on screen.
+///
+/// The wrapped time jumps back to zero once per wrap period, an hour by
+/// default, and the field jumps with it. Raise the period with
+/// [`Time::set_wrap_period`] if that's visible.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use noisy_bevy::AnimatedNoise;
+/// #[derive(Component)]
+/// struct Boat;
+///
+/// fn waves() -> AnimatedNoise {
+///     AnimatedNoise {
+///         frequency: 0.1,
+///         speed: 0.3,
+///         ..default()
+///     }
+/// }
+///
+/// fn bob(time: Res<Time>, mut boats: Query<&mut Transform, With<Boat>>) {
+///     for mut transform in &mut boats {
+///         let pos = transform.translation.xz();
+///         transform.translation.y = waves().sample_2d_time(pos, &time);
+///     }
+/// }
+/// ```
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct AnimatedNoise {
+    /// Noise periods per world unit
+    pub frequency: f32,
+    /// How fast the field changes, in noise periods per second
+    pub speed: f32,
+    /// Number of fbm octaves, 1 gives plain simplex noise
+    pub octaves: usize,
+    /// Frequency multiplier between octaves, both in space and time
+    pub lacunarity: f32,
+    /// Amplitude multiplier between octaves
+    pub gain: f32,
+    /// Fields with different seeds are uncorrelated. The 3d field is shifted
+    /// in space by the seed, since 4d simplex noise has no seeded variant,
+    /// so keep 3d seeds small like positions.
+    pub seed: f32,
+}
+
+impl Default for AnimatedNoise {
+    /// Plain simplex noise changing by one noise period per second
+    fn default() -> Self {
+        Self {
+            frequency: 1.0,
+            speed: 1.0,
+            octaves: 1,
+            lacunarity: 2.0,
+            gain: 0.5,
+            seed: 0.0,
+        }
+    }
+}
+
+// 4d simplex noise has no seeded variant, so seeds shift the 3d field in space
+// instead. Over a noise cell per seed, so whole seeds are uncorrelated, but
+// small enough to keep positions near the origin where f32 is precise.
+const SEED_OFFSET: Vec3 = Vec3::new(1.731, 1.317, 1.593);
+
+impl AnimatedNoise {
+    /// Samples the 2d field at `time` seconds, in the range -1..1 for a
+    /// single octave
+    pub fn sample_2d(&self, pos: Vec2, time: f32) -> f32 {
+        fbm_simplex_3d_seeded(
+            (pos * self.frequency).extend(time * self.speed),
+            self.octaves,
+            self.lacunarity,
+            self.gain,
+            Vec3::splat(self.seed),
+        )
+    }
+
+    /// Samples the 3d field at `time` seconds, in the range -1..1 for a
+    /// single octave
+    pub fn sample_3d(&self, pos: Vec3, time: f32) -> f32 {
+        fbm_simplex_4d(
+            (pos * self.frequency + self.seed * SEED_OFFSET).extend(time * self.speed),
+            self.octaves,
+            self.lacunarity,
+            self.gain,
+        )
+    }
+
+    /// Samples the 2d field at the wrapped elapsed time of the given clock,
+    /// the time the shaders see
+    pub fn sample_2d_time<T: Default>(&self, pos: Vec2, time: &Time<T>) -> f32 {
+        self.sample_2d(pos, time.elapsed_secs_wrapped())
+    }
+
+    /// Samples the 3d field at the wrapped elapsed time of the given clock,
+    /// the time the shaders see
+    pub fn sample_3d_time<T: Default>(&self, pos: Vec3, time: &Time<T>) -> f32 {
+        self.sample_3d(pos, time.elapsed_secs_wrapped())
+    }
+}
+
+#[cfg(all(feature = "gpu", feature = "image"))]
+mod gpu {
+    use bevy::render::render_resource::ShaderType;
+
+    use super::AnimatedNoise;
+
+    /// The GPU representation of [`AnimatedNoise`], `AnimatedNoise` in the
+    /// `noisy_bevy::animated` shader module
+    #[derive(Clone, Copy, Debug, Default, ShaderType)]
+    pub struct AnimatedNoiseUniform {
+        /// See [`AnimatedNoise::frequency`]
+        pub frequency: f32,
+        /// See [`AnimatedNoise::speed`]
+        pub speed: f32,
+        /// See [`AnimatedNoise::octaves`]
+        pub octaves: i32,
+        /// See [`AnimatedNoise::lacunarity`]
+        pub lacunarity: f32,
+        /// See [`AnimatedNoise::gain`]
+        pub gain: f32,
+        /// See [`AnimatedNoise::seed`]
+        pub seed: f32,
+    }
+
+    impl From<&AnimatedNoise> for AnimatedNoiseUniform {
+        fn from(noise: &AnimatedNoise) -> Self {
+            Self {
+                frequency: noise.frequency,
+                speed: noise.speed,
+                octaves: noise.octaves as i32,
+                lacunarity: noise.lacunarity,
+                gain: noise.gain,
+                seed: noise.seed,
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "gpu", feature = "image"))]
+pub use gpu::*;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::math::{vec2, vec3};
+    use std::time::Duration;
+
+    #[test]
+    fn changes_smoothly_over_time() {
+        let noise = AnimatedNoise {
+            octaves: 3,
+            ..Default::default()
+        };
+        let p2 = vec2(0.3, -1.7);
+        let p3 = vec3(0.3, -1.7, 2.2);
+        assert_ne!(noise.sample_2d(p2, 0.0), noise.sample_2d(p2, 0.5));
+        assert_ne!(noise.sample_3d(p3, 0.0), noise.sample_3d(p3, 0.5));
+        let step_2d = (noise.sample_2d(p2, 0.5) - noise.sample_2d(p2, 0.501)).abs();
+        let step_3d = (noise.sample_3d(p3, 0.5) - noise.sample_3d(p3, 0.501)).abs();
+        assert!(step_2d < 0.05 && step_3d < 0.05, "{step_2d} {step_3d}");
+
+        // no speed, no animation
+        let still = AnimatedNoise {
+            speed: 0.0,
+            ..noise
+        };
+        assert_eq!(still.sample_3d(p3, 0.0), still.sample_3d(p3, 7.0));
+        let seeded = AnimatedNoise { seed: 1.0, ..noise };
+        assert_ne!(seeded.sample_3d(p3, 0.0), noise.sample_3d(p3, 0.0));
+        assert_ne!(seeded.sample_2d(p2, 0.0), noise.sample_2d(p2, 0.0));
+    }
+
+    #[test]
+    fn sample_time_uses_wrapped_time() {
+        let noise = AnimatedNoise::default();
+        let mut time = Time::<()>::default();
+        time.set_wrap_period(Duration::from_secs(10));
+        time.advance_by(Duration::from_millis(12_500));
+        let p = vec3(1.0, 2.0, 3.0);
+        assert_eq!(noise.sample_3d_time(p, &time), noise.sample_3d(p, 2.5));
+        let p = vec2(1.0, 2.0);
+        assert_eq!(noise.sample_2d_time(p, &time), noise.sample_2d(p, 2.5));
+    }
+}
@@ -0,0 +1,322 @@
+use bevy::math::{Vec2, vec2};
+
+use crate::NoiseLayer;
+
+/// A named [`NoiseLayer`], one of the axes of a [`BiomeMap`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiomeAxis {
+    /// Name of the layer, like "temperature" or "moisture"
+    pub name: String,
+    /// The noise, the table covers the range of the layer
+    pub layer: NoiseLayer,
+}
+
+impl BiomeAxis {
+    /// An axis sampling `layer`
+    pub fn new(name: impl Into<String>, layer: NoiseLayer) -> Self {
+        Self {
+            name: name.into(),
+            layer,
+        }
+    }
+
+    /// Where the layer is at `pos` along the axis of the table, between 0 and
+    /// 1
+    pub fn sample(&self, pos: Vec2) -> f32 {
+        let layer = &self.layer;
+        if layer.min == layer.max {
+            return 0.5;
+        }
+        ((layer.sample(pos) - layer.min) / (layer.max - layer.min)).clamp(0.0, 1.0)
+    }
+}
+
+/// Classifies positions into biomes with a Whittaker style lookup table over
+/// two noise layers, like temperature and moisture
+///
+/// The table is split into equally sized cells along both axes, the rows
+/// going from the low end of the `y` layer to the high end, and the columns
+/// from the low end of the `x` layer to the high end. Repeat a biome in
+/// neighboring cells to make it cover more of the table.
+///
+/// ```
+/// # use noisy_bevy::{BiomeAxis, BiomeMap, NoiseLayer};
+/// # use bevy::math::vec2;
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum Biome { Tundra, Desert, Forest, Rainforest }
+///
+/// let biomes = BiomeMap::new(
+///     BiomeAxis::new("temperature", NoiseLayer::new(0.002).with_seed(1.0)),
+///     BiomeAxis::new("moisture", NoiseLayer::new(0.003).with_seed(2.0)),
+///     vec![
+///         // dry
+///         vec![Biome::Tundra, Biome::Desert, Biome::Desert],
+///         // wet
+///         vec![Biome::Tundra, Biome::Forest, Biome::Rainforest],
+///     ],
+/// );
+/// let biome = biomes.biome(vec2(100.0, 250.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiomeMap<B> {
+    /// The layer along the columns of the table
+    pub x: BiomeAxis,
+    /// The layer along the rows of the table
+    pub y: BiomeAxis,
+    /// Biomes, row by row
+    pub table: Vec<Vec<B>>,
+    /// How far into each table cell biomes blend with their neighbors in
+    /// [`BiomeMap::weights`], as a fraction of the cell, between 0 and 0.5
+    pub blend: f32,
+}
+
+impl<B: PartialEq> BiomeMap<B> {
+    /// A map looking up `table` with the `x` and `y` layers
+    ///
+    /// # Panics
+    ///
+    /// If the table is empty or its rows have different lengths.
+    pub fn new(x: BiomeAxis, y: BiomeAxis, table: Vec<Vec<B>>) -> Self {
+        let columns = table.first().map_or(0, Vec::len);
+        assert!(columns > 0, "the biome table is empty");
+        assert!(
+            table.iter().all(|row| row.len() == columns),
+            "the rows of the biome table have different lengths"
+        );
+        Self {
+            x,
+            y,
+            table,
+            blend: 0.25,
+        }
+    }
+
+    /// Returns the map with a different [`BiomeMap::blend`]
+    pub fn with_blend(mut self, blend: f32) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// The value of the layer with the given name at `pos`
+    pub fn sample_layer(&self, name: &str, pos: Vec2) -> Option<f32> {
+        [&self.x, &self.y]
+            .into_iter()
+            .find(|axis| axis.name == name)
+            .map(|axis| axis.layer.sample(pos))
+    }
+
+    /// Where `pos` falls in the table, between 0 and 1 along both axes
+    pub fn climate(&self, pos: Vec2) -> Vec2 {
+        vec2(self.x.sample(pos), self.y.sample(pos))
+    }
+
+    /// The biome at `pos`
+    pub fn biome(&self, pos: Vec2) -> &B {
+        self.biome_at_climate(self.climate(pos))
+    }
+
+    /// The biome at a position in the table, see [`BiomeMap::climate`]
+    pub fn biome_at_climate(&self, climate: Vec2) -> &B {
+        let (column, row) = self.cell(climate);
+        &self.table[row][column]
+    }
+
+    /// The biomes around `pos` and how much each contributes, summing to 1
+    /// and sorted by weight, heaviest first
+    ///
+    /// Within [`BiomeMap::blend`] of the edge of a table cell, the neighboring
+    /// cell gets a share that grows smoothly to half at the edge, so blending
+    /// terrain heights or colors with the weights has no seams.
+    pub fn weights(&self, pos: Vec2) -> Vec<(&B, f32)> {
+        self.weights_at_climate(self.climate(pos))
+    }
+
+    /// [`BiomeMap::weights`] at a position in the table
+    pub fn weights_at_climate(&self, climate: Vec2) -> Vec<(&B, f32)> {
+        let (column, row) = self.cell(climate);
+        let size = self.size();
+        let columns = axis_weights(climate.x, column, size.0, self.blend);
+        let rows = axis_weights(climate.y, row, size.1, self.blend);
+
+        let mut weights: Vec<(&B, f32)> = Vec::with_capacity(4);
+        for (row, row_weight) in rows {
+            for (column, column_weight) in columns {
+                let weight = row_weight * column_weight;
+                if weight == 0.0 {
+                    continue;
+                }
+                let biome = &self.table[row][column];
+                match weights.iter_mut().find(|(b, _)| *b == biome) {
+                    Some((_, w)) => *w += weight,
+                    None => weights.push((biome, weight)),
+                }
+            }
+        }
+        weights.sort_by(|a, b| b.1.total_cmp(&a.1));
+        weights
+    }
+
+    /// Columns and rows of the table
+    fn size(&self) -> (usize, usize) {
+        (self.table[0].len(), self.table.len())
+    }
+
+    fn cell(&self, climate: Vec2) -> (usize, usize) {
+        let (columns, rows) = self.size();
+        let index = |t: f32, n: usize| ((t * n as f32) as usize).min(n - 1);
+        (index(climate.x, columns), index(climate.y, rows))
+    }
+}
+
+/// The weights of a cell and the neighbor closest to `t` along one axis of
+/// the table
+fn axis_weights(t: f32, cell: usize, cells: usize, blend: f32) -> [(usize, f32); 2] {
+    let local = t * cells as f32 - cell as f32;
+    let (neighbor, edge_distance) = if local < 0.5 {
+        (cell.checked_sub(1), local)
+    } else {
+        (Some(cell + 1).filter(|n| *n < cells), 1.0 - local)
+    };
+    let Some(neighbor) = neighbor else {
+        return [(cell, 1.0), (cell, 0.0)];
+    };
+    let blend = blend.clamp(0.0, 0.5);
+    let share = if edge_distance >= blend {
+        0.0
+    } else {
+        let x = 1.0 - edge_distance / blend;
+        0.5 * x * x * (3.0 - 2.0 * x)
+    };
+    [(cell, 1.0 - share), (neighbor, share)]
+}
+
+#[cfg(feature = "image")]
+mod image {
+    use bevy::{
+        asset::RenderAssetUsages,
+        color::{Color, ColorToPacked, LinearRgba, Srgba},
+        image::Image,
+        math::{Rect, UVec2, Vec2},
+        render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    use super::BiomeMap;
+
+    impl<B: PartialEq> BiomeMap<B> {
+        /// Renders the biomes in `region` into a `size` image, blending the
+        /// colors of neighboring biomes with [`BiomeMap::weights`], for
+        /// debugging and tuning the layers
+        ///
+        /// The first row of the image is the top of the region, at
+        /// `region.max.y`.
+        pub fn debug_image(&self, region: Rect, size: UVec2, color: impl Fn(&B) -> Color) -> Image {
+            let data = (0..size.y)
+                .flat_map(|y| (0..size.x).map(move |x| UVec2::new(x, y)))
+                .flat_map(|texel| {
+                    let t = (texel.as_vec2() + 0.5) / size.as_vec2();
+                    let pos = region.min + Vec2::new(t.x, 1.0 - t.y) * region.size();
+                    let blended = self
+                        .weights(pos)
+                        .into_iter()
+                        .fold(LinearRgba::NONE, |sum, (biome, weight)| {
+                            sum + LinearRgba::from(color(biome)) * weight
+                        });
+                    Srgba::from(blended).to_u8_array()
+                })
+                .collect();
+            Image::new(
+                Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                data,
+                TextureFormat::Rgba8UnormSrgb,
+                RenderAssetUsages::default(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Biome {
+        Tundra,
+        Desert,
+        Forest,
+    }
+    use Biome::*;
+
+    fn map() -> BiomeMap<Biome> {
+        BiomeMap::new(
+            BiomeAxis::new("temperature", NoiseLayer::new(0.01).with_seed(1.0)),
+            BiomeAxis::new("moisture", NoiseLayer::new(0.01).with_seed(2.0)),
+            vec![vec![Tundra, Desert, Desert], vec![Tundra, Forest, Forest]],
+        )
+    }
+
+    #[test]
+    fn looks_up_table() {
+        let map = map();
+        assert_eq!(*map.biome_at_climate(vec2(0.1, 0.1)), Tundra);
+        assert_eq!(*map.biome_at_climate(vec2(0.5, 0.2)), Desert);
+        assert_eq!(*map.biome_at_climate(vec2(1.0, 1.0)), Forest);
+        assert!(map.sample_layer("moisture", Vec2::ZERO).is_some());
+        assert!(map.sample_layer("altitude", Vec2::ZERO).is_none());
+    }
+
+    #[test]
+    fn weights_blend_at_edges() {
+        let map = map();
+        // in the middle of a cell
+        assert_eq!(
+            map.weights_at_climate(vec2(1.0 / 6.0, 0.25)),
+            [(&Tundra, 1.0)]
+        );
+        // on the edge between tundra and desert
+        let weights = map.weights_at_climate(vec2(1.0 / 3.0, 0.1));
+        assert_eq!(weights.len(), 2);
+        assert!((weights[0].1 - 0.5).abs() < 1e-5);
+        // the two desert cells merge
+        assert_eq!(
+            map.weights_at_climate(vec2(2.0 / 3.0, 0.1)),
+            [(&Desert, 1.0)]
+        );
+    }
+
+    #[test]
+    fn weights_sum_to_one() {
+        let map = map();
+        for i in 0..200 {
+            let pos = vec2(i as f32 * 3.1, i as f32 * -1.7);
+            let weights = map.weights(pos);
+            let sum: f32 = weights.iter().map(|(_, w)| w).sum();
+            assert!((sum - 1.0).abs() < 1e-5);
+            assert!(weights.iter().any(|(b, _)| *b == map.biome(pos)));
+        }
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn debug_image_uses_biome_colors() {
+        use bevy::{
+            color::{Color, palettes::css},
+            math::{Rect, UVec2},
+        };
+
+        let map = BiomeMap::new(
+            BiomeAxis::new("temperature", NoiseLayer::new(0.01)),
+            BiomeAxis::new("moisture", NoiseLayer::constant(0.0)),
+            vec![vec![Tundra]],
+        );
+        let image = map.debug_image(Rect::new(0.0, 0.0, 10.0, 10.0), UVec2::new(4, 2), |_| {
+            Color::from(css::RED)
+        });
+        assert_eq!(image.size(), UVec2::new(4, 2));
+        assert_eq!(image.data.as_deref().unwrap()[..4], [255, 0, 0, 255]);
+    }
+}
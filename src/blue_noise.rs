@@ -0,0 +1,316 @@
+use bevy::{
+    math::{IVec2, Rect, UVec2, Vec2, ivec2},
+    reflect::Reflect,
+};
+
+use crate::rng::Rng;
+
+/// A tileable blue noise threshold map, made with the void-and-cluster
+/// method
+///
+/// Every texel gets a unique value between 0 and 1, and the texels below any
+/// threshold are spread out evenly, with no clumps and no visible grid, also
+/// across the edges where the map repeats. Repeat it over an infinite world
+/// and keep the texels below a density to place objects, see
+/// [`BlueNoise::points`].
+///
+/// Generating takes time quadratic in the number of texels, every texel is
+/// placed by scanning and updating the whole map. 64x64 takes about a tenth
+/// of a second in release builds, and each doubling of the size takes 16
+/// times longer. Generate it once and keep it around.
+///
+/// ```
+/// # use bevy::math::{Rect, UVec2};
+/// # use noisy_bevy::{BlueNoise, simplex_noise_2d};
+/// let blue_noise = BlueNoise::new(UVec2::splat(32), 4);
+/// let stars = blue_noise.points(Rect::new(0.0, 0.0, 100.0, 100.0), 1.0, |p| {
+///     0.1 + 0.05 * simplex_noise_2d(p * 0.02)
+/// });
+/// ```
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub struct BlueNoise {
+    size: UVec2,
+    values: Vec<f32>,
+}
+
+impl BlueNoise {
+    /// Generates a `size` blue noise map, different for each seed
+    ///
+    /// # Panics
+    ///
+    /// If `size` is zero along either axis.
+    pub fn new(size: UVec2, seed: u32) -> Self {
+        assert!(
+            size.cmpgt(UVec2::ZERO).all(),
+            "BlueNoise size must be positive, got {size}"
+        );
+        let ranks = void_and_cluster(size, seed);
+        let count = ranks.len() as f32;
+        Self {
+            size,
+            values: ranks.into_iter().map(|rank| rank as f32 / count).collect(),
+        }
+    }
+
+    /// The size of the map in texels
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// The values of the map, row by row
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// The value at a texel, wrapping around the edges, in `[0, 1)`
+    pub fn get(&self, texel: IVec2) -> f32 {
+        let texel = texel.rem_euclid(self.size.as_ivec2()).as_uvec2();
+        self.values[(texel.y * self.size.x + texel.x) as usize]
+    }
+
+    /// The centers of the texels in `region` where the map is below `density`,
+    /// with the map repeated every `texel_size` world units
+    ///
+    /// `density` is the fraction of texels that get a point, between 0 and 1,
+    /// and may vary with position. Like [`PoissonDisk`](crate::PoissonDisk),
+    /// `region` includes its minimum edges but not its maximum edges, and the
+    /// points don't depend on how the world is split into regions.
+    pub fn points(
+        &self,
+        region: Rect,
+        texel_size: f32,
+        mut density: impl FnMut(Vec2) -> f32,
+    ) -> Vec<Vec2> {
+        let min = (region.min / texel_size - 0.5).ceil().as_ivec2();
+        let max = (region.max / texel_size - 0.5).ceil().as_ivec2();
+        let mut points = Vec::new();
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                let texel = ivec2(x, y);
+                let center = (texel.as_vec2() + 0.5) * texel_size;
+                if self.get(texel) < density(center) {
+                    points.push(center);
+                }
+            }
+        }
+        points
+    }
+}
+
+#[cfg(feature = "image")]
+mod image {
+    use bevy::{
+        asset::RenderAssetUsages,
+        image::Image,
+        render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    use super::BlueNoise;
+
+    impl BlueNoise {
+        /// The map as a linear `R8Unorm` [`Image`], for dithering and
+        /// thresholding in shaders
+        ///
+        /// Use a repeating sampler to tile it.
+        pub fn to_image(&self) -> Image {
+            let data = self
+                .values
+                .iter()
+                .map(|value| (value * 256.0) as u8)
+                .collect();
+            Image::new(
+                Extent3d {
+                    width: self.size.x,
+                    height: self.size.y,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                data,
+                TextureFormat::R8Unorm,
+                RenderAssetUsages::default(),
+            )
+        }
+    }
+}
+
+/// Gaussian width of the energy filter, in texels, from Ulichney's paper
+const SIGMA: f32 = 1.5;
+
+/// Ranks every texel from 0 to the texel count, see "The void-and-cluster
+/// method for dither array generation" by Robert Ulichney
+fn void_and_cluster(size: UVec2, seed: u32) -> Vec<u32> {
+    let count = (size.x * size.y) as usize;
+    let mut energy = Energy::new(size);
+    let mut ones = vec![false; count];
+    let mut rng = Rng::new(seed);
+
+    // a random initial pattern, with a tenth of the texels set
+    let initial = (count / 10).max(1);
+    let mut set = 0;
+    while set < initial {
+        let i = (rng.next_u32() as usize) % count;
+        if !ones[i] {
+            ones[i] = true;
+            energy.add(i, 1.0);
+            set += 1;
+        }
+    }
+
+    // even out the initial pattern by moving the tightest cluster into the
+    // largest void, until that doesn't change anything
+    for _ in 0..count {
+        let cluster = energy.tightest_cluster(&ones);
+        ones[cluster] = false;
+        energy.add(cluster, -1.0);
+        let void = energy.largest_void(&ones);
+        ones[void] = true;
+        energy.add(void, 1.0);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0; count];
+
+    // rank the initial pattern by removing the tightest clusters first
+    let mut removing = ones.clone();
+    let mut removing_energy = energy.clone();
+    for rank in (0..initial).rev() {
+        let cluster = removing_energy.tightest_cluster(&removing);
+        removing[cluster] = false;
+        removing_energy.add(cluster, -1.0);
+        ranks[cluster] = rank as u32;
+    }
+
+    // rank the rest by filling the largest voids first
+    for rank in initial..count {
+        let void = energy.largest_void(&ones);
+        ones[void] = true;
+        energy.add(void, 1.0);
+        ranks[void] = rank as u32;
+    }
+
+    ranks
+}
+
+/// The Gaussian filtered pattern, wrapping around the edges
+#[derive(Clone)]
+struct Energy {
+    size: UVec2,
+    /// The filter at every wrapped offset
+    kernel: Vec<f32>,
+    values: Vec<f32>,
+}
+
+impl Energy {
+    fn new(size: UVec2) -> Self {
+        let count = (size.x * size.y) as usize;
+        let kernel = (0..count)
+            .map(|i| {
+                let offset = UVec2::new(i as u32 % size.x, i as u32 / size.x);
+                // the shortest way around the torus
+                let wrapped = offset.min(size - offset).as_vec2();
+                (-wrapped.length_squared() / (2.0 * SIGMA * SIGMA)).exp()
+            })
+            .collect();
+        Self {
+            size,
+            kernel,
+            values: vec![0.0; count],
+        }
+    }
+
+    fn add(&mut self, texel: usize, sign: f32) {
+        let (w, h) = (self.size.x as usize, self.size.y as usize);
+        let (tx, ty) = (texel % w, texel / w);
+        for y in 0..h {
+            let dy = (y + h - ty) % h;
+            for x in 0..w {
+                let dx = (x + w - tx) % w;
+                self.values[y * w + x] += sign * self.kernel[dy * w + dx];
+            }
+        }
+    }
+
+    /// The set texel with the most energy, the first one on ties
+    fn tightest_cluster(&self, ones: &[bool]) -> usize {
+        let mut best = None;
+        for (i, value) in self.values.iter().enumerate() {
+            if ones[i] && best.is_none_or(|(_, best)| *value > best) {
+                best = Some((i, *value));
+            }
+        }
+        best.expect("no texel to remove").0
+    }
+
+    /// The unset texel with the least energy, the first one on ties
+    fn largest_void(&self, ones: &[bool]) -> usize {
+        let mut best = None;
+        for (i, value) in self.values.iter().enumerate() {
+            if !ones[i] && best.is_none_or(|(_, best)| *value < best) {
+                best = Some((i, *value));
+            }
+        }
+        best.expect("no texel to fill").0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn values_are_unique_ranks() {
+        let blue_noise = BlueNoise::new(UVec2::new(16, 8), 1);
+        let mut values = blue_noise.values().to_vec();
+        values.sort_by(f32::total_cmp);
+        for (i, value) in values.into_iter().enumerate() {
+            assert_eq!(value, i as f32 / 128.0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "BlueNoise size must be positive")]
+    fn rejects_empty_size() {
+        BlueNoise::new(UVec2::new(8, 0), 1);
+    }
+
+    #[test]
+    fn tiles() {
+        let blue_noise = BlueNoise::new(UVec2::splat(8), 2);
+        assert_eq!(blue_noise.get(ivec2(-1, 9)), blue_noise.get(ivec2(7, 1)));
+    }
+
+    #[test]
+    fn low_values_are_spread_out() {
+        let size = 32;
+        let blue_noise = BlueNoise::new(UVec2::splat(size), 3);
+        let lowest: Vec<IVec2> = (0..size * size)
+            .filter(|i| blue_noise.values()[*i as usize] < 0.1)
+            .map(|i| ivec2((i % size) as i32, (i / size) as i32))
+            .collect();
+        // no two of the lowest tenth touch, even across the edges
+        for (i, a) in lowest.iter().enumerate() {
+            for b in &lowest[i + 1..] {
+                let d = (*a - *b).abs();
+                let d = d.min(IVec2::splat(size as i32) - d);
+                assert!(d.max_element() > 1, "{a} and {b} touch");
+            }
+        }
+    }
+
+    #[test]
+    fn points_match_across_regions() {
+        let blue_noise = BlueNoise::new(UVec2::splat(8), 4);
+        let density = |_| 0.25;
+        let whole = blue_noise.points(Rect::new(-4.0, 0.0, 12.0, 8.0), 0.5, density);
+        let mut halves = blue_noise.points(Rect::new(-4.0, 0.0, 4.0, 8.0), 0.5, density);
+        halves.extend(blue_noise.points(Rect::new(4.0, 0.0, 12.0, 8.0), 0.5, density));
+        let by_position = |a: &Vec2, b: &Vec2| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x));
+        let (mut whole, mut halves) = (whole, halves);
+        whole.sort_by(by_position);
+        halves.sort_by(by_position);
+        assert_eq!(whole, halves);
+        assert_eq!(whole.len(), 32 * 16 / 4);
+    }
+}
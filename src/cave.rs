@@ -0,0 +1,297 @@
+use std::collections::VecDeque;
+
+use bevy::math::{IVec2, UVec2, ivec2};
+
+use crate::TileGrid;
+
+/// How [`generate_cave`] turns noise into walls and open space
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CaveSettings {
+    /// Tiles where the noise is above the threshold start out as walls
+    pub threshold: f32,
+    /// Number of cellular automata passes smoothing the walls
+    pub smoothing_passes: u32,
+    /// An open tile becomes a wall with at least this many walls among its 8
+    /// neighbors
+    pub birth_limit: u8,
+    /// A wall stays a wall with at least this many walls among its 8
+    /// neighbors
+    pub survival_limit: u8,
+    /// Open regions with fewer tiles are filled in
+    pub min_cave_size: usize,
+    /// Carve tunnels between the remaining open regions, so every open tile
+    /// can be reached from every other
+    pub connect: bool,
+    /// Tunnels clear the tiles within this distance of their center line
+    pub tunnel_radius: u32,
+    /// Keep the outermost tiles of the grid solid, so caves are closed
+    pub solid_border: bool,
+}
+
+impl Default for CaveSettings {
+    /// The classic 4-5 rule with a few smoothing passes
+    fn default() -> Self {
+        Self {
+            threshold: 0.0,
+            smoothing_passes: 4,
+            birth_limit: 5,
+            survival_limit: 4,
+            min_cave_size: 8,
+            connect: true,
+            tunnel_radius: 1,
+            solid_border: true,
+        }
+    }
+}
+
+/// Generates a cave from the noise returned by `noise` for each tile from
+/// `origin` to `origin + size`, `true` for walls
+///
+/// The noise is thresholded, smoothed with cellular automata, cleaned of tiny
+/// pockets and, if [`CaveSettings::connect`] is set, the open regions are
+/// joined with tunnels. Tiles outside the grid count as walls. The result
+/// only depends on the settings and the noise, so seeded noise gives the same
+/// cave everywhere.
+///
+/// For 3d noise, sample a slice, or several at different depths for the
+/// floors of a dungeon.
+///
+/// ```
+/// # use bevy::math::{IVec2, UVec2};
+/// # use noisy_bevy::{CaveSettings, generate_cave, simplex_noise_3d};
+/// let cave = generate_cave(
+///     &CaveSettings::default(),
+///     IVec2::ZERO,
+///     UVec2::new(64, 48),
+///     |tile| simplex_noise_3d(tile.as_vec2().extend(3.0) * 0.15),
+/// );
+/// // smooth borders for a wall mesh or autotiling
+/// let masks = cave.marching_squares(|wall| *wall);
+/// ```
+pub fn generate_cave(
+    settings: &CaveSettings,
+    origin: IVec2,
+    size: UVec2,
+    mut noise: impl FnMut(IVec2) -> f32,
+) -> TileGrid<bool> {
+    let on_border = |tile: IVec2| {
+        let offset = tile - origin;
+        offset.cmpeq(IVec2::ZERO).any() || offset.cmpeq(size.as_ivec2() - 1).any()
+    };
+    let border = |tile: IVec2| settings.solid_border && on_border(tile);
+
+    let mut cave = TileGrid::from_fn(origin, size, |tile| {
+        border(tile) || noise(tile) > settings.threshold
+    });
+
+    for _ in 0..settings.smoothing_passes {
+        cave = cave.map(|tile, wall| {
+            let walls = (-1..=1)
+                .flat_map(|y| (-1..=1).map(move |x| ivec2(x, y)))
+                .filter(|offset| *offset != IVec2::ZERO)
+                .filter(|offset| cave.get(tile + *offset).is_none_or(|wall| *wall))
+                .count() as u8;
+            let limit = if *wall {
+                settings.survival_limit
+            } else {
+                settings.birth_limit
+            };
+            border(tile) || walls >= limit
+        });
+    }
+
+    let mut regions = open_regions(&cave);
+    for region in &regions {
+        if region.len() < settings.min_cave_size {
+            for tile in region {
+                *cave.get_mut(*tile).unwrap() = true;
+            }
+        }
+    }
+    regions.retain(|region| region.len() >= settings.min_cave_size);
+
+    if settings.connect && !regions.is_empty() {
+        // grow the largest cave by tunneling to the closest other cave, until
+        // there's only one
+        regions.sort_by_key(|region| std::cmp::Reverse(region.len()));
+        let mut connected = edge_tiles(&cave, &regions.remove(0));
+        while !regions.is_empty() {
+            let mut closest = (u32::MAX, 0, IVec2::ZERO, IVec2::ZERO);
+            for (i, region) in regions.iter().enumerate() {
+                for to in edge_tiles(&cave, region) {
+                    for from in &connected {
+                        let distance = (to - *from).length_squared() as u32;
+                        if distance < closest.0 {
+                            closest = (distance, i, *from, to);
+                        }
+                    }
+                }
+            }
+            let (_, i, from, to) = closest;
+            let tunnel = carve_tunnel(&mut cave, from, to, settings.tunnel_radius, border);
+            connected.extend(tunnel);
+            connected.extend(edge_tiles(&cave, &regions.remove(i)));
+        }
+    }
+
+    cave
+}
+
+/// The 4-connected regions of open tiles, in row order of their first tile
+fn open_regions(cave: &TileGrid<bool>) -> Vec<Vec<IVec2>> {
+    let mut seen = cave.map(|_, wall| *wall);
+    let mut regions = Vec::new();
+    for (start, wall) in cave.iter() {
+        if *wall || *seen.get(start).unwrap() {
+            continue;
+        }
+        *seen.get_mut(start).unwrap() = true;
+        let mut region = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(tile) = queue.pop_front() {
+            region.push(tile);
+            for offset in [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y] {
+                if let Some(seen) = seen.get_mut(tile + offset)
+                    && !*seen
+                {
+                    *seen = true;
+                    queue.push_back(tile + offset);
+                }
+            }
+        }
+        regions.push(region);
+    }
+    regions
+}
+
+/// The tiles of a region next to a wall, the only ones a shortest tunnel can
+/// start from
+fn edge_tiles(cave: &TileGrid<bool>, region: &[IVec2]) -> Vec<IVec2> {
+    region
+        .iter()
+        .copied()
+        .filter(|tile| {
+            [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y]
+                .into_iter()
+                .any(|offset| cave.get(*tile + offset).is_none_or(|wall| *wall))
+        })
+        .collect()
+}
+
+/// Clears the tiles within `radius` of the line from `from` to `to`, except
+/// `keep` ones, returning the cleared tiles
+fn carve_tunnel(
+    cave: &mut TileGrid<bool>,
+    from: IVec2,
+    to: IVec2,
+    radius: u32,
+    keep: impl Fn(IVec2) -> bool,
+) -> Vec<IVec2> {
+    let radius = radius as i32;
+    let mut carved = Vec::new();
+    let mut carve = |center: IVec2| {
+        for y in -radius..=radius {
+            for x in -radius..=radius {
+                let tile = center + ivec2(x, y);
+                if x * x + y * y > radius * radius || keep(tile) {
+                    continue;
+                }
+                if let Some(wall) = cave.get_mut(tile)
+                    && *wall
+                {
+                    *wall = false;
+                    carved.push(tile);
+                }
+            }
+        }
+    };
+
+    // Bresenham's line, stepping one axis at a time so the tunnel stays
+    // 4-connected even with a radius of 0
+    let delta = (to - from).abs();
+    let step = (to - from).signum();
+    let mut error = delta.x - delta.y;
+    let mut tile = from;
+    carve(tile);
+    while tile != to {
+        if 2 * error > -delta.y && tile.x != to.x {
+            error -= delta.y;
+            tile.x += step.x;
+        } else {
+            error += delta.x;
+            tile.y += step.y;
+        }
+        carve(tile);
+    }
+    carved
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simplex_noise_2d_seeded;
+
+    fn cave(settings: &CaveSettings) -> TileGrid<bool> {
+        generate_cave(settings, ivec2(-20, 5), UVec2::new(60, 40), |tile| {
+            simplex_noise_2d_seeded(tile.as_vec2() * 0.2, 3.0)
+        })
+    }
+
+    #[test]
+    fn is_deterministic_with_solid_border() {
+        let settings = CaveSettings::default();
+        let cave = cave(&settings);
+        assert_eq!(cave, self::cave(&settings));
+        for (tile, wall) in cave.iter() {
+            let offset = tile - cave.origin();
+            if offset.x == 0 || offset.y == 0 || offset.x == 59 || offset.y == 39 {
+                assert!(wall);
+            }
+        }
+        assert!(cave.tiles().iter().any(|wall| !wall));
+    }
+
+    #[test]
+    fn smoothing_removes_lone_tiles() {
+        let settings = CaveSettings {
+            connect: false,
+            min_cave_size: 0,
+            ..Default::default()
+        };
+        let cave = cave(&settings);
+        for (tile, wall) in cave.iter() {
+            let neighbors = (-1..=1)
+                .flat_map(|y| (-1..=1).map(move |x| ivec2(x, y)))
+                .filter(|offset| *offset != IVec2::ZERO)
+                .filter(|offset| cave.get(tile + *offset).is_none_or(|w| w == wall))
+                .count();
+            assert!(neighbors > 0, "{tile} is alone");
+        }
+    }
+
+    #[test]
+    fn connects_all_caves() {
+        let unconnected = cave(&CaveSettings {
+            connect: false,
+            ..Default::default()
+        });
+        assert!(open_regions(&unconnected).len() > 1);
+        for radius in [0, 1, 2] {
+            let connected = cave(&CaveSettings {
+                tunnel_radius: radius,
+                ..Default::default()
+            });
+            assert_eq!(open_regions(&connected).len(), 1);
+        }
+    }
+
+    #[test]
+    fn fills_small_caves() {
+        let cave = cave(&CaveSettings {
+            connect: false,
+            min_cave_size: 30,
+            ..Default::default()
+        });
+        assert!(open_regions(&cave).iter().all(|region| region.len() >= 30));
+    }
+}
@@ -0,0 +1,231 @@
+use bevy::{
+    math::{Vec3, vec3},
+    reflect::Reflect,
+};
+
+use crate::fbm_simplex_3d_seeded;
+
+/// A layer of clouds between two heights, with fbm density drifting with the
+/// wind
+///
+/// The same density is raymarched by the `noisy_bevy::cloud` shader module and
+/// `CloudMaterial`, so gameplay can ask whether something is hidden by the
+/// clouds the player sees.
+///
+/// ```
+/// # use bevy::math::{Vec3, vec3};
+/// # use noisy_bevy::CloudLayer;
+/// let clouds = CloudLayer {
+///     coverage: 0.6,
+///     wind: vec3(5.0, 0.0, 2.0),
+///     ..Default::default()
+/// };
+/// let time = 12.0;
+/// let visibility = clouds.transmittance(Vec3::ZERO, vec3(100.0, 800.0, 50.0), time, 32);
+/// let spotted = visibility > 0.5;
+/// ```
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct CloudLayer {
+    /// World height of the bottom of the clouds
+    pub bottom: f32,
+    /// World height of the top of the clouds
+    pub top: f32,
+    /// How much of the sky is covered, between 0 and 1
+    pub coverage: f32,
+    /// Extinction of the thickest clouds, per world unit
+    pub density: f32,
+    /// Noise periods per world unit
+    pub frequency: f32,
+    /// Number of fbm octaves
+    pub octaves: usize,
+    /// Frequency multiplier between octaves
+    pub lacunarity: f32,
+    /// Amplitude multiplier between octaves
+    pub gain: f32,
+    /// Layers with different seeds are uncorrelated
+    pub seed: f32,
+    /// How far the clouds drift per second, in world units
+    pub wind: Vec3,
+}
+
+impl Default for CloudLayer {
+    /// Half covered skies between 500 and 800 units up
+    fn default() -> Self {
+        Self {
+            bottom: 500.0,
+            top: 800.0,
+            coverage: 0.5,
+            density: 0.02,
+            frequency: 0.002,
+            octaves: 5,
+            lacunarity: 2.0,
+            gain: 0.5,
+            seed: 0.0,
+            wind: vec3(10.0, 0.0, 0.0),
+        }
+    }
+}
+
+impl CloudLayer {
+    /// Extinction per world unit at a world position, 0 outside the clouds
+    ///
+    /// `time` is in seconds, the shaders use bevy's global time, which is
+    /// wrapped like
+    /// [`Time::elapsed_secs_wrapped`](bevy::time::Time::elapsed_secs_wrapped).
+    pub fn density(&self, pos: Vec3, time: f32) -> f32 {
+        if pos.y <= self.bottom || pos.y >= self.top || self.coverage <= 0.0 {
+            return 0.0;
+        }
+        // round off the bottom and top of the clouds
+        let h = (pos.y - self.bottom) / (self.top - self.bottom);
+        let shape = 4.0 * h * (1.0 - h);
+        let p = (pos - self.wind * time) * self.frequency;
+        let noise = fbm_simplex_3d_seeded(
+            p,
+            self.octaves,
+            self.lacunarity,
+            self.gain,
+            Vec3::splat(self.seed),
+        ) * 0.5
+            + 0.5;
+        let cloud = (noise * shape + self.coverage - 1.0) / self.coverage;
+        cloud.clamp(0.0, 1.0) * self.density
+    }
+
+    /// How much light gets through the clouds between two world positions,
+    /// from 1 for clear skies to 0 when completely blocked
+    ///
+    /// The density is sampled at `steps` points along the part of the
+    /// segment inside the layer, like the shaders do.
+    pub fn transmittance(&self, from: Vec3, to: Vec3, time: f32, steps: u32) -> f32 {
+        let Some((start, end)) = self.clip(from, to) else {
+            return 1.0;
+        };
+        let step = (end - start) / steps.max(1) as f32;
+        let length = step.length();
+        let optical_depth: f32 = (0..steps.max(1))
+            .map(|i| self.density(start + step * (i as f32 + 0.5), time) * length)
+            .sum();
+        (-optical_depth).exp()
+    }
+
+    /// The part of the segment between the bottom and top of the layer
+    fn clip(&self, from: Vec3, to: Vec3) -> Option<(Vec3, Vec3)> {
+        let delta = to - from;
+        let (mut t0, mut t1) = (0.0_f32, 1.0_f32);
+        if delta.y == 0.0 {
+            if from.y <= self.bottom || from.y >= self.top {
+                return None;
+            }
+        } else {
+            let a = (self.bottom - from.y) / delta.y;
+            let b = (self.top - from.y) / delta.y;
+            t0 = t0.max(a.min(b));
+            t1 = t1.min(a.max(b));
+        }
+        (t0 < t1).then(|| (from + delta * t0, from + delta * t1))
+    }
+}
+
+#[cfg(all(feature = "gpu", feature = "image"))]
+mod gpu {
+    use bevy::{math::Vec3, render::render_resource::ShaderType};
+
+    use super::CloudLayer;
+
+    /// The GPU representation of [`CloudLayer`], `CloudLayer` in the
+    /// `noisy_bevy::cloud` shader module
+    #[derive(Clone, Copy, Debug, Default, ShaderType)]
+    pub struct CloudLayerUniform {
+        /// See [`CloudLayer::wind`]
+        pub wind: Vec3,
+        /// See [`CloudLayer::bottom`]
+        pub bottom: f32,
+        /// See [`CloudLayer::top`]
+        pub top: f32,
+        /// See [`CloudLayer::coverage`]
+        pub coverage: f32,
+        /// See [`CloudLayer::density`]
+        pub density: f32,
+        /// See [`CloudLayer::frequency`]
+        pub frequency: f32,
+        /// See [`CloudLayer::octaves`]
+        pub octaves: i32,
+        /// See [`CloudLayer::lacunarity`]
+        pub lacunarity: f32,
+        /// See [`CloudLayer::gain`]
+        pub gain: f32,
+        /// See [`CloudLayer::seed`]
+        pub seed: f32,
+    }
+
+    impl From<&CloudLayer> for CloudLayerUniform {
+        fn from(layer: &CloudLayer) -> Self {
+            Self {
+                wind: layer.wind,
+                bottom: layer.bottom,
+                top: layer.top,
+                coverage: layer.coverage,
+                density: layer.density,
+                frequency: layer.frequency,
+                octaves: layer.octaves as i32,
+                lacunarity: layer.lacunarity,
+                gain: layer.gain,
+                seed: layer.seed,
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "gpu", feature = "image"))]
+pub use gpu::*;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn only_inside_the_layer() {
+        let clouds = CloudLayer {
+            coverage: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(clouds.density(vec3(0.0, 499.0, 0.0), 0.0), 0.0);
+        assert_eq!(clouds.density(vec3(0.0, 801.0, 0.0), 0.0), 0.0);
+        assert!(clouds.density(vec3(0.0, 650.0, 0.0), 0.0) > 0.0);
+        // below the clouds, looking sideways
+        let clear = clouds.transmittance(Vec3::ZERO, vec3(1000.0, 100.0, 0.0), 0.0, 8);
+        assert_eq!(clear, 1.0);
+        let cloudy = clouds.transmittance(Vec3::ZERO, vec3(0.0, 1000.0, 0.0), 0.0, 8);
+        assert!(cloudy < 0.5, "{cloudy}");
+    }
+
+    #[test]
+    fn coverage_and_wind() {
+        let clouds = CloudLayer::default();
+        let points = (0..200).map(|i| vec3(i as f32 * 37.0, 650.0, i as f32 * -11.0));
+        let covered = |clouds: CloudLayer| {
+            points
+                .clone()
+                .filter(|p| clouds.density(*p, 0.0) > 0.0)
+                .count()
+        };
+        let none = covered(CloudLayer {
+            coverage: 0.0,
+            ..clouds
+        });
+        let some = covered(clouds);
+        let all = covered(CloudLayer {
+            coverage: 1.0,
+            ..clouds
+        });
+        assert!(none == 0 && some > 0 && some < all, "{none} {some} {all}");
+
+        // the clouds drift with the wind
+        let p = vec3(120.0, 650.0, 40.0);
+        assert_eq!(
+            clouds.density(p, 0.0),
+            clouds.density(p + clouds.wind * 3.0, 3.0)
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use bevy::{
+    asset::{Asset, Handle, uuid_handle},
+    color::{Color, ColorToComponents, LinearRgba, palettes::css},
+    material::AlphaMode,
+    math::{Vec3, Vec4, vec3},
+    mesh::MeshVertexBufferLayoutRef,
+    pbr::{Material, MaterialPipeline, MaterialPipelineKey},
+    reflect::Reflect,
+    render::render_resource::{
+        AsBindGroup, RenderPipelineDescriptor, ShaderType, SpecializedMeshPipelineError,
+    },
+    shader::{Shader, ShaderRef},
+};
+
+use crate::{CloudLayer, CloudLayerUniform};
+
+/// Unlit sky with a raymarched [`CloudLayer`], seen from the camera through
+/// the mesh
+///
+/// Put it on a large sphere around the camera for a skybox, or on a quad
+/// covering the screen. Both sides of the mesh are drawn, and only the
+/// direction from the camera to the surface matters, so the mesh can be any
+/// shape. Keep it closer than the far plane of the camera.
+///
+/// Registered by [`NoisyShaderPlugin`](crate::NoisyShaderPlugin) when
+/// `materials` is set. The clouds match [`CloudLayer::density`] and
+/// [`CloudLayer::transmittance`] on the CPU.
+///
+/// ```
+/// # use bevy::{prelude::*, color::palettes::css};
+/// # use noisy_bevy::{CloudLayer, CloudMaterial};
+/// fn setup(
+///     mut commands: Commands,
+///     mut meshes: ResMut<Assets<Mesh>>,
+///     mut materials: ResMut<Assets<CloudMaterial>>,
+/// ) {
+///     commands.spawn((
+///         Camera3d::default(),
+///         children![(
+///             Mesh3d(meshes.add(Sphere::new(900.0))),
+///             MeshMaterial3d(materials.add(CloudMaterial {
+///                 layer: CloudLayer {
+///                     coverage: 0.6,
+///                     ..default()
+///                 },
+///                 ..default()
+///             })),
+///         )],
+///     ));
+/// }
+/// ```
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+#[uniform(0, CloudMaterialUniform)]
+pub struct CloudMaterial {
+    /// The clouds
+    pub layer: CloudLayer,
+    /// Color of the sky straight up
+    pub zenith: Color,
+    /// Color of the sky at the horizon, and below it. Transparent sky colors
+    /// need [`AlphaMode::Blend`].
+    pub horizon: Color,
+    /// The direction the sunlight travels in, like the forward direction of a
+    /// `DirectionalLight`
+    pub sun_direction: Vec3,
+    /// Light the clouds get from the sun
+    pub sun_color: Color,
+    /// Light the clouds get from the sky, also in their own shadow
+    pub ambient: Color,
+    /// Number of density samples along each view ray
+    pub steps: u32,
+    /// How far to look for clouds, in world units
+    pub max_distance: f32,
+    /// Set to blend when the sky colors are transparent
+    pub alpha_mode: AlphaMode,
+}
+
+impl Default for CloudMaterial {
+    /// White clouds in a blue sky, with the sun high up
+    fn default() -> Self {
+        Self {
+            layer: CloudLayer::default(),
+            zenith: css::DEEP_SKY_BLUE.into(),
+            horizon: css::LIGHT_BLUE.into(),
+            sun_direction: vec3(-0.3, -1.0, -0.4),
+            sun_color: Color::WHITE,
+            ambient: Color::srgb(0.45, 0.5, 0.6),
+            steps: 48,
+            max_distance: 20_000.0,
+            alpha_mode: AlphaMode::Opaque,
+        }
+    }
+}
+
+/// The GPU representation of [`CloudMaterial`], `CloudMaterial` in the
+/// `noisy_bevy/cloud_material.wgsl` shader
+#[derive(Clone, Default, ShaderType)]
+pub struct CloudMaterialUniform {
+    /// See [`CloudMaterial::layer`]
+    pub layer: CloudLayerUniform,
+    /// See [`CloudMaterial::zenith`], in linear rgba
+    pub zenith: Vec4,
+    /// See [`CloudMaterial::horizon`], in linear rgba
+    pub horizon: Vec4,
+    /// See [`CloudMaterial::sun_direction`]
+    pub sun_direction: Vec3,
+    /// See [`CloudMaterial::steps`]
+    pub steps: u32,
+    /// See [`CloudMaterial::sun_color`], in linear rgb
+    pub sun_color: Vec3,
+    /// See [`CloudMaterial::max_distance`]
+    pub max_distance: f32,
+    /// See [`CloudMaterial::ambient`], in linear rgb
+    pub ambient: Vec3,
+}
+
+impl From<&CloudMaterial> for CloudMaterialUniform {
+    fn from(material: &CloudMaterial) -> Self {
+        let linear = |color: Color| LinearRgba::from(color).to_vec4();
+        Self {
+            layer: (&material.layer).into(),
+            zenith: linear(material.zenith),
+            horizon: linear(material.horizon),
+            sun_direction: material.sun_direction,
+            steps: material.steps,
+            sun_color: linear(material.sun_color).truncate(),
+            max_distance: material.max_distance,
+            ambient: linear(material.ambient).truncate(),
+        }
+    }
+}
+
+impl Material for CloudMaterial {
+    fn fragment_shader() -> ShaderRef {
+        CLOUD_MATERIAL_SHADER_HANDLE.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        // seen from inside a skybox sphere
+        descriptor.primitive.cull_mode = None;
+        Ok(())
+    }
+}
+
+pub(crate) const CLOUD_MATERIAL_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("d89fceb6-28cd-4028-9f6e-4d320ee95a6f");
@@ -2,10 +2,88 @@ use bevy::math::{
     Vec2, Vec2Swizzles, Vec3, Vec3Swizzles, Vec4, Vec4Swizzles, bvec3, vec2, vec3, vec4,
 };
 
+fn permute_1(x: f32) -> f32 {
+    ((x * 34. + 1.) * x) % 289.
+}
+
+fn permute_2(x: Vec2) -> Vec2 {
+    (((x * 34.) + 1.) * x) % Vec2::splat(289.)
+}
+
 fn permute_3(x: Vec3) -> Vec3 {
     (((x * 34.) + 1.) * x) % Vec3::splat(289.)
 }
 
+// MIT License. © Stefan Gustavson, Johan Helsing
+/// Simplex noise in one dimension
+pub fn simplex_noise_1d(v: f32) -> f32 {
+    // first corner
+    let mut i = v.floor();
+    let x0 = v - i;
+    let x1 = x0 - 1.;
+
+    // permutations
+    i %= 289.;
+    let p = permute_2(i + vec2(0., 1.));
+
+    // gradients: 41 points uniformly over a line
+    let g = 2. * (p * (1. / 41.)).fract_gl() - 1.;
+
+    let mut t = Vec2::max(1. - vec2(x0 * x0, x1 * x1), Vec2::ZERO);
+    t *= t;
+    t *= t;
+
+    // compute final noise value at P
+    3.16 * Vec2::dot(t, g * vec2(x0, x1))
+}
+
+// MIT License. © Stefan Gustavson, Johan Helsing
+/// Simplex noise in one dimension
+pub fn simplex_noise_1d_seeded(v: f32, seed: f32) -> f32 {
+    // first corner
+    let mut i = v.floor();
+    let x0 = v - i;
+    let x1 = x0 - 1.;
+
+    // permutations
+    i %= 289.;
+    let mut p = permute_2(i + vec2(0., 1.));
+    p = permute_2(p + Vec2::splat(seed));
+
+    // gradients: 41 points uniformly over a line
+    let g = 2. * (p * (1. / 41.)).fract_gl() - 1.;
+
+    let mut t = Vec2::max(1. - vec2(x0 * x0, x1 * x1), Vec2::ZERO);
+    t *= t;
+    t *= t;
+
+    // compute final noise value at P
+    3.16 * Vec2::dot(t, g * vec2(x0, x1))
+}
+
+/// Scales gradients with squared length `r` to unit length
+///
+/// Uses a Taylor approximation of `1 / sqrt(r)`, unless the
+/// `high-quality-gradients` feature is enabled. Matches the
+/// `NOISY_HIGH_QUALITY_GRADIENTS` shader def.
+fn gradient_norm_3(r: Vec3) -> Vec3 {
+    if cfg!(feature = "high-quality-gradients") {
+        r.map(f32::sqrt).recip()
+    } else {
+        1.792_842_9 - 0.853_734_73 * r
+    }
+}
+
+/// Output scale of the 2d simplex noise
+///
+/// Exact normalization gives longer gradients than the approximation, so the
+/// scale is lowered to keep the output within -1..1.
+const SIMPLEX_2D_SCALE: f32 = if cfg!(feature = "high-quality-gradients") {
+    99.
+} else {
+    130.
+};
+
 // MIT License. © Ian McEwan, Stefan Gustavson, Munrocket, Johan Helsing
 /// Simplex noise in two dimensions
 pub fn simplex_noise_2d(v: Vec2) -> f32 {
@@ -39,13 +117,13 @@ pub fn simplex_noise_2d(v: Vec2) -> f32 {
     let h = x.abs() - 0.5;
     let ox = (x + 0.5).floor();
     let a0 = x - ox;
-    m *= 1.792_842_9 - 0.853_734_73 * (a0 * a0 + h * h);
+    m *= gradient_norm_3(a0 * a0 + h * h);
     let g = vec3(
         a0.x * x0.x + h.x * x0.y,
         a0.y * x12.x + h.y * x12.y,
         a0.z * x12.z + h.z * x12.w,
     );
-    130. * Vec3::dot(m, g)
+    SIMPLEX_2D_SCALE * Vec3::dot(m, g)
 }
 
 // MIT License. © Ian McEwan, Stefan Gustavson, Munrocket, Johan Helsing
@@ -96,7 +174,7 @@ pub fn simplex_noise_2d_seeded(v: Vec2, seed: f32) -> f32 {
 
     // normalize gradients implicitly by scaling m
     // approximation of: m *= inversesqrt(a0 * a0 + h * h);
-    m *= 1.792_842_9 - 0.853_734_73 * (a0 * a0 + h * h);
+    m *= gradient_norm_3(a0 * a0 + h * h);
     let g = vec3(
         a0.x * x0.x + h.x * x0.y,
         a0.y * x12.x + h.y * x12.y,
@@ -104,15 +182,19 @@ pub fn simplex_noise_2d_seeded(v: Vec2, seed: f32) -> f32 {
     );
 
     // compute final noise value at P
-    130. * Vec3::dot(m, g)
+    SIMPLEX_2D_SCALE * Vec3::dot(m, g)
 }
 
 fn permute_4(x: Vec4) -> Vec4 {
     ((x * 34. + 1.) * x) % Vec4::splat(289.)
 }
 
-fn taylor_inv_sqrt_4(r: Vec4) -> Vec4 {
-    1.792_842_9 - 0.853_734_73 * r
+fn gradient_norm_4(r: Vec4) -> Vec4 {
+    if cfg!(feature = "high-quality-gradients") {
+        r.map(f32::sqrt).recip()
+    } else {
+        1.792_842_9 - 0.853_734_73 * r
+    }
 }
 
 #[inline]
@@ -185,7 +267,7 @@ pub fn simplex_noise_3d(v: Vec3) -> f32 {
     let mut p3 = a1.zw().extend(h.w);
 
     // normalize gradients
-    let norm = taylor_inv_sqrt_4(vec4(
+    let norm = gradient_norm_4(vec4(
         Vec3::dot(p0, p0),
         Vec3::dot(p1, p1),
         Vec3::dot(p2, p2),
@@ -281,7 +363,7 @@ pub fn simplex_noise_3d_seeded(v: Vec3, seed: Vec3) -> f32 {
     let mut p3 = a1.zw().extend(h.w);
 
     // normalize gradients
-    let norm = taylor_inv_sqrt_4(vec4(
+    let norm = gradient_norm_4(vec4(
         Vec3::dot(p0, p0),
         Vec3::dot(p1, p1),
         Vec3::dot(p2, p2),
@@ -313,6 +395,255 @@ pub fn simplex_noise_3d_seeded(v: Vec3, seed: Vec3) -> f32 {
     )
 }
 
+/// One of the gradients spread over the surface of a 4d cross polytope
+fn gradient_4(j: f32, ip: Vec4) -> Vec4 {
+    let xyz = ((Vec3::splat(j) * ip.xyz()).fract_gl() * 7.).floor() * ip.z - 1.;
+    let p = xyz.extend(1.5 - xyz.abs().element_sum());
+    let s = Vec4::select(p.cmplt(Vec4::ZERO), Vec4::ONE, Vec4::ZERO);
+    (p.xyz() + (s.xyz() * 2. - 1.) * s.w).extend(p.w)
+}
+
+// MIT License. © Ian McEwan, Stefan Gustavson, Johan Helsing
+/// Simplex noise in four dimensions
+///
+/// Useful for animating 3d noise, with time along the fourth axis, see
+/// [`AnimatedNoise`](crate::AnimatedNoise).
+pub fn simplex_noise_4d(v: Vec4) -> f32 {
+    // (sqrt(5) - 1) / 4
+    const F4: f32 = 0.309_017;
+    // (5 - sqrt(5)) / 20 times 1, 2 and 3, and 4 * that - 1
+    const C: Vec4 = vec4(0.138_196_6, 0.276_393_2, 0.414_589_8, -0.447_213_6);
+
+    // first corner
+    let mut i = (v + Vec4::dot(v, Vec4::splat(F4))).floor();
+    let x0 = v - i + Vec4::dot(i, C.xxxx());
+
+    // other corners, ranking the coordinates by magnitude
+    let step = |edge: Vec3, x: Vec3| Vec3::select(Vec3::cmple(edge, x), Vec3::ONE, Vec3::ZERO);
+    let is_x = step(x0.yzw(), x0.xxx());
+    let is_yz = step(x0.zww(), x0.yyz());
+    let mut i0 = vec4(is_x.element_sum(), 1. - is_x.x, 1. - is_x.y, 1. - is_x.z);
+    i0.y += is_yz.x + is_yz.y;
+    i0.z += 1. - is_yz.x;
+    i0.w += 1. - is_yz.y;
+    i0.z += is_yz.z;
+    i0.w += 1. - is_yz.z;
+
+    let i3 = i0.clamp(Vec4::ZERO, Vec4::ONE);
+    let i2 = (i0 - 1.).clamp(Vec4::ZERO, Vec4::ONE);
+    let i1 = (i0 - 2.).clamp(Vec4::ZERO, Vec4::ONE);
+
+    let x1 = x0 - i1 + C.xxxx();
+    let x2 = x0 - i2 + C.yyyy();
+    let x3 = x0 - i3 + C.zzzz();
+    let x4 = x0 + C.wwww();
+
+    // permutations
+    i %= Vec4::splat(289.);
+    let j0 = permute_1(permute_1(permute_1(permute_1(i.w) + i.z) + i.y) + i.x);
+    let j1 = permute_4(
+        permute_4(
+            permute_4(
+                permute_4(i.w + vec4(i1.w, i2.w, i3.w, 1.)) + i.z + vec4(i1.z, i2.z, i3.z, 1.),
+            ) + i.y
+                + vec4(i1.y, i2.y, i3.y, 1.),
+        ) + i.x
+            + vec4(i1.x, i2.x, i3.x, 1.),
+    );
+
+    // gradients (7x7x6 points over a cube, mapped onto a 4d cross polytope)
+    let ip = vec4(1. / 294., 1. / 49., 1. / 7., 0.);
+    let mut p0 = gradient_4(j0, ip);
+    let mut p1 = gradient_4(j1.x, ip);
+    let mut p2 = gradient_4(j1.y, ip);
+    let mut p3 = gradient_4(j1.z, ip);
+    let mut p4 = gradient_4(j1.w, ip);
+
+    // normalize gradients
+    let norm = gradient_norm_4(vec4(
+        Vec4::dot(p0, p0),
+        Vec4::dot(p1, p1),
+        Vec4::dot(p2, p2),
+        Vec4::dot(p3, p3),
+    ));
+    p0 *= norm.x;
+    p1 *= norm.y;
+    p2 *= norm.z;
+    p3 *= norm.w;
+    p4 *= gradient_norm_4(Vec4::splat(Vec4::dot(p4, p4))).x;
+
+    // mix final noise value
+    let mut m0 =
+        (0.6 - vec3(Vec4::dot(x0, x0), Vec4::dot(x1, x1), Vec4::dot(x2, x2))).max(Vec3::ZERO);
+    let mut m1 = (0.6 - vec2(Vec4::dot(x3, x3), Vec4::dot(x4, x4))).max(Vec2::ZERO);
+    m0 *= m0;
+    m1 *= m1;
+    44. * (Vec3::dot(
+        m0 * m0,
+        vec3(Vec4::dot(p0, x0), Vec4::dot(p1, x1), Vec4::dot(p2, x2)),
+    ) + Vec2::dot(m1 * m1, vec2(Vec4::dot(p3, x3), Vec4::dot(p4, x4))))
+}
+
+// MIT License. © Stefan Gustavson, Ian McEwan, Johan Helsing
+/// Flow noise in two dimensions, simplex noise with all gradients rotated by
+/// `rotation` radians
+///
+/// Animating the rotation makes the noise swirl in place, instead of sliding
+/// like when the position is offset over time, see Perlin and Neyret's "Flow
+/// Noise". A full turn gives the same noise again. The gradients differ from
+/// [`simplex_noise_2d`], so it doesn't match it at rotation 0.
+pub fn simplex_noise_2d_flow(v: Vec2, rotation: f32) -> f32 {
+    const C: Vec2 = vec2(
+        0.211_324_87, // (3.0 - sqrt(3.0)) / 6.0
+        0.366_025_42, // 0.5 * (sqrt(3.0) - 1.0)
+    );
+
+    // first corner
+    let mut i = (v + Vec2::dot(v, C.yy())).floor();
+    let x0 = v - i + Vec2::dot(i, C.xx());
+
+    // other corners
+    let i1 = if x0.x > x0.y {
+        vec2(1., 0.)
+    } else {
+        vec2(0., 1.)
+    };
+    let x1 = x0 - i1 + C.x;
+    let x2 = x0 - 1. + 2. * C.x;
+
+    // permutations
+    i %= Vec2::splat(289.);
+    let p = permute_3(permute_3(i.y + vec3(0., i1.y, 1.)) + i.x + vec3(0., i1.x, 1.));
+
+    // gradients: unit vectors at hashed angles, all turned by the rotation
+    let psi = p * 0.07482 + rotation;
+    let g = |psi: f32, x: Vec2| Vec2::dot(vec2(psi.cos(), psi.sin()), x);
+
+    let mut m =
+        (0.5 - vec3(Vec2::dot(x0, x0), Vec2::dot(x1, x1), Vec2::dot(x2, x2))).max(Vec3::ZERO);
+    m *= m;
+    m *= m;
+
+    // compute final noise value at P, scaled to about -1..1 for unit gradients
+    99. * Vec3::dot(m, vec3(g(psi.x, x0), g(psi.y, x1), g(psi.z, x2)))
+}
+
+/// Unit gradient for a 3d flow noise corner with hash value `hash`, rotated
+/// by an angle with the given sine and cosine
+///
+/// The unrotated gradients lie on a fibonacci spiral over the sphere, and are
+/// rotated towards a hashed perpendicular direction.
+fn flow_gradient_3(hash: f32, sin_rotation: f32, cos_rotation: f32) -> Vec3 {
+    let (st, ct) = (hash * 3.883_222).sin_cos();
+    let sz = hash * -0.006_920_415 + 0.996_539_8;
+    let sz_prime = (1. - sz * sz).sqrt();
+    let p = vec3(ct * sz_prime, st * sz_prime, sz);
+
+    let (sp, cp) = (hash * 0.108_705_63).sin_cos();
+    let ctp = st * sp - ct * cp;
+    let q = vec3(
+        ctp * st + (sp - ctp * st) * sz,
+        -ctp * ct + (cp + ctp * ct) * sz,
+        -(p.y * cp + p.x * sp),
+    );
+
+    p * cos_rotation + q * sin_rotation
+}
+
+// MIT License. © Stefan Gustavson, Ian McEwan, Munrocket, Johan Helsing
+/// Flow noise in three dimensions, simplex noise with all gradients rotated by
+/// `rotation` radians
+///
+/// See [`simplex_noise_2d_flow`].
+pub fn simplex_noise_3d_flow(v: Vec3, rotation: f32) -> f32 {
+    const C: Vec2 = vec2(1. / 6., 1. / 3.);
+
+    // first corner
+    let mut i = (v + Vec3::dot(v, C.yyy())).floor();
+    let x0 = v - i + Vec3::dot(i, C.xxx());
+
+    // other corners
+    let g = step_3(x0.yzx(), x0.xyz());
+    let l = 1. - g;
+    let i1 = Vec3::min(g.xyz(), l.zxy());
+    let i2 = Vec3::max(g.xyz(), l.zxy());
+
+    let x1 = x0 - i1 + 1. * C.xxx();
+    let x2 = x0 - i2 + 2. * C.xxx();
+    let x3 = x0 - 1. + 3. * C.xxx();
+
+    // permutations
+    i %= Vec3::splat(289.);
+    let p = permute_4(
+        permute_4(permute_4(i.z + vec4(0., i1.z, i2.z, 1.)) + i.y + vec4(0., i1.y, i2.y, 1.))
+            + i.x
+            + vec4(0., i1.x, i2.x, 1.),
+    );
+
+    // gradients
+    let (sa, ca) = rotation.sin_cos();
+    let p0 = flow_gradient_3(p.x, sa, ca);
+    let p1 = flow_gradient_3(p.y, sa, ca);
+    let p2 = flow_gradient_3(p.z, sa, ca);
+    let p3 = flow_gradient_3(p.w, sa, ca);
+
+    // mix final noise value
+    let mut m = 0.5
+        - vec4(
+            Vec3::dot(x0, x0),
+            Vec3::dot(x1, x1),
+            Vec3::dot(x2, x2),
+            Vec3::dot(x3, x3),
+        );
+    m = Vec4::max(m, Vec4::ZERO);
+    m *= m;
+    105. * Vec4::dot(
+        m * m,
+        vec4(
+            Vec3::dot(p0, x0),
+            Vec3::dot(p1, x1),
+            Vec3::dot(p2, x2),
+            Vec3::dot(p3, x3),
+        ),
+    )
+}
+
+/// Fractional brownian motion (fbm) based on 1d simplex noise
+pub fn fbm_simplex_1d(pos: f32, octaves: usize, lacunarity: f32, gain: f32) -> f32 {
+    let mut sum = 0.;
+    let mut amplitude = 1.;
+    let mut frequency = 1.;
+
+    for _ in 0..octaves {
+        sum += simplex_noise_1d(pos * frequency) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+    }
+
+    sum
+}
+
+/// Fractional brownian motion (fbm) based on seeded 1d simplex noise
+pub fn fbm_simplex_1d_seeded(
+    pos: f32,
+    octaves: usize,
+    lacunarity: f32,
+    gain: f32,
+    seed: f32,
+) -> f32 {
+    let mut sum = 0.;
+    let mut amplitude = 1.;
+    let mut frequency = 1.;
+
+    for _ in 0..octaves {
+        sum += simplex_noise_1d_seeded(pos * frequency, seed) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+    }
+
+    sum
+}
+
 /// Fractional brownian motion (fbm) based on 2d simplex noise
 pub fn fbm_simplex_2d(pos: Vec2, octaves: usize, lacunarity: f32, gain: f32) -> f32 {
     let mut sum = 0.;
@@ -349,16 +680,24 @@ pub fn fbm_simplex_2d_seeded(
     sum
 }
 
-const MAX_WARP_ITERATIONS: usize = 4;
-
-/// The return value of the domain warping function.
-#[derive(Copy, Clone)]
-pub struct WarpResult {
-    /// The final noise value
+/// The default maximum number of domain warping iterations
+///
+/// Warping has diminishing returns due to the falloff param, so we don't need
+/// many iterations. Use [`warp_2d_n`] and [`warp_3d_n`] to allow more, and the `NOISY_MAX_WARP_ITERATIONS` shader def in WGSL.
+pub const MAX_WARP_ITERATIONS: usize = 4;
+
+/// The return value of the domain warping functions.
+///
+/// `N` is the maximum number of warp iterations, requesting more iterations
+/// than that is clamped.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WarpResult<const N: usize = MAX_WARP_ITERATIONS> {
+    /// The final noise value, sampled at `positions[0]`
     pub noise_value: f32,
     /// The history of warped coordinates, where positions[0] is the last iteration, positions[1] is second to last, etc.
+    /// Entries from before the first iteration hold the initial position.
     /// Can be useful for mixing colors.
-    pub positions: [Vec2; MAX_WARP_ITERATIONS],
+    pub positions: [Vec2; N],
 }
 
 /// A technique that distorts the position before feeding it to the noise
@@ -374,27 +713,223 @@ pub fn fbm_simplex_2d_warp_seeded(
     warp_scale: Vec2,
     falloff: f32,
 ) -> WarpResult {
+    let fbm = |p| fbm_simplex_2d_seeded(p, octaves, lacunarity, gain, seed);
+    warp_2d(pos_initial, warp_iterations, warp_scale, falloff, fbm, fbm)
+}
+
+/// Settings for [`fbm_simplex_2d_warp`]
+///
+/// `N` is the maximum number of warp iterations, see
+/// [`fbm_simplex_2d_warp_n`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DomainWarp2d<const N: usize = MAX_WARP_ITERATIONS> {
+    /// Number of fbm octaves
+    pub octaves: usize,
+    /// Frequency multiplier between octaves
+    pub lacunarity: f32,
+    /// Amplitude multiplier between octaves
+    pub gain: f32,
+    /// Seed of the final noise and the offset field
+    pub seed: f32,
+    /// Number of warp iterations, clamped to `N`
+    pub iterations: usize,
+    /// How far positions are displaced in the first iteration
+    pub scale: Vec2,
+    /// Scale multiplier between iterations
+    pub falloff: f32,
+    /// Added to the seed of the x and y displacement respectively.
+    ///
+    /// Use distinct offsets, like the default or Inigo Quilez's `(0, 0)` and
+    /// `(5.2, 1.3)`, for independent x and y displacement. With equal
+    /// offsets, both axes sample the same field, so positions mostly move
+    /// along the diagonal.
+    pub axis_seed_offsets: Vec2,
+    /// Frequency multiplier of the offset field between iterations
+    pub frequency_step: f32,
+    /// Octaves added to the offset field each iteration, may be negative
+    pub octave_step: i32,
+}
+
+impl<const N: usize> Default for DomainWarp2d<N> {
+    fn default() -> Self {
+        Self {
+            octaves: 4,
+            lacunarity: 2.0,
+            gain: 0.5,
+            seed: 0.0,
+            iterations: 2,
+            scale: Vec2::splat(0.4),
+            falloff: 0.5,
+            axis_seed_offsets: vec2(0.0, 1.0),
+            frequency_step: 1.0,
+            octave_step: 0,
+        }
+    }
+}
+
+/// Domain warping using seeded 2d simplex fbm, with independent offsets for
+/// the x and y displacement and per-iteration frequency and octaves
+///
+/// ```
+/// # use bevy::math::vec2;
+/// # use noisy_bevy::{fbm_simplex_2d_warp, DomainWarp2d};
+/// let result = fbm_simplex_2d_warp(
+///     vec2(1.2, 3.4),
+///     &DomainWarp2d {
+///         iterations: 3,
+///         frequency_step: 2.0,
+///         octave_step: -1,
+///         ..Default::default()
+///     },
+/// );
+/// ```
+pub fn fbm_simplex_2d_warp(pos_initial: Vec2, warp: &DomainWarp2d) -> WarpResult {
+    fbm_simplex_2d_warp_n(pos_initial, warp)
+}
+
+/// [`fbm_simplex_2d_warp`] with up to `N` iterations
+///
+/// ```
+/// # use bevy::math::vec2;
+/// # use noisy_bevy::{fbm_simplex_2d_warp_n, DomainWarp2d};
+/// let result = fbm_simplex_2d_warp_n(
+///     vec2(1.2, 3.4),
+///     &DomainWarp2d::<8> {
+///         iterations: 8,
+///         falloff: 0.7,
+///         ..Default::default()
+///     },
+/// );
+/// ```
+pub fn fbm_simplex_2d_warp_n<const N: usize>(
+    pos_initial: Vec2,
+    warp: &DomainWarp2d<N>,
+) -> WarpResult<N> {
     let mut pos = pos_initial;
     let mut scale = 1.0;
-    let mut positions = [Vec2::ZERO; MAX_WARP_ITERATIONS];
+    let mut frequency = 1.0;
+    let mut octaves = warp.octaves as i32;
+    let mut positions = [pos_initial; N];
+
+    let iterations = warp.iterations.min(N);
+    let seeds = warp.seed + warp.axis_seed_offsets;
+
+    for _ in 0..iterations {
+        let fbm = |p: Vec2, seed| {
+            fbm_simplex_2d_seeded(
+                p * frequency,
+                octaves.max(1) as usize,
+                warp.lacunarity,
+                warp.gain,
+                seed,
+            )
+        };
+        pos.x += scale * warp.scale.x * fbm(pos, seeds.x);
+        pos.y += scale * warp.scale.y * fbm(pos, seeds.y);
+
+        // Store positions in reverse order (last iteration at index 0)
+        positions.copy_within(0..N - 1, 1);
+        positions[0] = pos;
+
+        scale *= warp.falloff;
+        frequency *= warp.frequency_step;
+        octaves += warp.octave_step;
+    }
+
+    WarpResult {
+        noise_value: fbm_simplex_2d_seeded(
+            pos,
+            warp.octaves,
+            warp.lacunarity,
+            warp.gain,
+            warp.seed,
+        ),
+        positions,
+    }
+}
 
-    for i in 0..warp_iterations {
-        pos.x += scale * warp_scale.x * fbm_simplex_2d_seeded(pos, octaves, lacunarity, gain, seed);
-        pos.y += scale * warp_scale.y * fbm_simplex_2d_seeded(pos, octaves, lacunarity, gain, seed);
+/// Domain warping with pluggable noise functions
+///
+/// `offset_noise` is used for the offset field, and `noise` is sampled at the
+/// final warped position. Any noise function can be used for either, i.e.
+/// [`worley_2d`] or ridged noise.
+///
+/// Iterations are clamped to [`MAX_WARP_ITERATIONS`], see [`warp_2d_n`] for
+/// more.
+///
+/// ```
+/// # use bevy::math::{vec2, Vec2};
+/// # use noisy_bevy::{fbm_simplex_2d, warp_2d, worley_2d};
+/// let result = warp_2d(
+///     vec2(1.2, 3.4),
+///     3,
+///     Vec2::splat(0.4),
+///     0.5,
+///     |p| fbm_simplex_2d(p, 4, 2.0, 0.5),
+///     |p| worley_2d(p, 1.0).x,
+/// );
+/// ```
+pub fn warp_2d(
+    pos_initial: Vec2,
+    warp_iterations: usize,
+    warp_scale: Vec2,
+    falloff: f32,
+    offset_noise: impl Fn(Vec2) -> f32,
+    noise: impl Fn(Vec2) -> f32,
+) -> WarpResult {
+    warp_2d_n(
+        pos_initial,
+        warp_iterations,
+        warp_scale,
+        falloff,
+        offset_noise,
+        noise,
+    )
+}
+
+/// [`warp_2d`] with up to `N` iterations
+///
+/// `N` is the length of the position history in the returned [`WarpResult`].
+///
+/// ```
+/// # use bevy::math::{vec2, Vec2};
+/// # use noisy_bevy::{fbm_simplex_2d, warp_2d_n};
+/// let result = warp_2d_n::<8>(
+///     vec2(1.2, 3.4),
+///     8,
+///     Vec2::splat(0.4),
+///     0.5,
+///     |p| fbm_simplex_2d(p, 4, 2.0, 0.5),
+///     |p| fbm_simplex_2d(p, 4, 2.0, 0.5),
+/// );
+/// ```
+pub fn warp_2d_n<const N: usize>(
+    pos_initial: Vec2,
+    warp_iterations: usize,
+    warp_scale: Vec2,
+    falloff: f32,
+    offset_noise: impl Fn(Vec2) -> f32,
+    noise: impl Fn(Vec2) -> f32,
+) -> WarpResult<N> {
+    let mut pos = pos_initial;
+    let mut scale = 1.0;
+    let mut positions = [pos_initial; N];
+
+    let iterations = warp_iterations.min(N);
+
+    for _ in 0..iterations {
+        pos.x += scale * warp_scale.x * offset_noise(pos);
+        pos.y += scale * warp_scale.y * offset_noise(pos);
 
         // Store positions in reverse order (last iteration at index 0)
-        let index = MAX_WARP_ITERATIONS - 1 - i;
-        if index < MAX_WARP_ITERATIONS {
-            positions[index] = pos;
-        }
+        positions.copy_within(0..N - 1, 1);
+        positions[0] = pos;
 
         scale *= falloff;
     }
 
-    let noise_value = fbm_simplex_2d_seeded(positions[3], octaves, lacunarity, gain, seed);
-
     WarpResult {
-        noise_value,
+        noise_value: noise(pos),
         positions,
     }
 }
@@ -435,6 +970,167 @@ pub fn fbm_simplex_3d_seeded(
     sum
 }
 
+/// Fractional brownian motion (fbm) based on 4d simplex noise
+pub fn fbm_simplex_4d(pos: Vec4, octaves: usize, lacunarity: f32, gain: f32) -> f32 {
+    let mut sum = 0.;
+    let mut amplitude = 1.;
+    let mut frequency = 1.;
+
+    for _ in 0..octaves {
+        sum += simplex_noise_4d(pos * frequency) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+    }
+
+    sum
+}
+
+/// Fractional brownian motion (fbm) based on 2d flow noise
+///
+/// Each octave is rotated `rotation_gain` times as much as the one before it,
+/// so with a gain above 1, the small details swirl faster than the large
+/// ones, like in flowing liquids.
+pub fn fbm_simplex_2d_flow(
+    pos: Vec2,
+    octaves: usize,
+    lacunarity: f32,
+    gain: f32,
+    rotation: f32,
+    rotation_gain: f32,
+) -> f32 {
+    let mut sum = 0.;
+    let mut amplitude = 1.;
+    let mut frequency = 1.;
+    let mut angle = rotation;
+
+    for _ in 0..octaves {
+        sum += simplex_noise_2d_flow(pos * frequency, angle) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+        angle *= rotation_gain;
+    }
+
+    sum
+}
+
+/// Fractional brownian motion (fbm) based on 3d flow noise
+///
+/// See [`fbm_simplex_2d_flow`].
+pub fn fbm_simplex_3d_flow(
+    pos: Vec3,
+    octaves: usize,
+    lacunarity: f32,
+    gain: f32,
+    rotation: f32,
+    rotation_gain: f32,
+) -> f32 {
+    let mut sum = 0.;
+    let mut amplitude = 1.;
+    let mut frequency = 1.;
+    let mut angle = rotation;
+
+    for _ in 0..octaves {
+        sum += simplex_noise_3d_flow(pos * frequency, angle) * amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+        angle *= rotation_gain;
+    }
+
+    sum
+}
+
+/// The return value of the 3d domain warping functions.
+///
+/// `N` is the maximum number of warp iterations, requesting more iterations
+/// than that is clamped.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WarpResult3d<const N: usize = MAX_WARP_ITERATIONS> {
+    /// The final noise value, sampled at `positions[0]`
+    pub noise_value: f32,
+    /// The history of warped coordinates, where positions[0] is the last iteration, positions[1] is second to last, etc.
+    /// Entries from before the first iteration hold the initial position.
+    /// Can be useful for mixing colors.
+    pub positions: [Vec3; N],
+}
+
+/// Domain warping in three dimensions with pluggable noise functions
+///
+/// `offset_noise` is used for the offset field, and `noise` is sampled at the
+/// final warped position.
+///
+/// Iterations are clamped to [`MAX_WARP_ITERATIONS`], see [`warp_3d_n`] for
+/// more.
+pub fn warp_3d(
+    pos_initial: Vec3,
+    warp_iterations: usize,
+    warp_scale: Vec3,
+    falloff: f32,
+    offset_noise: impl Fn(Vec3) -> f32,
+    noise: impl Fn(Vec3) -> f32,
+) -> WarpResult3d {
+    warp_3d_n(
+        pos_initial,
+        warp_iterations,
+        warp_scale,
+        falloff,
+        offset_noise,
+        noise,
+    )
+}
+
+/// [`warp_3d`] with up to `N` iterations
+///
+/// `N` is the length of the position history in the returned
+/// [`WarpResult3d`].
+pub fn warp_3d_n<const N: usize>(
+    pos_initial: Vec3,
+    warp_iterations: usize,
+    warp_scale: Vec3,
+    falloff: f32,
+    offset_noise: impl Fn(Vec3) -> f32,
+    noise: impl Fn(Vec3) -> f32,
+) -> WarpResult3d<N> {
+    let mut pos = pos_initial;
+    let mut scale = 1.0;
+    let mut positions = [pos_initial; N];
+
+    let iterations = warp_iterations.min(N);
+
+    for _ in 0..iterations {
+        pos.x += scale * warp_scale.x * offset_noise(pos);
+        pos.y += scale * warp_scale.y * offset_noise(pos);
+        pos.z += scale * warp_scale.z * offset_noise(pos);
+
+        // Store positions in reverse order (last iteration at index 0)
+        positions.copy_within(0..N - 1, 1);
+        positions[0] = pos;
+
+        scale *= falloff;
+    }
+
+    WarpResult3d {
+        noise_value: noise(pos),
+        positions,
+    }
+}
+
+/// Domain warping in three dimensions using seeded 3d simplex fbm
+/// inspired by https://iquilezles.org/articles/warp/
+#[allow(clippy::too_many_arguments)]
+pub fn fbm_simplex_3d_warp_seeded(
+    pos_initial: Vec3,
+    octaves: usize,
+    lacunarity: f32,
+    gain: f32,
+    seed: Vec3,
+    warp_iterations: usize,
+    warp_scale: Vec3,
+    falloff: f32,
+) -> WarpResult3d {
+    let fbm = |p| fbm_simplex_3d_seeded(p, octaves, lacunarity, gain, seed);
+    warp_3d(pos_initial, warp_iterations, warp_scale, falloff, fbm, fbm)
+}
+
 /// Cellular noise
 pub fn worley_2d(pos: Vec2, jitter: f32) -> Vec2 {
     const K: f32 = 1.0 / 7.0;
@@ -499,6 +1195,30 @@ pub fn worley_2d(pos: Vec2, jitter: f32) -> Vec2 {
 mod test {
     use super::*;
     use insta::assert_debug_snapshot;
+    use std::f32::consts::TAU;
+
+    /// Snapshots noise built on simplex gradients, recorded separately for the
+    /// `high-quality-gradients` feature
+    macro_rules! assert_gradient_snapshot {
+        ($value:expr) => {
+            if cfg!(feature = "high-quality-gradients") {
+                insta::with_settings!({ snapshot_suffix => "high_quality_gradients" }, {
+                    assert_debug_snapshot!($value);
+                });
+            } else {
+                assert_debug_snapshot!($value);
+            }
+        };
+    }
+
+    fn sample_1d_fn(f: fn(f32) -> f32) -> Vec<f32> {
+        let mut values = Vec::new();
+        for x in -200..200 {
+            let x = x as f32 / 10.;
+            values.push(f(x));
+        }
+        values
+    }
 
     fn sample_2d_fn(f: fn(Vec2) -> f32) -> Vec<f32> {
         let mut values = Vec::new();
@@ -547,61 +1267,296 @@ mod test {
         values
     }
 
+    #[test]
+    fn simplex_1d_values_unchanged() {
+        assert_debug_snapshot!(sample_1d_fn(simplex_noise_1d));
+    }
+
+    #[test]
+    fn simplex_1d_seeded_values_unchanged() {
+        assert_debug_snapshot!(sample_1d_fn(|p| simplex_noise_1d_seeded(p, 0.0)));
+        assert_debug_snapshot!(sample_1d_fn(|p| simplex_noise_1d_seeded(p, 123.0)));
+    }
+
+    #[test]
+    fn simplex_1d_in_range() {
+        for x in -10000..10000 {
+            let v = simplex_noise_1d(x as f32 / 100.);
+            assert!((-1.0..=1.0).contains(&v), "{v} out of range");
+        }
+    }
+
+    #[test]
+    fn simplex_2d_3d_in_range() {
+        for x in -200..200 {
+            for y in -200..200 {
+                let p = vec2(x as f32, y as f32) * 0.013;
+                for v in [simplex_noise_2d(p), simplex_noise_2d_seeded(p, 17.0)] {
+                    assert!((-1.0..=1.0).contains(&v), "{v} out of range at {p}");
+                }
+            }
+        }
+        for x in -30..30 {
+            for y in -30..30 {
+                for z in -30..30 {
+                    let p = vec3(x as f32, y as f32, z as f32) * 0.031;
+                    for v in [simplex_noise_3d(p), simplex_noise_3d_seeded(p, Vec3::X)] {
+                        assert!((-1.0..=1.0).contains(&v), "{v} out of range at {p}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fbm_1d_seeded_values_unchanged() {
+        assert_debug_snapshot!(sample_1d_fn(|p| {
+            fbm_simplex_1d_seeded(p, 5, 2.0, 0.5, 123.0)
+        }));
+    }
+
     #[test]
     fn simplex_2d_values_unchanged() {
-        assert_debug_snapshot!(sample_2d_fn(simplex_noise_2d));
+        assert_gradient_snapshot!(sample_2d_fn(simplex_noise_2d));
     }
 
     #[test]
     fn simplex_2d_seeded_values_unchanged() {
-        assert_debug_snapshot!(sample_2d_fn(|p| simplex_noise_2d_seeded(p, 0.0)));
-        assert_debug_snapshot!(sample_2d_fn(|p| simplex_noise_2d_seeded(p, 123.0)));
+        assert_gradient_snapshot!(sample_2d_fn(|p| simplex_noise_2d_seeded(p, 0.0)));
+        assert_gradient_snapshot!(sample_2d_fn(|p| simplex_noise_2d_seeded(p, 123.0)));
     }
 
     #[test]
     fn fbm_2d_warp_seeded_values_unchanged() {
-        assert_debug_snapshot!(sample_2d_fn(|p| {
+        assert_gradient_snapshot!(sample_2d_fn(|p| {
             fbm_simplex_2d_warp_seeded(p, 10, 2.9, 0.4, 324.0, 4, vec2(0.4, 0.4), 0.1).noise_value
         }));
     }
 
+    #[test]
+    fn warp_2d_values_unchanged() {
+        assert_gradient_snapshot!(sample_2d_fn(|p| {
+            warp_2d(
+                p,
+                3,
+                vec2(0.4, 0.4),
+                0.5,
+                |p| simplex_noise_2d_seeded(p, 12.0),
+                |p| worley_2d(p, 1.0).x,
+            )
+            .noise_value
+        }));
+    }
+
+    #[test]
+    fn fbm_2d_warp_values_unchanged() {
+        assert_gradient_snapshot!(sample_2d_fn(|p| {
+            fbm_simplex_2d_warp(
+                p,
+                &DomainWarp2d {
+                    seed: 324.0,
+                    iterations: 3,
+                    frequency_step: 1.5,
+                    octave_step: -1,
+                    ..Default::default()
+                },
+            )
+            .noise_value
+        }));
+    }
+
+    #[test]
+    fn fbm_2d_warp_matches_warp_seeded() {
+        let warp = DomainWarp2d {
+            octaves: 10,
+            lacunarity: 2.9,
+            gain: 0.4,
+            seed: 324.0,
+            iterations: 4,
+            scale: vec2(0.4, 0.4),
+            falloff: 0.1,
+            axis_seed_offsets: Vec2::ZERO,
+            frequency_step: 1.0,
+            octave_step: 0,
+        };
+        for p in [vec2(0.3, 1.7), vec2(-4.2, 0.1)] {
+            assert_eq!(
+                fbm_simplex_2d_warp(p, &warp),
+                fbm_simplex_2d_warp_seeded(p, 10, 2.9, 0.4, 324.0, 4, vec2(0.4, 0.4), 0.1)
+            );
+        }
+    }
+
+    #[test]
+    fn fbm_2d_warp_allows_more_iterations() {
+        let p = vec2(0.3, 1.7);
+        let warp = DomainWarp2d::<8> {
+            iterations: 6,
+            ..Default::default()
+        };
+        let more = fbm_simplex_2d_warp_n(p, &warp);
+        assert_ne!(more.positions[5], p);
+        assert_eq!(more.positions[6], p);
+
+        let clamped = fbm_simplex_2d_warp(
+            p,
+            &DomainWarp2d {
+                iterations: 6,
+                ..Default::default()
+            },
+        );
+        assert_eq!(clamped.positions[3], more.positions[5]);
+    }
+
+    #[test]
+    fn warp_history_starts_at_last_iteration() {
+        let p = vec2(0.3, 1.7);
+        let fbm = |p| fbm_simplex_2d_seeded(p, 4, 2.0, 0.5, 7.0);
+        let one = warp_2d(p, 1, Vec2::splat(0.4), 0.5, fbm, fbm);
+        let two = warp_2d(p, 2, Vec2::splat(0.4), 0.5, fbm, fbm);
+
+        assert_eq!(two.positions[1], one.positions[0]);
+        assert_eq!(two.positions[2], p);
+        assert_eq!(two.positions[3], p);
+        assert_eq!(two.noise_value, fbm(two.positions[0]));
+    }
+
+    #[test]
+    fn warp_without_iterations_samples_initial_position() {
+        let p = vec2(0.3, 1.7);
+        let result = fbm_simplex_2d_warp_seeded(p, 4, 2.0, 0.5, 7.0, 0, Vec2::splat(0.4), 0.5);
+        assert_eq!(result.positions, [p; MAX_WARP_ITERATIONS]);
+        assert_eq!(
+            result.noise_value,
+            fbm_simplex_2d_seeded(p, 4, 2.0, 0.5, 7.0)
+        );
+    }
+
+    #[test]
+    fn warp_iterations_are_clamped() {
+        let p = vec2(0.3, 1.7);
+        let warp = |iterations| {
+            fbm_simplex_2d_warp_seeded(p, 4, 2.0, 0.5, 7.0, iterations, Vec2::splat(0.4), 0.5)
+        };
+        assert_eq!(warp(MAX_WARP_ITERATIONS + 3), warp(MAX_WARP_ITERATIONS));
+
+        let p = vec3(0.3, 1.7, -0.6);
+        let fbm = |p| fbm_simplex_3d(p, 4, 2.0, 0.5);
+        let a = warp_3d_n::<8>(p, 20, Vec3::splat(0.4), 0.5, fbm, fbm);
+        let b = warp_3d_n::<8>(p, 8, Vec3::splat(0.4), 0.5, fbm, fbm);
+        assert_eq!(a, b);
+        assert_ne!(a.positions[7], p);
+    }
+
     #[test]
     fn simplex_3d_values_unchanged() {
-        assert_debug_snapshot!(sample_3d_fn(simplex_noise_3d));
+        assert_gradient_snapshot!(sample_3d_fn(simplex_noise_3d));
     }
 
     #[test]
     fn simplex_3d_seeded_values_unchanged() {
-        assert_debug_snapshot!(sample_3d_seeded_fn(simplex_noise_3d_seeded));
+        assert_gradient_snapshot!(sample_3d_seeded_fn(simplex_noise_3d_seeded));
     }
 
     #[test]
     fn fbm_2d_values_unchanged() {
-        assert_debug_snapshot!(sample_2d_fn(|p| { fbm_simplex_2d(p, 5, 2.0, 0.5) }));
+        assert_gradient_snapshot!(sample_2d_fn(|p| { fbm_simplex_2d(p, 5, 2.0, 0.5) }));
     }
 
     #[test]
     fn fbm_2d_seeded_values_unchanged() {
-        assert_debug_snapshot!(sample_2d_fn(|p| {
+        assert_gradient_snapshot!(sample_2d_fn(|p| {
             fbm_simplex_2d_seeded(p, 5, 2.0, 0.5, 0.0)
         }));
-        assert_debug_snapshot!(sample_2d_fn(|p| {
+        assert_gradient_snapshot!(sample_2d_fn(|p| {
             fbm_simplex_2d_seeded(p, 5, 2.0, 0.5, 123.0)
         }));
     }
 
     #[test]
     fn fbm_3d_values_unchanged() {
-        assert_debug_snapshot!(sample_3d_fn(|p| { fbm_simplex_3d(p, 5, 2.0, 0.5) }));
+        assert_gradient_snapshot!(sample_3d_fn(|p| { fbm_simplex_3d(p, 5, 2.0, 0.5) }));
     }
 
     #[test]
     fn fbm_3d_seeded_values_unchanged() {
-        assert_debug_snapshot!(sample_3d_seeded_fn(|p, s| {
+        assert_gradient_snapshot!(sample_3d_seeded_fn(|p, s| {
             fbm_simplex_3d_seeded(p, 5, 2.0, 0.5, s)
         }));
     }
 
+    #[test]
+    fn fbm_3d_warp_seeded_values_unchanged() {
+        assert_gradient_snapshot!(sample_3d_fn(|p| {
+            fbm_simplex_3d_warp_seeded(p, 4, 2.0, 0.5, Vec3::X, 3, Vec3::splat(0.4), 0.5)
+                .noise_value
+        }));
+    }
+
+    #[test]
+    fn simplex_4d_values_unchanged() {
+        assert_gradient_snapshot!({
+            let mut values = Vec::new();
+            for w in -3..3 {
+                let w = w as f32 / 5.;
+                for x in -3..3 {
+                    for y in -3..3 {
+                        for z in -3..3 {
+                            let p = vec4(x as f32, y as f32, z as f32, 0.) / 5.;
+                            values.push(simplex_noise_4d(p.with_w(w)));
+                        }
+                    }
+                }
+            }
+            values
+        });
+    }
+
+    #[test]
+    fn simplex_4d_in_range() {
+        for x in -12..12 {
+            for y in -12..12 {
+                for z in -12..12 {
+                    for w in -12..12 {
+                        let p = vec4(x as f32, y as f32, z as f32, w as f32) * 0.093;
+                        let v = simplex_noise_4d(p);
+                        assert!((-1.0..=1.0).contains(&v), "{v} out of range at {p}");
+                    }
+                }
+            }
+        }
+        assert_eq!(simplex_noise_4d(Vec4::ZERO), 0.);
+    }
+
+    #[test]
+    fn flow_values_unchanged() {
+        assert_debug_snapshot!((
+            sample_2d_fn(|p| simplex_noise_2d_flow(p, 0.7)),
+            sample_3d_fn(|p| simplex_noise_3d_flow(p, 0.7)),
+        ));
+    }
+
+    #[test]
+    fn flow_rotation() {
+        for i in 0..500 {
+            let p = vec3(i as f32 * 0.37, i as f32 * -0.21, i as f32 * 0.13);
+            let rotation = i as f32 * 0.1;
+            let v2 = simplex_noise_2d_flow(p.xy(), rotation);
+            let v3 = simplex_noise_3d_flow(p, rotation);
+            assert!((-1.0..=1.0).contains(&v2), "{v2} out of range");
+            assert!((-1.0..=1.0).contains(&v3), "{v3} out of range");
+
+            // a full turn gives the same noise
+            let turned = simplex_noise_2d_flow(p.xy(), rotation + TAU);
+            assert!((v2 - turned).abs() < 1e-4, "{v2} != {turned}");
+            let turned = simplex_noise_3d_flow(p, rotation + TAU);
+            assert!((v3 - turned).abs() < 1e-4, "{v3} != {turned}");
+        }
+
+        let gradient = flow_gradient_3(123., 0.6_f32.sin(), 0.6_f32.cos());
+        assert!((gradient.length() - 1.).abs() < 1e-4, "{gradient}");
+    }
+
     #[test]
     fn worley_2d_values_unchanged() {
         assert_debug_snapshot!({
@@ -0,0 +1,442 @@
+use bevy::math::{IVec2, UVec2, Vec2, ivec2, vec2};
+
+use crate::rng::Rng;
+
+/// A grid of heights, one per texel, for eroding noise terrain
+///
+/// Heights and distances are in the same units, one texel apart, so slopes
+/// compare directly with [`ThermalErosion::talus`]. Scale the noise up
+/// accordingly, fbm between -1 and 1 on a 256 texel map is very flat.
+///
+/// ```
+/// # use bevy::math::UVec2;
+/// # use noisy_bevy::{Heightfield, HydraulicErosion, ThermalErosion, fbm_simplex_2d_seeded};
+/// let mut terrain = Heightfield::from_fn(UVec2::splat(128), |texel| {
+///     fbm_simplex_2d_seeded(texel.as_vec2() * 0.01, 5, 2.0, 0.5, 3.0) * 40.0
+/// });
+/// terrain.erode_hydraulic(&HydraulicErosion {
+///     droplets: 10_000,
+///     ..Default::default()
+/// });
+/// terrain.erode_thermal(&ThermalErosion::default());
+/// let river = terrain.trace_river(UVec2::new(64, 64), 500);
+/// terrain.carve_river(&river, 0.5);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heightfield {
+    size: UVec2,
+    heights: Vec<f32>,
+}
+
+/// How [`Heightfield::erode_hydraulic`] moves soil with water droplets, see
+/// "Implementation of a method for hydraulic erosion" by Hans Theobald Beyer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HydraulicErosion {
+    /// Number of droplets to simulate, one after another
+    pub droplets: u32,
+    /// Steps a droplet takes at most before it's dropped
+    pub max_lifetime: u32,
+    /// How much a droplet keeps its direction instead of following the slope,
+    /// between 0 and 1
+    pub inertia: f32,
+    /// Sediment a droplet can carry per unit of speed, water and slope
+    pub sediment_capacity: f32,
+    /// Sediment a droplet can always carry, so it keeps eroding flat ground
+    pub min_sediment_capacity: f32,
+    /// Fraction of the free capacity a droplet erodes per step
+    pub erode_speed: f32,
+    /// Fraction of the excess sediment a droplet deposits per step
+    pub deposit_speed: f32,
+    /// Fraction of the water that evaporates per step
+    pub evaporate_speed: f32,
+    /// How fast droplets speed up going downhill
+    pub gravity: f32,
+    /// Erosions with different seeds start the droplets at different texels
+    pub seed: u32,
+}
+
+impl Default for HydraulicErosion {
+    fn default() -> Self {
+        Self {
+            droplets: 50_000,
+            max_lifetime: 30,
+            inertia: 0.05,
+            sediment_capacity: 4.0,
+            min_sediment_capacity: 0.01,
+            erode_speed: 0.3,
+            deposit_speed: 0.3,
+            evaporate_speed: 0.01,
+            gravity: 4.0,
+            seed: 0,
+        }
+    }
+}
+
+/// How [`Heightfield::erode_thermal`] lets steep slopes crumble
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThermalErosion {
+    /// Number of passes over the whole heightfield
+    pub iterations: u32,
+    /// The steepest stable slope, in height units per texel
+    pub talus: f32,
+    /// Fraction of the material above the talus slope moved per pass,
+    /// between 0 and 1
+    pub rate: f32,
+}
+
+impl Default for ThermalErosion {
+    fn default() -> Self {
+        Self {
+            iterations: 50,
+            talus: 1.0,
+            rate: 0.5,
+        }
+    }
+}
+
+const NEIGHBORS: [IVec2; 8] = [
+    ivec2(1, 0),
+    ivec2(1, 1),
+    ivec2(0, 1),
+    ivec2(-1, 1),
+    ivec2(-1, 0),
+    ivec2(-1, -1),
+    ivec2(0, -1),
+    ivec2(1, -1),
+];
+
+impl Heightfield {
+    /// A heightfield with the heights returned by `height` for each texel
+    pub fn from_fn(size: UVec2, mut height: impl FnMut(UVec2) -> f32) -> Self {
+        let heights = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| UVec2::new(x, y)))
+            .map(&mut height)
+            .collect();
+        Self { size, heights }
+    }
+
+    /// Size in texels
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// The heights, row by row
+    pub fn heights(&self) -> &[f32] {
+        &self.heights
+    }
+
+    /// The heights, row by row
+    pub fn heights_mut(&mut self) -> &mut [f32] {
+        &mut self.heights
+    }
+
+    /// The height at a texel, clamped to the edges
+    pub fn get(&self, texel: IVec2) -> f32 {
+        let texel = texel.clamp(IVec2::ZERO, self.size.as_ivec2() - 1);
+        self.heights[(texel.y as u32 * self.size.x + texel.x as u32) as usize]
+    }
+
+    fn index(&self, texel: IVec2) -> Option<usize> {
+        (texel.cmpge(IVec2::ZERO).all() && texel.cmplt(self.size.as_ivec2()).all())
+            .then(|| (texel.y as u32 * self.size.x + texel.x as u32) as usize)
+    }
+
+    /// The height between texels, interpolated bilinearly
+    pub fn height_at(&self, pos: Vec2) -> f32 {
+        self.height_and_gradient(pos).0
+    }
+
+    /// The slope between texels, in height units per texel
+    pub fn gradient_at(&self, pos: Vec2) -> Vec2 {
+        self.height_and_gradient(pos).1
+    }
+
+    fn height_and_gradient(&self, pos: Vec2) -> (f32, Vec2) {
+        let cell = pos.floor();
+        let t = pos - cell;
+        let cell = cell.as_ivec2();
+        let h00 = self.get(cell);
+        let h10 = self.get(cell + IVec2::X);
+        let h01 = self.get(cell + IVec2::Y);
+        let h11 = self.get(cell + IVec2::ONE);
+        let gradient = vec2(
+            (h10 - h00) * (1.0 - t.y) + (h11 - h01) * t.y,
+            (h01 - h00) * (1.0 - t.x) + (h11 - h10) * t.x,
+        );
+        let height = h00 * (1.0 - t.x) * (1.0 - t.y)
+            + h10 * t.x * (1.0 - t.y)
+            + h01 * (1.0 - t.x) * t.y
+            + h11 * t.x * t.y;
+        (height, gradient)
+    }
+
+    /// Adds `amount` to the four texels around `pos`, weighted bilinearly
+    fn add_bilinear(&mut self, pos: Vec2, amount: f32) {
+        let cell = pos.floor();
+        let t = pos - cell;
+        let cell = cell.as_ivec2();
+        for (offset, weight) in [
+            (IVec2::ZERO, (1.0 - t.x) * (1.0 - t.y)),
+            (IVec2::X, t.x * (1.0 - t.y)),
+            (IVec2::Y, (1.0 - t.x) * t.y),
+            (IVec2::ONE, t.x * t.y),
+        ] {
+            if let Some(i) = self.index(cell + offset) {
+                self.heights[i] += amount * weight;
+            }
+        }
+    }
+
+    /// Simulates water droplets running downhill, eroding soil where they
+    /// speed up and depositing it where they slow down, which carves valleys
+    /// and leaves sediment fans
+    ///
+    /// Droplets run one after another in a fixed order, using only
+    /// arithmetic and square roots, so the same settings give the same
+    /// terrain on every machine.
+    pub fn erode_hydraulic(&mut self, settings: &HydraulicErosion) {
+        let mut rng = Rng::new(settings.seed);
+        let max = (self.size - 1).as_vec2();
+        for _ in 0..settings.droplets {
+            let mut pos = vec2(rng.next_f32(), rng.next_f32()) * max;
+            let mut direction = Vec2::ZERO;
+            let mut speed = 1.0;
+            let mut water = 1.0;
+            let mut sediment = 0.0;
+
+            for _ in 0..settings.max_lifetime {
+                let (height, gradient) = self.height_and_gradient(pos);
+                direction = direction * settings.inertia - gradient * (1.0 - settings.inertia);
+                let Some(step) = direction.try_normalize() else {
+                    break;
+                };
+                direction = step;
+                let next = pos + direction;
+                if next.cmplt(Vec2::ZERO).any() || next.cmpgt(max).any() {
+                    // flowing off the map, the sediment goes with it
+                    break;
+                }
+
+                let delta = self.height_at(next) - height;
+                let capacity = (-delta * speed * water * settings.sediment_capacity)
+                    .max(settings.min_sediment_capacity);
+                if delta > 0.0 || sediment > capacity {
+                    // fill the pit uphill, or drop what's too much to carry
+                    let deposit = if delta > 0.0 {
+                        delta.min(sediment)
+                    } else {
+                        (sediment - capacity) * settings.deposit_speed
+                    };
+                    sediment -= deposit;
+                    self.add_bilinear(pos, deposit);
+                } else {
+                    // never dig deeper than the drop, or it'd carve a pit
+                    let erode = ((capacity - sediment) * settings.erode_speed).min(-delta);
+                    sediment += erode;
+                    self.add_bilinear(pos, -erode);
+                }
+
+                speed = (speed * speed - delta * settings.gravity).max(0.0).sqrt();
+                water *= 1.0 - settings.evaporate_speed;
+                pos = next;
+            }
+        }
+    }
+
+    /// Moves material from texels to lower neighbors where the slope is
+    /// steeper than [`ThermalErosion::talus`], like loose rock sliding down
+    /// until it settles into scree slopes
+    ///
+    /// Each pass computes all moves before applying them, so the result
+    /// doesn't depend on the order texels are visited in. The total volume
+    /// stays the same.
+    pub fn erode_thermal(&mut self, settings: &ThermalErosion) {
+        let mut changes = vec![0.0; self.heights.len()];
+        for _ in 0..settings.iterations {
+            changes.fill(0.0);
+            for y in 0..self.size.y as i32 {
+                for x in 0..self.size.x as i32 {
+                    let texel = ivec2(x, y);
+                    let height = self.get(texel);
+                    let mut total_excess = 0.0;
+                    let mut max_excess = 0.0_f32;
+                    for offset in NEIGHBORS {
+                        if let Some(i) = self.index(texel + offset) {
+                            let excess = height - self.heights[i] - talus(settings, offset);
+                            if excess > 0.0 {
+                                total_excess += excess;
+                                max_excess = max_excess.max(excess);
+                            }
+                        }
+                    }
+                    if total_excess == 0.0 {
+                        continue;
+                    }
+                    // move half the steepest excess, so the texel doesn't end
+                    // up below its neighbors, shared by how much lower they are
+                    let moved = settings.rate * max_excess * 0.5;
+                    for offset in NEIGHBORS {
+                        if let Some(i) = self.index(texel + offset) {
+                            let excess = height - self.heights[i] - talus(settings, offset);
+                            if excess > 0.0 {
+                                changes[i] += moved * excess / total_excess;
+                            }
+                        }
+                    }
+                    changes[self.index(texel).unwrap()] -= moved;
+                }
+            }
+            for (height, change) in self.heights.iter_mut().zip(&changes) {
+                *height += change;
+            }
+        }
+    }
+
+    /// Follows the steepest descent from `source`, texel by texel, until it
+    /// reaches a pit or the edge of the map, or has taken `max_length` steps
+    ///
+    /// Erode the heightfield first, rivers on raw noise end in pits quickly.
+    pub fn trace_river(&self, source: UVec2, max_length: usize) -> Vec<UVec2> {
+        let mut river = vec![source];
+        let mut texel = source.as_ivec2();
+        while river.len() <= max_length {
+            let height = self.get(texel);
+            let lowest = NEIGHBORS
+                .into_iter()
+                .filter_map(|offset| {
+                    let neighbor = texel + offset;
+                    self.index(neighbor)?;
+                    let drop = (height - self.get(neighbor)) / offset.as_vec2().length();
+                    (drop > 0.0).then_some((neighbor, drop))
+                })
+                // the first one on ties, so rivers don't depend on float noise
+                .fold(
+                    None,
+                    |best: Option<(IVec2, f32)>, (neighbor, drop)| match best {
+                        Some((_, best_drop)) if best_drop >= drop => best,
+                        _ => Some((neighbor, drop)),
+                    },
+                );
+            let Some((next, _)) = lowest else {
+                break;
+            };
+            texel = next;
+            river.push(texel.as_uvec2());
+        }
+        river
+    }
+
+    /// Lowers the texels of a river by `depth`, and further where needed so
+    /// it only ever flows downhill
+    pub fn carve_river(&mut self, river: &[UVec2], depth: f32) {
+        let mut previous = f32::INFINITY;
+        for texel in river {
+            if let Some(i) = self.index(texel.as_ivec2()) {
+                let height = (self.heights[i] - depth).min(previous);
+                self.heights[i] = height;
+                previous = height;
+            }
+        }
+    }
+}
+
+/// The talus slope towards a neighbor, diagonals are farther away
+fn talus(settings: &ThermalErosion, offset: IVec2) -> f32 {
+    if offset.x != 0 && offset.y != 0 {
+        settings.talus * std::f32::consts::SQRT_2
+    } else {
+        settings.talus
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fbm_simplex_2d_seeded;
+
+    fn terrain() -> Heightfield {
+        Heightfield::from_fn(UVec2::splat(48), |texel| {
+            fbm_simplex_2d_seeded(texel.as_vec2() * 0.04, 4, 2.0, 0.5, 1.0) * 20.0
+        })
+    }
+
+    fn volume(terrain: &Heightfield) -> f32 {
+        terrain.heights().iter().sum()
+    }
+
+    #[test]
+    fn hydraulic_is_deterministic() {
+        let settings = HydraulicErosion {
+            droplets: 2000,
+            seed: 5,
+            ..Default::default()
+        };
+        let mut a = terrain();
+        a.erode_hydraulic(&settings);
+        let mut b = terrain();
+        b.erode_hydraulic(&settings);
+        assert_eq!(a, b);
+        assert_ne!(a, terrain());
+
+        let mut c = terrain();
+        c.erode_hydraulic(&HydraulicErosion {
+            seed: 6,
+            ..settings
+        });
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn hydraulic_removes_material() {
+        let mut eroded = terrain();
+        eroded.erode_hydraulic(&HydraulicErosion {
+            droplets: 2000,
+            ..Default::default()
+        });
+        // sediment only leaves with the droplets that run off the map
+        assert!(volume(&eroded) <= volume(&terrain()) + 1e-2);
+    }
+
+    #[test]
+    fn thermal_flattens_steep_slopes() {
+        let steepest = |terrain: &Heightfield| {
+            let mut steepest = 0.0_f32;
+            for y in 0..47 {
+                for x in 0..47 {
+                    let h = terrain.get(ivec2(x, y));
+                    steepest = steepest
+                        .max((h - terrain.get(ivec2(x + 1, y))).abs())
+                        .max((h - terrain.get(ivec2(x, y + 1))).abs());
+                }
+            }
+            steepest
+        };
+        let settings = ThermalErosion {
+            iterations: 200,
+            talus: 0.5,
+            rate: 0.5,
+        };
+        let mut eroded = terrain();
+        let before = steepest(&eroded);
+        eroded.erode_thermal(&settings);
+        let after = steepest(&eroded);
+        assert!(after < before * 0.5, "{before} {after}");
+        assert!((volume(&eroded) - volume(&terrain())).abs() < 1e-1);
+    }
+
+    #[test]
+    fn rivers_flow_downhill() {
+        let mut terrain = terrain();
+        let river = terrain.trace_river(UVec2::new(20, 30), 100);
+        assert!(river.len() > 1);
+        for pair in river.windows(2) {
+            let [a, b] = [pair[0], pair[1]].map(|t| terrain.get(t.as_ivec2()));
+            assert!(b < a);
+        }
+        terrain.carve_river(&river, 0.5);
+        for pair in river.windows(2) {
+            let [a, b] = [pair[0], pair[1]].map(|t| terrain.get(t.as_ivec2()));
+            assert!(b <= a);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::{
+    asset::{Asset, Handle, uuid_handle},
+    pbr::{ExtendedMaterial, MaterialExtension, StandardMaterial},
+    reflect::Reflect,
+    render::render_resource::{AsBindGroup, ShaderType},
+    shader::{Shader, ShaderRef},
+};
+
+use crate::{NoiseParams, NoiseParamsUniform};
+
+/// A [`StandardMaterial`] with albedo, roughness and normals perturbed by noise
+pub type NoiseStandardMaterial = ExtendedMaterial<StandardMaterial, NoiseExtension>;
+
+/// Perturbs the PBR inputs of a [`StandardMaterial`] with noise, see
+/// [`NoiseStandardMaterial`]
+///
+/// The noise is sampled in world space with triplanar projection, see
+/// [`NoiseParams::sample_triplanar`], so it doesn't stretch on meshes
+/// without good UVs.
+///
+/// Registered by [`NoisyShaderPlugin`](crate::NoisyShaderPlugin) when
+/// `materials` is set.
+///
+/// ```
+/// # use bevy::{pbr::{ExtendedMaterial, StandardMaterial}, color::palettes::css};
+/// # use noisy_bevy::{NoiseExtension, NoiseGradient, NoiseParams, NoiseType};
+/// let rock = ExtendedMaterial {
+///     base: StandardMaterial::default(),
+///     extension: NoiseExtension {
+///         noise: NoiseParams {
+///             noise_type: NoiseType::Worley,
+///             frequency: 4.0,
+///             gradient: NoiseGradient::linear([(0.0, css::DIM_GRAY), (1.0, css::SILVER)]),
+///             ..Default::default()
+///         },
+///         albedo: 1.0,
+///         normal: 0.3,
+///         ..Default::default()
+///     },
+/// };
+/// ```
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+#[uniform(100, NoiseExtensionUniform)]
+pub struct NoiseExtension {
+    /// Noise and the colors for albedo
+    pub noise: NoiseParams,
+    /// How much the gradient color replaces the base color, between 0 and 1
+    pub albedo: f32,
+    /// Added to the perceptual roughness, scaled by the noise value
+    pub roughness: f32,
+    /// How much the noise bumps the normals
+    pub normal: f32,
+    /// Higher values give narrower transitions between the triplanar
+    /// projections
+    pub triplanar_sharpness: f32,
+}
+
+impl Default for NoiseExtension {
+    /// Fractal noise bumping the normals a little
+    fn default() -> Self {
+        Self {
+            noise: NoiseParams {
+                noise_type: crate::NoiseType::Fbm,
+                ..Default::default()
+            },
+            albedo: 0.0,
+            roughness: 0.0,
+            normal: 0.2,
+            triplanar_sharpness: 4.0,
+        }
+    }
+}
+
+/// The GPU representation of [`NoiseExtension`], `NoiseExtension` in the
+/// `noisy_bevy/noise_extension.wgsl` shader
+#[derive(Clone, Default, ShaderType)]
+pub struct NoiseExtensionUniform {
+    /// See [`NoiseExtension::noise`]
+    pub noise: NoiseParamsUniform,
+    /// See [`NoiseExtension::albedo`]
+    pub albedo: f32,
+    /// See [`NoiseExtension::roughness`]
+    pub roughness: f32,
+    /// See [`NoiseExtension::normal`]
+    pub normal: f32,
+    /// See [`NoiseExtension::triplanar_sharpness`]
+    pub triplanar_sharpness: f32,
+}
+
+impl From<&NoiseExtension> for NoiseExtensionUniform {
+    fn from(extension: &NoiseExtension) -> Self {
+        Self {
+            noise: (&extension.noise).into(),
+            albedo: extension.albedo,
+            roughness: extension.roughness,
+            normal: extension.normal,
+            triplanar_sharpness: extension.triplanar_sharpness,
+        }
+    }
+}
+
+impl MaterialExtension for NoiseExtension {
+    fn fragment_shader() -> ShaderRef {
+        NOISE_EXTENSION_SHADER_HANDLE.into()
+    }
+
+    fn deferred_fragment_shader() -> ShaderRef {
+        NOISE_EXTENSION_SHADER_HANDLE.into()
+    }
+}
+
+pub(crate) const NOISE_EXTENSION_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("3a9d6e14-c8b2-4f57-9e0a-5b1c7d2f8e63");
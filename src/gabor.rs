@@ -0,0 +1,240 @@
+use std::f32::consts::PI;
+
+use bevy::{
+    math::{IVec2, Vec2, ivec2, vec2},
+    reflect::Reflect,
+};
+
+use crate::rng::{Rng, hash_2d};
+
+/// Sparse convolution Gabor noise in two dimensions, for directional textures
+/// like wood grain, brushed metal and ripples
+///
+/// Random impulses are scattered over a grid, each adding a Gabor kernel: a
+/// cosine wave along `orientation`, faded out by a gaussian. Following Lagae
+/// et al., "Procedural Noise using Sparse Gabor Convolution".
+///
+/// The impulses are placed with the same integer hashes on the CPU and in the
+/// `noisy_bevy::gabor` shader module.
+///
+/// ```
+/// # use bevy::math::vec2;
+/// # use noisy_bevy::GaborNoise;
+/// let grain = GaborNoise {
+///     orientation: 0.1,
+///     frequency: 4.0,
+///     bandwidth: 1.5,
+///     ..Default::default()
+/// };
+/// let v = grain.sample(vec2(0.3, 1.2));
+/// ```
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct GaborNoise {
+    /// Direction the waves travel in, in radians from the x axis. The stripes
+    /// run perpendicular to it.
+    pub orientation: f32,
+    /// Wave periods per world unit
+    pub frequency: f32,
+    /// Width of the gaussian envelope in frequency space, at least
+    /// [`MIN_GABOR_BANDWIDTH`]. The kernels reach `1 / bandwidth` world units,
+    /// so lower values give longer, more regular stripes.
+    pub bandwidth: f32,
+    /// Average number of impulses per `1 / bandwidth` sized grid cell, up to
+    /// [`MAX_GABOR_IMPULSE_DENSITY`]. More impulses give smoother noise, each
+    /// sample visits 9 cells worth of them.
+    pub impulse_density: f32,
+    /// Noise with different seeds is uncorrelated
+    pub seed: u32,
+}
+
+/// Smaller, zero and negative [`GaborNoise::bandwidth`] values are clamped to
+/// this
+pub const MIN_GABOR_BANDWIDTH: f32 = 0.001;
+
+/// Larger [`GaborNoise::impulse_density`] values are clamped to this, to bound
+/// the cost of a sample
+pub const MAX_GABOR_IMPULSE_DENSITY: f32 = 64.0;
+
+impl Default for GaborNoise {
+    /// Stripes along the y axis, one per world unit
+    fn default() -> Self {
+        Self {
+            orientation: 0.0,
+            frequency: 1.0,
+            bandwidth: 0.5,
+            impulse_density: 12.0,
+            seed: 0,
+        }
+    }
+}
+
+impl GaborNoise {
+    /// Samples the noise, roughly in the range -1..1
+    pub fn sample(&self, pos: Vec2) -> f32 {
+        let noise = self.clamped();
+        let radius = 1.0 / noise.bandwidth;
+        let direction = Vec2::from_angle(self.orientation);
+        let cell_pos = pos * noise.bandwidth;
+        let cell = cell_pos.floor();
+        let offset = cell_pos - cell;
+        let cell = cell.as_ivec2();
+
+        let mut sum = 0.0;
+        for y in -1..=1 {
+            for x in -1..=1 {
+                let neighbor = ivec2(x, y);
+                sum += noise.cell(
+                    cell + neighbor,
+                    (offset - neighbor.as_vec2()) * radius,
+                    direction,
+                );
+            }
+        }
+        sum * noise.normalization()
+    }
+
+    /// The noise with the bandwidth and impulse density in their limits
+    fn clamped(&self) -> Self {
+        Self {
+            bandwidth: self.bandwidth.max(MIN_GABOR_BANDWIDTH),
+            impulse_density: self.impulse_density.clamp(0.0, MAX_GABOR_IMPULSE_DENSITY),
+            ..*self
+        }
+    }
+
+    /// Sum of the kernels of the impulses in `cell`, `pos` being relative to
+    /// the corner of the cell
+    fn cell(&self, cell: IVec2, pos: Vec2, direction: Vec2) -> f32 {
+        let radius = 1.0 / self.bandwidth;
+        let mut rng = Rng::new(hash_2d(cell, self.seed));
+        // round randomly, so fractional densities average out
+        let density = self.impulse_density;
+        let extra = rng.next_f32() < density.fract();
+        let impulses = density as u32 + extra as u32;
+
+        let mut sum = 0.0;
+        for _ in 0..impulses {
+            let impulse = vec2(rng.next_f32(), rng.next_f32()) * radius;
+            let weight = if rng.next_f32() < 0.5 { -1.0 } else { 1.0 };
+            let d = pos - impulse;
+            let r2 = d.length_squared();
+            if r2 < radius * radius {
+                let envelope = (-PI * self.bandwidth * self.bandwidth * r2).exp();
+                let wave = (2.0 * PI * self.frequency * d.dot(direction)).cos();
+                sum += weight * envelope * wave;
+            }
+        }
+        sum
+    }
+
+    /// Scales the sum of kernels to three standard deviations, see the
+    /// variance in section 3.3 of Lagae et al.
+    fn normalization(&self) -> f32 {
+        let ratio = self.frequency / self.bandwidth;
+        let variance = self.impulse_density / 4.0 * (1.0 + (-2.0 * PI * ratio * ratio).exp());
+        if variance > 0.0 {
+            1.0 / (3.0 * variance.sqrt())
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(all(feature = "gpu", feature = "image"))]
+mod gpu {
+    use bevy::render::render_resource::ShaderType;
+
+    use super::GaborNoise;
+
+    /// The GPU representation of [`GaborNoise`], `GaborNoise` in the
+    /// `noisy_bevy::gabor` shader module
+    #[derive(Clone, Copy, Debug, Default, ShaderType)]
+    pub struct GaborNoiseUniform {
+        /// See [`GaborNoise::orientation`]
+        pub orientation: f32,
+        /// See [`GaborNoise::frequency`]
+        pub frequency: f32,
+        /// See [`GaborNoise::bandwidth`]
+        pub bandwidth: f32,
+        /// See [`GaborNoise::impulse_density`]
+        pub impulse_density: f32,
+        /// See [`GaborNoise::seed`]
+        pub seed: u32,
+    }
+
+    impl From<&GaborNoise> for GaborNoiseUniform {
+        fn from(noise: &GaborNoise) -> Self {
+            Self {
+                orientation: noise.orientation,
+                frequency: noise.frequency,
+                bandwidth: noise.bandwidth,
+                impulse_density: noise.impulse_density,
+                seed: noise.seed,
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "gpu", feature = "image"))]
+pub use gpu::*;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn samples(noise: &GaborNoise) -> Vec<f32> {
+        (0..40)
+            .flat_map(|y| (0..40).map(move |x| vec2(x as f32, y as f32) * 0.13 - 2.0))
+            .map(|p| noise.sample(p))
+            .collect()
+    }
+
+    #[test]
+    fn roughly_normalized() {
+        let noise = GaborNoise::default();
+        let values = samples(&noise);
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        let max = values.iter().fold(0.0_f32, |max, v| max.max(v.abs()));
+        assert!(mean.abs() < 0.15, "{mean}");
+        assert!(max > 0.3 && max < 1.5, "{max}");
+        assert_ne!(values, samples(&GaborNoise { seed: 1, ..noise }));
+    }
+
+    #[test]
+    fn oriented() {
+        // waves along x: changes fast along x, slowly along y
+        let noise = GaborNoise {
+            frequency: 2.0,
+            ..Default::default()
+        };
+        let (mut along, mut across) = (0.0, 0.0);
+        for i in 0..200 {
+            let p = vec2(i as f32 * 0.731, i as f32 * -0.377);
+            let v = noise.sample(p);
+            along += (noise.sample(p + vec2(0.1, 0.0)) - v).abs();
+            across += (noise.sample(p + vec2(0.0, 0.1)) - v).abs();
+        }
+        assert!(along > across * 2.0, "{along} {across}");
+    }
+
+    #[test]
+    fn clamps_settings() {
+        let dense = GaborNoise {
+            impulse_density: 1e9,
+            ..Default::default()
+        };
+        let max = GaborNoise {
+            impulse_density: MAX_GABOR_IMPULSE_DENSITY,
+            ..dense
+        };
+        assert_eq!(samples(&dense), samples(&max));
+
+        for bandwidth in [0.0, -1.0] {
+            let noise = GaborNoise {
+                bandwidth,
+                ..Default::default()
+            };
+            assert!(samples(&noise).iter().all(|v| v.is_finite()));
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use bevy::{
     app::{App, Plugin},
     asset::{Handle, load_internal_asset, uuid_handle},
-    shader::Shader,
+    ecs::resource::Resource,
+    shader::{Shader, ShaderDefVal},
 };
 
 /// Adds noise library as a wgsl import
@@ -11,18 +12,493 @@ use bevy::{
 /// ```wgsl
 /// #import noisy_bevy
 /// ```
-pub struct NoisyShaderPlugin;
+///
+/// Or pick a sub-module to avoid pulling in everything:
+///
+/// ```wgsl
+/// #import noisy_bevy::simplex::simplex_noise_2d
+/// #import noisy_bevy::fbm::fbm_simplex_2d
+/// #import noisy_bevy::warp::warp_2d
+/// #import noisy_bevy::worley::worley_2d
+/// #import noisy_bevy::hash::permute_3_
+/// #import noisy_bevy::gradient::{NoiseGradient, sample_gradient}
+/// #import noisy_bevy::projection::{triplanar_fbm_2d, sphere_fbm_3d}
+/// #import noisy_bevy::animated::{AnimatedNoise, animated_noise_3d}
+/// #import noisy_bevy::gabor::{GaborNoise, gabor_noise_2d}
+/// #import noisy_bevy::material::{NoiseParams, sample_noise, noise_color}
+/// ```
+///
+/// The plugin can be added more than once, for instance by an app and a
+/// library it depends on. The requested modules and shader defs are merged
+/// into [`NoisySettings`].
+///
+/// There is no half precision option, the permutation hashes need
+/// intermediate values up to 289² · 34, far outside the range of `f16`.
+#[derive(Clone, Debug)]
+pub struct NoisyShaderPlugin {
+    /// Shader modules to load, their dependencies are loaded as well
+    pub modules: Vec<NoisyModule>,
+    /// Normalize simplex gradients with an exact `inverseSqrt` instead of a
+    /// cheaper Taylor approximation, by defining `NOISY_HIGH_QUALITY_GRADIENTS`.
+    ///
+    /// Defaults to whether the `high-quality-gradients` feature is enabled, so
+    /// the shaders match the CPU functions.
+    pub high_quality_gradients: bool,
+    /// Additional shader defs the modules are compiled with, for instance
+    /// `NOISY_MAX_WARP_ITERATIONS`
+    pub shader_defs: Vec<ShaderDefVal>,
+    /// Register `NoiseMaterial2d`, and `NoiseMaterial` and
+    /// `NoiseStandardMaterial`, depending on which of the `material-2d` and
+    /// `material-3d` features are enabled. Loads
+    /// [`NoisyModule::Material`] too.
+    ///
+    /// Needs bevy's render plugins, so it's off by default.
+    pub materials: bool,
+}
+
+impl Default for NoisyShaderPlugin {
+    fn default() -> Self {
+        Self {
+            modules: NoisyModule::ALL.to_vec(),
+            high_quality_gradients: cfg!(feature = "high-quality-gradients"),
+            shader_defs: Vec::new(),
+            materials: false,
+        }
+    }
+}
+
+impl NoisyShaderPlugin {
+    /// The shader defs the noise modules are compiled with
+    pub fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        let mut shader_defs = self.shader_defs.clone();
+        if self.high_quality_gradients {
+            shader_defs.push("NOISY_HIGH_QUALITY_GRADIENTS".into());
+        }
+        shader_defs
+    }
+}
 
 impl Plugin for NoisyShaderPlugin {
     fn build(&self, app: &mut App) {
+        let settings = {
+            let mut settings = app.world_mut().get_resource_or_init::<NoisySettings>();
+            settings.merge(self);
+            settings.clone()
+        };
+
+        // reload everything, so modules loaded by an earlier instance of the
+        // plugin pick up the merged shader defs too
+        for module in &settings.modules {
+            module.load(app, settings.shader_defs.clone());
+        }
+
+        #[cfg(any(feature = "material-2d", feature = "material-3d"))]
+        if settings.materials {
+            crate::material::add_materials(app);
+        }
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
+
+/// The noisy_bevy shader modules and shader defs in use, merged from all
+/// added [`NoisyShaderPlugin`]s.
+///
+/// Insert it before adding the plugin to load modules or set shader defs
+/// regardless of what the plugins ask for. Changes after the plugins are
+/// built have no effect.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct NoisySettings {
+    /// Loaded modules, including dependencies, in dependency order
+    pub modules: Vec<NoisyModule>,
+    /// Shader defs for all modules. When plugins disagree about the value of
+    /// a def, the last one added wins.
+    pub shader_defs: Vec<ShaderDefVal>,
+    /// Whether the noise materials are registered, set if any plugin asks for
+    /// them
+    pub materials: bool,
+}
+
+impl NoisySettings {
+    fn merge(&mut self, plugin: &NoisyShaderPlugin) {
+        for module in &plugin.modules {
+            self.modules.extend(module.with_dependencies());
+        }
+        if plugin.materials {
+            self.materials = true;
+            self.modules
+                .extend(NoisyModule::Material.with_dependencies());
+            // `CloudMaterial` is only registered with material-3d
+            if cfg!(feature = "material-3d") {
+                self.modules.extend(NoisyModule::Cloud.with_dependencies());
+            }
+        }
+        self.modules.sort();
+        self.modules.dedup();
+
+        for shader_def in plugin.shader_defs() {
+            let name = shader_def_name(&shader_def).to_string();
+            self.shader_defs.retain(|d| shader_def_name(d) != name);
+            self.shader_defs.push(shader_def);
+        }
+    }
+}
+
+fn shader_def_name(shader_def: &ShaderDefVal) -> &str {
+    match shader_def {
+        ShaderDefVal::Bool(name, _) | ShaderDefVal::Int(name, _) | ShaderDefVal::UInt(name, _) => {
+            name
+        }
+    }
+}
+
+/// A noisy_bevy shader module
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NoisyModule {
+    /// `noisy_bevy::hash`, permutation hashes
+    Hash,
+    /// `noisy_bevy::simplex`, simplex noise
+    Simplex,
+    /// `noisy_bevy::fbm`, fractional brownian motion
+    Fbm,
+    /// `noisy_bevy::warp`, domain warping
+    Warp,
+    /// `noisy_bevy::worley`, cellular noise
+    Worley,
+    /// `noisy_bevy::gradient`, color gradients, see `NoiseGradient`
+    Gradient,
+    /// `noisy_bevy::projection`, triplanar and spherical sampling
+    Projection,
+    /// `noisy_bevy::normal_map`, normals from height slopes, and
+    /// [`NORMAL_MAP_COMPUTE_SHADER_HANDLE`]
+    NormalMap,
+    /// `noisy_bevy::cloud`, raymarched cloud layers, see `CloudLayer`
+    Cloud,
+    /// `noisy_bevy::animated`, noise evolving over time, see `AnimatedNoise`
+    Animated,
+    /// `noisy_bevy::gabor`, sparse convolution Gabor noise, see `GaborNoise`
+    Gabor,
+    /// `noisy_bevy`, forwards the simplex, fbm, warp and worley modules
+    Umbrella,
+    /// `noisy_bevy::material`, the sampling used by the noise materials
+    Material,
+}
+
+impl NoisyModule {
+    /// All modules, in dependency order
+    pub const ALL: [NoisyModule; 13] = [
+        NoisyModule::Hash,
+        NoisyModule::Simplex,
+        NoisyModule::Fbm,
+        NoisyModule::Warp,
+        NoisyModule::Worley,
+        NoisyModule::Gradient,
+        NoisyModule::Projection,
+        NoisyModule::NormalMap,
+        NoisyModule::Cloud,
+        NoisyModule::Animated,
+        NoisyModule::Gabor,
+        NoisyModule::Umbrella,
+        NoisyModule::Material,
+    ];
+
+    /// The path the module is imported with in wgsl
+    pub fn import_path(self) -> &'static str {
+        match self {
+            NoisyModule::Hash => "noisy_bevy::hash",
+            NoisyModule::Simplex => "noisy_bevy::simplex",
+            NoisyModule::Fbm => "noisy_bevy::fbm",
+            NoisyModule::Warp => "noisy_bevy::warp",
+            NoisyModule::Worley => "noisy_bevy::worley",
+            NoisyModule::Gradient => "noisy_bevy::gradient",
+            NoisyModule::Projection => "noisy_bevy::projection",
+            NoisyModule::NormalMap => "noisy_bevy::normal_map",
+            NoisyModule::Cloud => "noisy_bevy::cloud",
+            NoisyModule::Animated => "noisy_bevy::animated",
+            NoisyModule::Gabor => "noisy_bevy::gabor",
+            NoisyModule::Umbrella => "noisy_bevy",
+            NoisyModule::Material => "noisy_bevy::material",
+        }
+    }
+
+    /// Modules imported by this module
+    pub fn dependencies(self) -> &'static [NoisyModule] {
+        match self {
+            NoisyModule::Hash => &[],
+            NoisyModule::Simplex => &[NoisyModule::Hash],
+            NoisyModule::Fbm => &[NoisyModule::Simplex],
+            NoisyModule::Warp => &[NoisyModule::Fbm],
+            NoisyModule::Worley => &[NoisyModule::Hash],
+            NoisyModule::Gradient => &[],
+            NoisyModule::Projection => &[NoisyModule::Simplex, NoisyModule::Fbm],
+            NoisyModule::NormalMap => &[],
+            NoisyModule::Cloud => &[NoisyModule::Fbm],
+            NoisyModule::Animated => &[NoisyModule::Fbm],
+            NoisyModule::Gabor => &[NoisyModule::Hash],
+            NoisyModule::Umbrella => &[
+                NoisyModule::Simplex,
+                NoisyModule::Fbm,
+                NoisyModule::Warp,
+                NoisyModule::Worley,
+            ],
+            NoisyModule::Material => &[
+                NoisyModule::Simplex,
+                NoisyModule::Fbm,
+                NoisyModule::Warp,
+                NoisyModule::Worley,
+                NoisyModule::Gradient,
+                NoisyModule::Projection,
+            ],
+        }
+    }
+
+    /// This module and everything it imports, directly or indirectly
+    pub fn with_dependencies(self) -> Vec<NoisyModule> {
+        let mut modules = vec![self];
+        for dependency in self.dependencies() {
+            modules.extend(dependency.with_dependencies());
+        }
+        modules.sort();
+        modules.dedup();
+        modules
+    }
+
+    /// Handle of the module's shader asset
+    pub fn handle(self) -> Handle<Shader> {
+        match self {
+            NoisyModule::Hash => NOISY_HASH_SHADER_HANDLE,
+            NoisyModule::Simplex => NOISY_SIMPLEX_SHADER_HANDLE,
+            NoisyModule::Fbm => NOISY_FBM_SHADER_HANDLE,
+            NoisyModule::Warp => NOISY_WARP_SHADER_HANDLE,
+            NoisyModule::Worley => NOISY_WORLEY_SHADER_HANDLE,
+            NoisyModule::Gradient => NOISY_GRADIENT_SHADER_HANDLE,
+            NoisyModule::Projection => NOISY_PROJECTION_SHADER_HANDLE,
+            NoisyModule::NormalMap => NOISY_NORMAL_MAP_SHADER_HANDLE,
+            NoisyModule::Cloud => NOISY_CLOUD_SHADER_HANDLE,
+            NoisyModule::Animated => NOISY_ANIMATED_SHADER_HANDLE,
+            NoisyModule::Gabor => NOISY_GABOR_SHADER_HANDLE,
+            NoisyModule::Umbrella => NOISY_SHADER_HANDLE,
+            NoisyModule::Material => NOISY_MATERIAL_SHADER_HANDLE,
+        }
+    }
+
+    fn load(self, app: &mut App, shader_defs: Vec<ShaderDefVal>) {
         // workaround: embedded_asset is broken in bevy 0.12.0
-        load_internal_asset!(
-            app,
-            NOISY_SHADER_HANDLE,
-            "../assets/noisy_bevy.wgsl",
-            Shader::from_wgsl
-        );
+        match self {
+            NoisyModule::Hash => load_internal_asset!(
+                app,
+                NOISY_HASH_SHADER_HANDLE,
+                "../assets/noisy_bevy/hash.wgsl",
+                Shader::from_wgsl_with_defs,
+                shader_defs
+            ),
+            NoisyModule::Simplex => load_internal_asset!(
+                app,
+                NOISY_SIMPLEX_SHADER_HANDLE,
+                "../assets/noisy_bevy/simplex.wgsl",
+                Shader::from_wgsl_with_defs,
+                shader_defs
+            ),
+            NoisyModule::Fbm => load_internal_asset!(
+                app,
+                NOISY_FBM_SHADER_HANDLE,
+                "../assets/noisy_bevy/fbm.wgsl",
+                Shader::from_wgsl_with_defs,
+                shader_defs
+            ),
+            NoisyModule::Warp => load_internal_asset!(
+                app,
+                NOISY_WARP_SHADER_HANDLE,
+                "../assets/noisy_bevy/warp.wgsl",
+                Shader::from_wgsl_with_defs,
+                shader_defs
+            ),
+            NoisyModule::Worley => load_internal_asset!(
+                app,
+                NOISY_WORLEY_SHADER_HANDLE,
+                "../assets/noisy_bevy/worley.wgsl",
+                Shader::from_wgsl_with_defs,
+                shader_defs
+            ),
+            NoisyModule::Gradient => load_internal_asset!(
+                app,
+                NOISY_GRADIENT_SHADER_HANDLE,
+                "../assets/noisy_bevy/gradient.wgsl",
+                Shader::from_wgsl_with_defs,
+                shader_defs
+            ),
+            NoisyModule::Projection => load_internal_asset!(
+                app,
+                NOISY_PROJECTION_SHADER_HANDLE,
+                "../assets/noisy_bevy/projection.wgsl",
+                Shader::from_wgsl_with_defs,
+                shader_defs
+            ),
+            NoisyModule::NormalMap => {
+                load_internal_asset!(
+                    app,
+                    NOISY_NORMAL_MAP_SHADER_HANDLE,
+                    "../assets/noisy_bevy/normal_map.wgsl",
+                    Shader::from_wgsl_with_defs,
+                    shader_defs.clone()
+                );
+                load_internal_asset!(
+                    app,
+                    NORMAL_MAP_COMPUTE_SHADER_HANDLE,
+                    "../assets/noisy_bevy/normal_map_compute.wgsl",
+                    Shader::from_wgsl_with_defs,
+                    shader_defs
+                );
+            }
+            NoisyModule::Cloud => load_internal_asset!(
+                app,
+                NOISY_CLOUD_SHADER_HANDLE,
+                "../assets/noisy_bevy/cloud.wgsl",
+                Shader::from_wgsl_with_defs,
+                shader_defs
+            ),
+            NoisyModule::Animated => load_internal_asset!(
+                app,
+                NOISY_ANIMATED_SHADER_HANDLE,
+                "../assets/noisy_bevy/animated.wgsl",
+                Shader::from_wgsl_with_defs,
+                shader_defs
+            ),
+            NoisyModule::Gabor => load_internal_asset!(
+                app,
+                NOISY_GABOR_SHADER_HANDLE,
+                "../assets/noisy_bevy/gabor.wgsl",
+                Shader::from_wgsl_with_defs,
+                shader_defs
+            ),
+            NoisyModule::Umbrella => load_internal_asset!(
+                app,
+                NOISY_SHADER_HANDLE,
+                "../assets/noisy_bevy.wgsl",
+                Shader::from_wgsl_with_defs,
+                shader_defs
+            ),
+            NoisyModule::Material => load_internal_asset!(
+                app,
+                NOISY_MATERIAL_SHADER_HANDLE,
+                "../assets/noisy_bevy/material.wgsl",
+                Shader::from_wgsl_with_defs,
+                shader_defs
+            ),
+        }
     }
 }
 
 const NOISY_SHADER_HANDLE: Handle<Shader> = uuid_handle!("9e85d206-7851-41d9-a04f-c4879ddd7143");
+const NOISY_HASH_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("5f0c8a1e-2b8d-4a4e-9c52-6f3b7d1e0a91");
+const NOISY_SIMPLEX_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("c3e9b6f2-7d14-4b0a-8e6d-2a5f9c1b4e37");
+const NOISY_FBM_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("8a2d4f6b-1c3e-4d5f-a7b9-0e2c4a6b8d1f");
+const NOISY_WARP_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("e47b1d93-5a2c-4f86-b0d3-9c8e7f6a5b42");
+const NOISY_WORLEY_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("2b6e9d4a-8f1c-4a3b-9d7e-5c0f2a8b6e13");
+const NOISY_GRADIENT_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("0c7e5a92-3f1d-4b8e-a6c4-9d2b7e1f5a38");
+const NOISY_PROJECTION_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("71b4e8d2-6a3f-4c95-8b0e-d4f2a9c6e157");
+const NOISY_MATERIAL_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("f5a3c7e1-0d4b-4b62-9a8f-7e2c1d6b3a50");
+const NOISY_NORMAL_MAP_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("b82f0c6d-4e19-4a7b-93d5-e1c8a7f26b04");
+const NOISY_CLOUD_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("def2865d-3fb7-46e9-90a1-f54c6507f175");
+const NOISY_ANIMATED_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("4a9c1e7b-62d8-4f35-b1a0-8e3d5c7f2b96");
+const NOISY_GABOR_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("c61f3a08-9d2e-4b7c-a5e4-17b8d0f93c2a");
+
+/// Compute shader baking a height texture into a normal map on the GPU, loaded
+/// with [`NoisyModule::NormalMap`]
+///
+/// The `bake_normal_map` entry point runs one invocation per texel in 8x8
+/// workgroups, and expects bind group 0 to hold
+///
+/// - binding 0: the heights, a `texture_2d<f32>` read from the red channel
+/// - binding 1: the normal map, a write-only `rgba8unorm` storage texture of
+///   the same size
+/// - binding 2: a uniform `NormalMapComputeSettings`
+///
+/// It matches `normal_map` on the CPU.
+pub const NORMAL_MAP_COMPUTE_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("6d1e9b37-a0c4-4f28-8e5b-3f7a2c9d1e86");
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::asset::{AssetApp, AssetPlugin, Assets};
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(AssetPlugin::default())
+            .init_asset::<Shader>();
+        app
+    }
+
+    #[test]
+    fn loads_dependencies() {
+        let mut app = app();
+        app.add_plugins(NoisyShaderPlugin {
+            modules: vec![NoisyModule::Warp],
+            ..Default::default()
+        });
+
+        let settings = app.world().resource::<NoisySettings>();
+        let expected = [
+            NoisyModule::Hash,
+            NoisyModule::Simplex,
+            NoisyModule::Fbm,
+            NoisyModule::Warp,
+        ];
+        assert_eq!(settings.modules, expected);
+
+        let shaders = app.world().resource::<Assets<Shader>>();
+        for module in NoisyModule::ALL {
+            assert_eq!(
+                shaders.contains(&module.handle()),
+                expected.contains(&module)
+            );
+        }
+    }
+
+    #[test]
+    fn added_twice_merges_settings() {
+        let mut app = app();
+        app.add_plugins(NoisyShaderPlugin {
+            modules: vec![NoisyModule::Simplex],
+            shader_defs: vec![ShaderDefVal::Int("NOISY_MAX_WARP_ITERATIONS".into(), 6)],
+            ..Default::default()
+        })
+        .add_plugins(NoisyShaderPlugin {
+            modules: vec![NoisyModule::Worley],
+            high_quality_gradients: true,
+            shader_defs: vec![ShaderDefVal::Int("NOISY_MAX_WARP_ITERATIONS".into(), 8)],
+            materials: false,
+        });
+
+        let settings = app.world().resource::<NoisySettings>();
+        assert_eq!(
+            settings.modules,
+            [NoisyModule::Hash, NoisyModule::Simplex, NoisyModule::Worley]
+        );
+        let expected_defs = vec![
+            ShaderDefVal::Int("NOISY_MAX_WARP_ITERATIONS".into(), 8),
+            "NOISY_HIGH_QUALITY_GRADIENTS".into(),
+        ];
+        assert_eq!(settings.shader_defs, expected_defs);
+
+        // modules loaded by the first plugin got the merged defs too
+        let shaders = app.world().resource::<Assets<Shader>>();
+        let simplex = shaders.get(&NoisyModule::Simplex.handle()).unwrap();
+        assert_eq!(simplex.shader_defs, expected_defs);
+    }
+}
@@ -0,0 +1,247 @@
+use bevy::{
+    color::{Color, LinearRgba, Mix},
+    reflect::Reflect,
+};
+
+/// The most stops [`NoiseGradientUniform`] holds, the wgsl `NoiseGradient`
+/// has the same limit
+pub const MAX_GRADIENT_STOPS: usize = 8;
+
+/// How [`NoiseGradient`] blends between stops
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GradientInterpolation {
+    /// Blend linearly between neighboring stops
+    #[default]
+    Linear,
+    /// Use the color of the stop below, for banded palettes
+    Step,
+}
+
+/// A color at a position in a [`NoiseGradient`]
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct GradientStop {
+    /// Position of the stop, usually between 0 and 1
+    pub position: f32,
+    /// Color at the position
+    pub color: Color,
+}
+
+/// Maps noise values to colors
+///
+/// Colors are blended in linear RGB, which is also what the GPU does, so
+/// [`NoiseGradient::sample`] matches `sample_gradient` in the
+/// `noisy_bevy::gradient` shader module.
+///
+/// ```
+/// # use bevy::color::palettes::css;
+/// # use noisy_bevy::NoiseGradient;
+/// let terrain = NoiseGradient::step([
+///     (0.0, css::NAVY),
+///     (0.45, css::SANDY_BROWN),
+///     (0.5, css::FOREST_GREEN),
+///     (0.8, css::WHITE),
+/// ]);
+/// assert_eq!(terrain.sample(0.6), css::FOREST_GREEN.into());
+/// ```
+#[derive(Reflect, Clone, Debug, PartialEq)]
+pub struct NoiseGradient {
+    /// Stops, sorted by position
+    pub stops: Vec<GradientStop>,
+    /// How to blend between stops
+    pub interpolation: GradientInterpolation,
+}
+
+impl Default for NoiseGradient {
+    /// Black at 0 to white at 1
+    fn default() -> Self {
+        Self::linear([(0.0, Color::BLACK), (1.0, Color::WHITE)])
+    }
+}
+
+impl NoiseGradient {
+    /// A gradient with the given stops, which are sorted by position
+    pub fn new(
+        stops: impl IntoIterator<Item = (f32, impl Into<Color>)>,
+        interpolation: GradientInterpolation,
+    ) -> Self {
+        let mut stops: Vec<_> = stops
+            .into_iter()
+            .map(|(position, color)| GradientStop {
+                position,
+                color: color.into(),
+            })
+            .collect();
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+        Self {
+            stops,
+            interpolation,
+        }
+    }
+
+    /// A gradient blending linearly between the stops
+    pub fn linear(stops: impl IntoIterator<Item = (f32, impl Into<Color>)>) -> Self {
+        Self::new(stops, GradientInterpolation::Linear)
+    }
+
+    /// A gradient using the color of the stop below, for banded palettes
+    pub fn step(stops: impl IntoIterator<Item = (f32, impl Into<Color>)>) -> Self {
+        Self::new(stops, GradientInterpolation::Step)
+    }
+
+    /// The color at `t`, clamped to the first and last stop
+    ///
+    /// An empty gradient is transparent black.
+    pub fn sample(&self, t: f32) -> LinearRgba {
+        let Some(first) = self.stops.first() else {
+            return LinearRgba::NONE;
+        };
+        if t <= first.position {
+            return first.color.into();
+        }
+        for pair in self.stops.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if t < b.position {
+                return match self.interpolation {
+                    GradientInterpolation::Step => a.color.into(),
+                    GradientInterpolation::Linear => {
+                        let f = (t - a.position) / (b.position - a.position);
+                        LinearRgba::from(a.color).mix(&b.color.into(), f)
+                    }
+                };
+            }
+        }
+        // not empty, checked above
+        self.stops[self.stops.len() - 1].color.into()
+    }
+}
+
+#[cfg(feature = "image")]
+mod image {
+    use bevy::{
+        asset::RenderAssetUsages,
+        color::{ColorToPacked, Srgba},
+        image::Image,
+        render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    use super::NoiseGradient;
+
+    impl NoiseGradient {
+        /// Bakes the gradient between 0 and 1 into a 1d texture `width` texels
+        /// wide, for gradients with many stops
+        ///
+        /// Sample it with `sample_gradient_texture` from the
+        /// `noisy_bevy::gradient` shader module. Step gradients need a nearest
+        /// neighbor sampler to keep their edges sharp.
+        pub fn to_image(&self, width: u32) -> Image {
+            let data = (0..width)
+                .flat_map(|x| {
+                    // sample texel centers, like the GPU does
+                    let t = (x as f32 + 0.5) / width as f32;
+                    Srgba::from(self.sample(t)).to_u8_array()
+                })
+                .collect();
+            Image::new(
+                Extent3d {
+                    width,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D1,
+                data,
+                TextureFormat::Rgba8UnormSrgb,
+                RenderAssetUsages::default(),
+            )
+        }
+    }
+}
+
+#[cfg(all(feature = "gpu", feature = "image"))]
+mod gpu {
+    use bevy::{
+        color::{ColorToComponents, LinearRgba},
+        math::Vec4,
+        render::render_resource::ShaderType,
+    };
+
+    use super::{GradientInterpolation, MAX_GRADIENT_STOPS, NoiseGradient};
+
+    /// The GPU representation of a [`NoiseGradient`], `NoiseGradient` in the
+    /// `noisy_bevy::gradient` shader module
+    ///
+    /// Only the first [`MAX_GRADIENT_STOPS`] stops are uploaded, use
+    /// [`NoiseGradient::to_image`] for more.
+    #[derive(Clone, Default, ShaderType)]
+    pub struct NoiseGradientUniform {
+        /// Linear colors of the stops
+        pub colors: [Vec4; MAX_GRADIENT_STOPS],
+        /// Positions of the stops, packed four to a vector since uniform
+        /// array elements are 16 byte aligned
+        pub positions: [Vec4; MAX_GRADIENT_STOPS / 4],
+        /// Number of stops in use
+        pub count: u32,
+        /// The `GRADIENT_INTERPOLATION_*` constant of the interpolation
+        pub interpolation: u32,
+    }
+
+    impl From<&NoiseGradient> for NoiseGradientUniform {
+        fn from(gradient: &NoiseGradient) -> Self {
+            let mut uniform = Self {
+                count: gradient.stops.len().min(MAX_GRADIENT_STOPS) as u32,
+                interpolation: match gradient.interpolation {
+                    GradientInterpolation::Linear => 0,
+                    GradientInterpolation::Step => 1,
+                },
+                ..Default::default()
+            };
+            for (i, stop) in gradient.stops.iter().take(MAX_GRADIENT_STOPS).enumerate() {
+                uniform.colors[i] = LinearRgba::from(stop.color).to_vec4();
+                uniform.positions[i / 4][i % 4] = stop.position;
+            }
+            uniform
+        }
+    }
+}
+
+#[cfg(all(feature = "gpu", feature = "image"))]
+pub use gpu::*;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::color::palettes::css;
+
+    #[test]
+    fn linear_blends_between_stops() {
+        let gradient = NoiseGradient::linear([(0.0, Color::BLACK), (1.0, Color::WHITE)]);
+        assert_eq!(gradient.sample(0.5), LinearRgba::rgb(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn step_uses_stop_below() {
+        let gradient = NoiseGradient::step([(0.0, css::RED), (0.5, css::LIME), (0.8, css::BLUE)]);
+        assert_eq!(gradient.sample(0.49), css::RED.into());
+        assert_eq!(gradient.sample(0.5), css::LIME.into());
+        assert_eq!(gradient.sample(0.79), css::LIME.into());
+    }
+
+    #[test]
+    fn clamps_outside_stops() {
+        let gradient = NoiseGradient::linear([(0.2, css::RED), (0.6, css::BLUE)]);
+        assert_eq!(gradient.sample(-1.0), css::RED.into());
+        assert_eq!(gradient.sample(2.0), css::BLUE.into());
+    }
+
+    #[test]
+    fn sorts_stops() {
+        let gradient = NoiseGradient::linear([(1.0, css::BLUE), (0.0, css::RED)]);
+        assert_eq!(gradient.stops[0].position, 0.0);
+        assert_eq!(gradient.sample(0.0), css::RED.into());
+    }
+
+    #[test]
+    fn empty_is_transparent() {
+        let gradient = NoiseGradient::linear(Vec::<(f32, Color)>::new());
+        assert_eq!(gradient.sample(0.5), LinearRgba::NONE);
+    }
+}
@@ -0,0 +1,144 @@
+use bevy::{
+    math::{Vec2, vec2},
+    reflect::Reflect,
+};
+
+use crate::fbm_simplex_2d_seeded;
+
+/// Seeded 2d fbm noise mapped to a range, for driving densities, heights and
+/// other values that vary over a world
+///
+/// ```
+/// # use bevy::math::vec2;
+/// # use noisy_bevy::NoiseLayer;
+/// let moisture = NoiseLayer::new(0.01).with_fbm(4, 2.0, 0.5).with_seed(3.0);
+/// let value = moisture.sample(vec2(120.0, -40.0));
+/// assert!((0.0..=1.0).contains(&value));
+/// ```
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct NoiseLayer {
+    /// Noise periods per world unit
+    pub frequency: f32,
+    /// Number of fbm octaves, 1 gives plain simplex noise
+    pub octaves: usize,
+    /// Frequency multiplier between octaves
+    pub lacunarity: f32,
+    /// Amplitude multiplier between octaves
+    pub gain: f32,
+    /// Layers with different seeds are uncorrelated
+    pub seed: f32,
+    /// The value where the noise is -1
+    pub min: f32,
+    /// The value where the noise is 1
+    pub max: f32,
+}
+
+impl Default for NoiseLayer {
+    /// Single octave noise between 0 and 1
+    fn default() -> Self {
+        Self {
+            frequency: 1.0,
+            octaves: 1,
+            lacunarity: 2.0,
+            gain: 0.5,
+            seed: 0.0,
+            min: 0.0,
+            max: 1.0,
+        }
+    }
+}
+
+impl NoiseLayer {
+    /// A single octave layer between 0 and 1 with the given frequency
+    pub fn new(frequency: f32) -> Self {
+        Self {
+            frequency,
+            ..Default::default()
+        }
+    }
+
+    /// A layer that is `value` everywhere
+    pub fn constant(value: f32) -> Self {
+        Self::default().with_range(value, value)
+    }
+
+    /// Returns the layer with a different seed
+    pub fn with_seed(mut self, seed: f32) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the layer with the given fbm parameters
+    pub fn with_fbm(mut self, octaves: usize, lacunarity: f32, gain: f32) -> Self {
+        self.octaves = octaves;
+        self.lacunarity = lacunarity;
+        self.gain = gain;
+        self
+    }
+
+    /// Returns the layer mapped between `min` and `max`
+    pub fn with_range(mut self, min: f32, max: f32) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// The value at `pos`, between [`NoiseLayer::min`] and
+    /// [`NoiseLayer::max`]
+    ///
+    /// Fbm with several octaves can overshoot -1..1, and is clamped.
+    pub fn sample(&self, pos: Vec2) -> f32 {
+        if self.min == self.max {
+            return self.min;
+        }
+        let noise = fbm_simplex_2d_seeded(
+            pos * self.frequency,
+            self.octaves,
+            self.lacunarity,
+            self.gain,
+            self.seed,
+        )
+        .clamp(-1.0, 1.0);
+        self.min + (noise * 0.5 + 0.5) * (self.max - self.min)
+    }
+
+    /// The slope of the layer at `pos`, in value units per world unit,
+    /// estimated with central differences
+    pub fn gradient(&self, pos: Vec2) -> Vec2 {
+        if self.min == self.max {
+            return Vec2::ZERO;
+        }
+        // small compared to the finest octave
+        let e = 0.01 / (self.frequency * self.lacunarity.powi(self.octaves as i32 - 1));
+        vec2(
+            self.sample(pos + vec2(e, 0.0)) - self.sample(pos - vec2(e, 0.0)),
+            self.sample(pos + vec2(0.0, e)) - self.sample(pos - vec2(0.0, e)),
+        ) / (2.0 * e)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn maps_to_range() {
+        let layer = NoiseLayer::new(0.3)
+            .with_fbm(4, 2.0, 0.8)
+            .with_range(10.0, 20.0)
+            .with_seed(2.0);
+        for i in 0..1000 {
+            let value = layer.sample(vec2(i as f32, i as f32 * 0.7) * 0.1);
+            assert!((10.0..=20.0).contains(&value), "{value}");
+        }
+        assert_eq!(NoiseLayer::constant(4.0).sample(vec2(1.0, 2.0)), 4.0);
+    }
+
+    #[test]
+    fn gradient_points_uphill() {
+        let layer = NoiseLayer::new(0.1).with_fbm(3, 2.0, 0.5);
+        let p = vec2(3.0, 7.0);
+        let step = layer.gradient(p).normalize() * 0.01;
+        assert!(layer.sample(p + step) > layer.sample(p));
+    }
+}
@@ -12,3 +12,120 @@ mod cpu;
 
 #[cfg(feature = "cpu")]
 pub use cpu::*;
+
+#[cfg(feature = "cpu")]
+mod projection;
+
+#[cfg(feature = "cpu")]
+pub use projection::*;
+
+#[cfg(feature = "cpu")]
+mod normal_map;
+
+#[cfg(feature = "cpu")]
+pub use normal_map::*;
+
+#[cfg(feature = "cpu")]
+mod rng;
+
+#[cfg(feature = "cpu")]
+mod poisson;
+
+#[cfg(feature = "cpu")]
+pub use poisson::*;
+
+#[cfg(feature = "cpu")]
+mod blue_noise;
+
+#[cfg(feature = "cpu")]
+pub use blue_noise::*;
+
+#[cfg(feature = "cpu")]
+mod layer;
+
+#[cfg(feature = "cpu")]
+pub use layer::*;
+
+#[cfg(feature = "cpu")]
+mod biome;
+
+#[cfg(feature = "cpu")]
+pub use biome::*;
+
+#[cfg(feature = "cpu")]
+mod tilemap;
+
+#[cfg(feature = "cpu")]
+pub use tilemap::*;
+
+#[cfg(feature = "cpu")]
+mod cave;
+
+#[cfg(feature = "cpu")]
+pub use cave::*;
+
+#[cfg(feature = "cpu")]
+mod erosion;
+
+#[cfg(feature = "cpu")]
+pub use erosion::*;
+
+#[cfg(feature = "cpu")]
+mod cloud;
+
+#[cfg(feature = "cpu")]
+pub use cloud::*;
+
+#[cfg(feature = "cpu")]
+mod animated;
+
+#[cfg(feature = "cpu")]
+pub use animated::*;
+
+#[cfg(feature = "cpu")]
+mod gabor;
+
+#[cfg(feature = "cpu")]
+pub use gabor::*;
+
+#[cfg(feature = "cpu")]
+mod scatter;
+
+#[cfg(feature = "cpu")]
+pub use scatter::*;
+
+#[cfg(feature = "cpu")]
+mod gradient;
+
+#[cfg(feature = "cpu")]
+pub use gradient::*;
+
+#[cfg(any(feature = "material-2d", feature = "material-3d"))]
+mod material;
+
+#[cfg(any(feature = "material-2d", feature = "material-3d"))]
+pub use material::*;
+
+#[cfg(feature = "material-3d")]
+mod extension;
+
+#[cfg(feature = "material-3d")]
+pub use extension::*;
+
+#[cfg(feature = "material-3d")]
+mod cloud_material;
+
+#[cfg(feature = "material-3d")]
+pub use cloud_material::*;
+
+#[cfg(feature = "cpu")]
+mod signal;
+
+#[cfg(feature = "cpu")]
+pub use signal::*;
+
+#[cfg(feature = "cpu")]
+mod shake;
+
+#[cfg(feature = "cpu")]
+pub use shake::*;
@@ -0,0 +1,332 @@
+use bevy::{
+    app::App,
+    asset::{Asset, Handle, load_internal_asset, uuid_handle},
+    color::Color,
+    math::{Vec2, Vec3, Vec3Swizzles},
+    reflect::Reflect,
+    render::render_resource::{AsBindGroup, ShaderType},
+    shader::{Shader, ShaderRef},
+};
+
+use crate::{
+    NoiseGradient, NoiseGradientUniform, fbm_simplex_2d_seeded, fbm_simplex_2d_warp_seeded,
+    fbm_simplex_3d_seeded, fbm_simplex_3d_warp_seeded, simplex_noise_2d_seeded,
+    simplex_noise_3d_seeded, triplanar_2d, worley_2d,
+};
+
+// warp settings used by `NoiseType::Warp`, must match material.wgsl
+const WARP_ITERATIONS: usize = 2;
+const WARP_SCALE: f32 = 0.4;
+const WARP_FALLOFF: f32 = 0.5;
+
+/// The noise function sampled by [`NoiseParams`]
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NoiseType {
+    /// Plain simplex noise, see [`simplex_noise_3d_seeded`]
+    #[default]
+    Simplex,
+    /// Fractal simplex noise, see [`fbm_simplex_3d_seeded`]
+    Fbm,
+    /// Distance to the nearest cell center, see [`worley_2d`]. Only uses the
+    /// x and y coordinates.
+    Worley,
+    /// Fractal simplex noise with two iterations of domain warping, see
+    /// [`fbm_simplex_3d_warp_seeded`]
+    Warp,
+}
+
+/// Noise and color settings of `NoiseMaterial` and `NoiseMaterial2d`
+///
+/// The noise is sampled in world space, so neighboring meshes with the same
+/// settings line up seamlessly.
+#[derive(Reflect, Clone, Debug, PartialEq)]
+pub struct NoiseParams {
+    /// The noise function to sample
+    pub noise_type: NoiseType,
+    /// World positions are multiplied by this before sampling
+    pub frequency: f32,
+    /// Number of octaves for [`NoiseType::Fbm`] and [`NoiseType::Warp`]
+    pub octaves: usize,
+    /// Frequency multiplier between octaves
+    pub lacunarity: f32,
+    /// Amplitude multiplier between octaves
+    pub gain: f32,
+    /// Materials with different seeds are uncorrelated
+    pub seed: f32,
+    /// Added to the position after scaling by `frequency`
+    pub offset: Vec3,
+    /// Added to the offset every second, to animate the noise
+    pub scroll: Vec3,
+    /// Maps the noise to colors, sampled at 0 where the noise is -1 and at 1
+    /// where it's 1
+    pub gradient: NoiseGradient,
+}
+
+impl Default for NoiseParams {
+    fn default() -> Self {
+        Self {
+            noise_type: NoiseType::Simplex,
+            frequency: 1.0,
+            octaves: 4,
+            lacunarity: 2.0,
+            gain: 0.5,
+            seed: 0.0,
+            offset: Vec3::ZERO,
+            scroll: Vec3::ZERO,
+            gradient: NoiseGradient::default(),
+        }
+    }
+}
+
+impl NoiseParams {
+    /// The noise value, roughly between -1 and 1, the materials compute at a
+    /// world position
+    ///
+    /// The shaders use bevy's global time, which is seconds since startup
+    /// wrapped like [`Time::elapsed_secs_wrapped`](bevy::time::Time::elapsed_secs_wrapped).
+    /// 2d materials sample at `z = 0`.
+    pub fn sample(&self, pos: Vec3, time: f32) -> f32 {
+        let p = pos * self.frequency + self.offset + self.scroll * time;
+        let seed = Vec3::splat(self.seed);
+        match self.noise_type {
+            NoiseType::Simplex => simplex_noise_3d_seeded(p, seed),
+            NoiseType::Fbm => {
+                fbm_simplex_3d_seeded(p, self.octaves, self.lacunarity, self.gain, seed)
+            }
+            NoiseType::Worley => worley_2d(p.xy() + seed.xy(), 1.0).x * 2.0 - 1.0,
+            NoiseType::Warp => {
+                fbm_simplex_3d_warp_seeded(
+                    p,
+                    self.octaves,
+                    self.lacunarity,
+                    self.gain,
+                    seed,
+                    WARP_ITERATIONS,
+                    Vec3::splat(WARP_SCALE),
+                    WARP_FALLOFF,
+                )
+                .noise_value
+            }
+        }
+    }
+
+    /// 2d noise value at an already scaled and offset position
+    fn sample_2d(&self, p: Vec2) -> f32 {
+        match self.noise_type {
+            NoiseType::Simplex => simplex_noise_2d_seeded(p, self.seed),
+            NoiseType::Fbm => {
+                fbm_simplex_2d_seeded(p, self.octaves, self.lacunarity, self.gain, self.seed)
+            }
+            NoiseType::Worley => worley_2d(p + self.seed, 1.0).x * 2.0 - 1.0,
+            NoiseType::Warp => {
+                fbm_simplex_2d_warp_seeded(
+                    p,
+                    self.octaves,
+                    self.lacunarity,
+                    self.gain,
+                    self.seed,
+                    WARP_ITERATIONS,
+                    Vec2::splat(WARP_SCALE),
+                    WARP_FALLOFF,
+                )
+                .noise_value
+            }
+        }
+    }
+
+    /// Blends 2d noise projected along the x, y and z axes by how much the
+    /// unit `normal` faces each axis, as `NoiseExtension` does
+    ///
+    /// Avoids the stretching 3d noise has on meshes without good UVs for
+    /// noise types that only come in 2d, like [`NoiseType::Worley`]. Higher
+    /// `sharpness` gives narrower transitions between the projections.
+    pub fn sample_triplanar(&self, pos: Vec3, normal: Vec3, sharpness: f32, time: f32) -> f32 {
+        let p = pos * self.frequency + self.offset + self.scroll * time;
+        triplanar_2d(p, normal, sharpness, |p| self.sample_2d(p))
+    }
+
+    /// The color the materials render at a world position
+    pub fn color(&self, pos: Vec3, time: f32) -> Color {
+        let t = self.sample(pos, time) * 0.5 + 0.5;
+        self.gradient.sample(t).into()
+    }
+}
+
+/// The GPU representation of [`NoiseParams`], `NoiseParams` in the
+/// `noisy_bevy::material` shader module
+#[derive(Clone, Default, ShaderType)]
+pub struct NoiseParamsUniform {
+    /// See [`NoiseParams::gradient`]
+    pub gradient: NoiseGradientUniform,
+    /// See [`NoiseParams::offset`]
+    pub offset: Vec3,
+    /// See [`NoiseParams::frequency`]
+    pub frequency: f32,
+    /// See [`NoiseParams::scroll`]
+    pub scroll: Vec3,
+    /// See [`NoiseParams::seed`]
+    pub seed: f32,
+    /// The `NOISE_TYPE_*` constant of the noise type
+    pub noise_type: u32,
+    /// See [`NoiseParams::octaves`]
+    pub octaves: i32,
+    /// See [`NoiseParams::lacunarity`]
+    pub lacunarity: f32,
+    /// See [`NoiseParams::gain`]
+    pub gain: f32,
+}
+
+impl From<&NoiseParams> for NoiseParamsUniform {
+    fn from(params: &NoiseParams) -> Self {
+        Self {
+            gradient: (&params.gradient).into(),
+            offset: params.offset,
+            frequency: params.frequency,
+            scroll: params.scroll,
+            seed: params.seed,
+            noise_type: match params.noise_type {
+                NoiseType::Simplex => 0,
+                NoiseType::Fbm => 1,
+                NoiseType::Worley => 2,
+                NoiseType::Warp => 3,
+            },
+            octaves: params.octaves as i32,
+            lacunarity: params.lacunarity,
+            gain: params.gain,
+        }
+    }
+}
+
+/// Unlit 2d material coloring [2d meshes](bevy::mesh::Mesh2d) with noise
+///
+/// Registered by [`NoisyShaderPlugin`](crate::NoisyShaderPlugin) when
+/// `materials` is set.
+#[cfg(feature = "material-2d")]
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+#[uniform(0, NoiseParamsUniform)]
+pub struct NoiseMaterial2d {
+    /// Noise and colors
+    pub noise: NoiseParams,
+    /// Set to blend when the colors are transparent
+    pub alpha_mode: bevy::sprite_render::AlphaMode2d,
+}
+
+#[cfg(feature = "material-2d")]
+impl From<&NoiseMaterial2d> for NoiseParamsUniform {
+    fn from(material: &NoiseMaterial2d) -> Self {
+        (&material.noise).into()
+    }
+}
+
+#[cfg(feature = "material-2d")]
+impl bevy::sprite_render::Material2d for NoiseMaterial2d {
+    fn fragment_shader() -> ShaderRef {
+        NOISE_MATERIAL_2D_SHADER_HANDLE.into()
+    }
+
+    fn alpha_mode(&self) -> bevy::sprite_render::AlphaMode2d {
+        self.alpha_mode
+    }
+}
+
+/// Unlit 3d material coloring meshes with noise
+///
+/// Registered by [`NoisyShaderPlugin`](crate::NoisyShaderPlugin) when
+/// `materials` is set.
+#[cfg(feature = "material-3d")]
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+#[uniform(0, NoiseParamsUniform)]
+pub struct NoiseMaterial {
+    /// Noise and colors
+    pub noise: NoiseParams,
+    /// Set to blend when the colors are transparent
+    pub alpha_mode: bevy::material::AlphaMode,
+}
+
+#[cfg(feature = "material-3d")]
+impl From<&NoiseMaterial> for NoiseParamsUniform {
+    fn from(material: &NoiseMaterial) -> Self {
+        (&material.noise).into()
+    }
+}
+
+#[cfg(feature = "material-3d")]
+impl bevy::pbr::Material for NoiseMaterial {
+    fn fragment_shader() -> ShaderRef {
+        NOISE_MATERIAL_3D_SHADER_HANDLE.into()
+    }
+
+    fn alpha_mode(&self) -> bevy::material::AlphaMode {
+        self.alpha_mode
+    }
+}
+
+/// Loads the material shaders and registers the materials, unless another
+/// instance of the plugin already did
+pub(crate) fn add_materials(app: &mut App) {
+    #[cfg(feature = "material-2d")]
+    {
+        use bevy::{asset::AssetApp, sprite_render::Material2dPlugin};
+
+        if !app.is_plugin_added::<Material2dPlugin<NoiseMaterial2d>>() {
+            load_internal_asset!(
+                app,
+                NOISE_MATERIAL_2D_SHADER_HANDLE,
+                "../assets/noisy_bevy/noise_material_2d.wgsl",
+                Shader::from_wgsl
+            );
+            app.add_plugins(Material2dPlugin::<NoiseMaterial2d>::default())
+                .register_asset_reflect::<NoiseMaterial2d>();
+        }
+    }
+
+    #[cfg(feature = "material-3d")]
+    {
+        use bevy::{asset::AssetApp, pbr::MaterialPlugin};
+
+        use crate::{
+            CloudMaterial, NoiseStandardMaterial, cloud_material::CLOUD_MATERIAL_SHADER_HANDLE,
+            extension::NOISE_EXTENSION_SHADER_HANDLE,
+        };
+
+        if !app.is_plugin_added::<MaterialPlugin<NoiseMaterial>>() {
+            load_internal_asset!(
+                app,
+                NOISE_MATERIAL_3D_SHADER_HANDLE,
+                "../assets/noisy_bevy/noise_material_3d.wgsl",
+                Shader::from_wgsl
+            );
+            app.add_plugins(MaterialPlugin::<NoiseMaterial>::default())
+                .register_asset_reflect::<NoiseMaterial>();
+        }
+
+        if !app.is_plugin_added::<MaterialPlugin<NoiseStandardMaterial>>() {
+            load_internal_asset!(
+                app,
+                NOISE_EXTENSION_SHADER_HANDLE,
+                "../assets/noisy_bevy/noise_extension.wgsl",
+                Shader::from_wgsl
+            );
+            app.add_plugins(MaterialPlugin::<NoiseStandardMaterial>::default())
+                .register_asset_reflect::<NoiseStandardMaterial>();
+        }
+
+        if !app.is_plugin_added::<MaterialPlugin<CloudMaterial>>() {
+            load_internal_asset!(
+                app,
+                CLOUD_MATERIAL_SHADER_HANDLE,
+                "../assets/noisy_bevy/cloud_material.wgsl",
+                Shader::from_wgsl
+            );
+            app.add_plugins(MaterialPlugin::<CloudMaterial>::default())
+                .register_asset_reflect::<CloudMaterial>();
+        }
+    }
+}
+
+#[cfg(feature = "material-2d")]
+const NOISE_MATERIAL_2D_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("6d1f3b8e-4c2a-4e9f-b5d7-1a8c0e6f2b94");
+#[cfg(feature = "material-3d")]
+const NOISE_MATERIAL_3D_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("b2e84c61-9f3d-4a7b-8e15-3c6d0f9a7e28");
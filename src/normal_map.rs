@@ -0,0 +1,455 @@
+use bevy::math::{UVec2, Vec2, Vec3, vec2, vec3};
+
+/// Size and look of a normal map baked with [`normal_map()`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NormalMapSettings {
+    /// Size of the normal map in texels
+    pub size: UVec2,
+    /// How much the height slope tilts the normals, in height units per texel
+    pub strength: f32,
+    /// Wrap around the edges when estimating slopes, so the normal map tiles
+    ///
+    /// Only gives seamless results when the heights repeat every `size`
+    /// texels, like noise sampled on a circle in each direction.
+    pub tileable: bool,
+}
+
+impl Default for NormalMapSettings {
+    fn default() -> Self {
+        Self {
+            size: UVec2::splat(256),
+            strength: 1.0,
+            tileable: false,
+        }
+    }
+}
+
+/// The tangent space normal of a surface with the given height slope, in
+/// height units per texel along the image x and y axes
+///
+/// Uses the OpenGL convention bevy expects, with +y pointing up in the image,
+/// since image rows go down. `strength` scales the slope, 0 is flat.
+pub fn normal_from_gradient(gradient: Vec2, strength: f32) -> Vec3 {
+    vec3(-gradient.x * strength, gradient.y * strength, 1.0).normalize()
+}
+
+/// Bakes the heights returned by `height` for each texel into tangent space
+/// normals, row by row
+///
+/// Slopes are estimated with central differences. Edge texels clamp to the
+/// image, or wrap around when [`NormalMapSettings::tileable`] is set.
+///
+/// ```
+/// # use bevy::math::{UVec2, Vec3};
+/// # use noisy_bevy::{NormalMapSettings, fbm_simplex_2d, normal_map};
+/// let settings = NormalMapSettings {
+///     size: UVec2::splat(64),
+///     strength: 8.0,
+///     ..Default::default()
+/// };
+/// let normals = normal_map(settings, |texel| {
+///     fbm_simplex_2d(texel.as_vec2() * 0.05, 4, 2.0, 0.5)
+/// });
+/// assert_eq!(normals.len(), 64 * 64);
+/// ```
+pub fn normal_map(settings: NormalMapSettings, mut height: impl FnMut(UVec2) -> f32) -> Vec<Vec3> {
+    let size = settings.size;
+    let heights: Vec<f32> = (0..size.y)
+        .flat_map(|y| (0..size.x).map(move |x| UVec2::new(x, y)))
+        .map(&mut height)
+        .collect();
+    let at = |x: i64, y: i64| {
+        let (x, y) = if settings.tileable {
+            (x.rem_euclid(size.x as i64), y.rem_euclid(size.y as i64))
+        } else {
+            (x.clamp(0, size.x as i64 - 1), y.clamp(0, size.y as i64 - 1))
+        };
+        heights[(y * size.x as i64 + x) as usize]
+    };
+    normal_map_from_gradient(settings, |texel| {
+        let (x, y) = (texel.x as i64, texel.y as i64);
+        vec2(at(x + 1, y) - at(x - 1, y), at(x, y + 1) - at(x, y - 1)) * 0.5
+    })
+}
+
+/// Like [`normal_map()`], for sources that know their slope, in height units
+/// per texel along the image x and y axes
+///
+/// Analytic gradients are sharper than estimated ones, and tile whenever the
+/// source does, regardless of [`NormalMapSettings::tileable`].
+pub fn normal_map_from_gradient(
+    settings: NormalMapSettings,
+    mut gradient: impl FnMut(UVec2) -> Vec2,
+) -> Vec<Vec3> {
+    (0..settings.size.y)
+        .flat_map(|y| (0..settings.size.x).map(move |x| UVec2::new(x, y)))
+        .map(|texel| normal_from_gradient(gradient(texel), settings.strength))
+        .collect()
+}
+
+#[cfg(feature = "image")]
+mod image {
+    use bevy::{
+        asset::RenderAssetUsages,
+        image::Image,
+        math::{UVec2, Vec3},
+        render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    use super::{NormalMapSettings, normal_map};
+
+    /// Bakes [`normal_map()`] into an [`Image`] for
+    /// `StandardMaterial::normal_map_texture`
+    ///
+    /// The image is linear `Rgba8Unorm`, normal maps must not be loaded as
+    /// sRGB. Remember to generate tangents for the mesh.
+    pub fn normal_map_image(
+        settings: NormalMapSettings,
+        height: impl FnMut(UVec2) -> f32,
+    ) -> Image {
+        normals_to_image(settings.size, &normal_map(settings, height))
+    }
+
+    /// Encodes tangent space normals, row by row, into a linear `Rgba8Unorm`
+    /// [`Image`], like `encode_normal` in the `noisy_bevy::normal_map` shader
+    /// module
+    ///
+    /// # Panics
+    ///
+    /// If there isn't one normal per texel.
+    pub fn normals_to_image(size: UVec2, normals: &[Vec3]) -> Image {
+        assert_eq!(normals.len(), (size.x * size.y) as usize);
+        let data = normals
+            .iter()
+            .flat_map(|n| {
+                let [r, g, b] = (*n * 0.5 + 0.5)
+                    .to_array()
+                    .map(|c| (c * 255.0).round() as u8);
+                [r, g, b, 255]
+            })
+            .collect();
+        Image::new(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8Unorm,
+            RenderAssetUsages::default(),
+        )
+    }
+}
+
+#[cfg(feature = "image")]
+pub use image::*;
+
+#[cfg(all(feature = "gpu", feature = "image"))]
+mod compute {
+    use bevy::{
+        app::{App, Plugin},
+        asset::{Handle, RenderAssetUsages},
+        ecs::{
+            component::Component,
+            resource::Resource,
+            schedule::IntoScheduleConfigs,
+            system::{Commands, Query, Res},
+        },
+        image::Image,
+        math::UVec2,
+        render::{
+            Render, RenderApp, RenderStartup, RenderSystems,
+            extract_component::{ExtractComponent, ExtractComponentPlugin},
+            render_asset::RenderAssets,
+            render_resource::{
+                BindGroup, BindGroupEntries, BindGroupLayoutDescriptor, BindGroupLayoutEntries,
+                CachedComputePipelineId, ComputePassDescriptor, ComputePipelineDescriptor,
+                Extent3d, PipelineCache, ShaderStages, ShaderType, StorageTextureAccess,
+                TextureDimension, TextureFormat, TextureSampleType, TextureUsages, UniformBuffer,
+                binding_types::{texture_2d, texture_storage_2d, uniform_buffer},
+            },
+            renderer::{RenderContext, RenderDevice, RenderGraph, RenderGraphSystems, RenderQueue},
+            texture::GpuImage,
+        },
+    };
+
+    use super::NormalMapSettings;
+    use crate::NORMAL_MAP_COMPUTE_SHADER_HANDLE;
+
+    /// The uniform at binding 2 of
+    /// [`NORMAL_MAP_COMPUTE_SHADER_HANDLE`](crate::NORMAL_MAP_COMPUTE_SHADER_HANDLE)
+    #[derive(Clone, Copy, Debug, Default, ShaderType)]
+    pub struct NormalMapComputeSettings {
+        /// See [`NormalMapSettings::strength`]
+        pub strength: f32,
+        /// 1 if [`NormalMapSettings::tileable`], 0 otherwise
+        pub tileable: u32,
+    }
+
+    impl From<&NormalMapSettings> for NormalMapComputeSettings {
+        fn from(settings: &NormalMapSettings) -> Self {
+            Self {
+                strength: settings.strength,
+                tileable: settings.tileable.into(),
+            }
+        }
+    }
+
+    impl From<&NormalMapBake> for NormalMapComputeSettings {
+        fn from(bake: &NormalMapBake) -> Self {
+            Self {
+                strength: bake.strength,
+                tileable: bake.tileable.into(),
+            }
+        }
+    }
+
+    /// Adds GPU normal map baking, see [`NormalMapBake`]
+    ///
+    /// Needs bevy's render plugins, and [`NoisyModule::NormalMap`] loaded by
+    /// `NoisyShaderPlugin`.
+    ///
+    /// [`NoisyModule::NormalMap`]: crate::NoisyModule::NormalMap
+    pub struct NormalMapComputePlugin;
+
+    impl Plugin for NormalMapComputePlugin {
+        fn build(&self, app: &mut App) {
+            app.add_plugins(ExtractComponentPlugin::<NormalMapBake>::default());
+            let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+                return;
+            };
+            render_app
+                .init_resource::<PreparedNormalMapBakes>()
+                .add_systems(RenderStartup, init_normal_map_pipeline)
+                .add_systems(
+                    Render,
+                    prepare_normal_map_bakes.in_set(RenderSystems::PrepareBindGroups),
+                )
+                .add_systems(
+                    RenderGraph,
+                    bake_normal_maps.in_set(RenderGraphSystems::Begin),
+                );
+        }
+    }
+
+    /// Bakes the red channel of `heights` into the normal map `normals` on
+    /// the GPU, with the `bake_normal_map` entry point of
+    /// [`NORMAL_MAP_COMPUTE_SHADER_HANDLE`]
+    ///
+    /// Bakes every frame while the entity exists, before the cameras render,
+    /// so the normal map follows heights that change on the GPU. Despawn it
+    /// once the heights are final. `normals` needs storage binding usage,
+    /// create it with [`NormalMapBake::target_image`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use noisy_bevy::NormalMapBake;
+    /// fn bake(mut commands: Commands, mut images: ResMut<Assets<Image>>, heights: Handle<Image>) {
+    ///     let normals = images.add(NormalMapBake::target_image(UVec2::splat(512)));
+    ///     commands.spawn(NormalMapBake {
+    ///         heights,
+    ///         normals,
+    ///         strength: 16.0,
+    ///         tileable: false,
+    ///     });
+    /// }
+    /// ```
+    #[derive(Component, ExtractComponent, Clone, Debug)]
+    pub struct NormalMapBake {
+        /// Height texture, with the heights in the red channel
+        pub heights: Handle<Image>,
+        /// The normal map written to, the same size as `heights`
+        pub normals: Handle<Image>,
+        /// See [`NormalMapSettings::strength`]
+        pub strength: f32,
+        /// See [`NormalMapSettings::tileable`]
+        pub tileable: bool,
+    }
+
+    impl NormalMapBake {
+        /// A flat normal map of `size` texels the GPU can bake into, linear
+        /// `Rgba8Unorm` like [`normal_map_image`](crate::normal_map_image)
+        pub fn target_image(size: UVec2) -> Image {
+            let mut image = Image::new_fill(
+                Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                &[128, 128, 255, 255],
+                TextureFormat::Rgba8Unorm,
+                RenderAssetUsages::RENDER_WORLD,
+            );
+            image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+                | TextureUsages::STORAGE_BINDING
+                | TextureUsages::COPY_DST;
+            image
+        }
+    }
+
+    const LABEL: &str = "noisy_bevy_normal_map_bake";
+
+    /// Workgroup size of `bake_normal_map`
+    const WORKGROUP_SIZE: u32 = 8;
+
+    #[derive(Resource)]
+    struct NormalMapPipeline {
+        layout: BindGroupLayoutDescriptor,
+        pipeline: CachedComputePipelineId,
+    }
+
+    /// Bind groups of this frame's bakes, with the size of their normal maps
+    #[derive(Resource, Default)]
+    struct PreparedNormalMapBakes(Vec<(BindGroup, UVec2)>);
+
+    fn init_normal_map_pipeline(mut commands: Commands, pipeline_cache: Res<PipelineCache>) {
+        let layout = BindGroupLayoutDescriptor::new(
+            LABEL,
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: false }),
+                    texture_storage_2d(TextureFormat::Rgba8Unorm, StorageTextureAccess::WriteOnly),
+                    uniform_buffer::<NormalMapComputeSettings>(false),
+                ),
+            ),
+        );
+        let pipeline = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some(LABEL.into()),
+            layout: vec![layout.clone()],
+            shader: NORMAL_MAP_COMPUTE_SHADER_HANDLE,
+            entry_point: Some("bake_normal_map".into()),
+            ..Default::default()
+        });
+        commands.insert_resource(NormalMapPipeline { layout, pipeline });
+    }
+
+    fn prepare_normal_map_bakes(
+        bakes: Query<&NormalMapBake>,
+        pipeline: Res<NormalMapPipeline>,
+        pipeline_cache: Res<PipelineCache>,
+        gpu_images: Res<RenderAssets<GpuImage>>,
+        render_device: Res<RenderDevice>,
+        render_queue: Res<RenderQueue>,
+        mut commands: Commands,
+    ) {
+        let layout = pipeline_cache.get_bind_group_layout(&pipeline.layout);
+        let mut prepared = Vec::new();
+        for bake in &bakes {
+            // not uploaded yet
+            let (Some(heights), Some(normals)) =
+                (gpu_images.get(&bake.heights), gpu_images.get(&bake.normals))
+            else {
+                continue;
+            };
+            let mut settings = UniformBuffer::from(NormalMapComputeSettings::from(bake));
+            settings.write_buffer(&render_device, &render_queue);
+            let bind_group = render_device.create_bind_group(
+                LABEL,
+                &layout,
+                &BindGroupEntries::sequential((
+                    &heights.texture_view,
+                    &normals.texture_view,
+                    &settings,
+                )),
+            );
+            prepared.push((bind_group, normals.size_2d()));
+        }
+        commands.insert_resource(PreparedNormalMapBakes(prepared));
+    }
+
+    fn bake_normal_maps(
+        mut render_context: RenderContext,
+        bakes: Res<PreparedNormalMapBakes>,
+        pipeline: Res<NormalMapPipeline>,
+        pipeline_cache: Res<PipelineCache>,
+    ) {
+        if bakes.0.is_empty() {
+            return;
+        }
+        // still compiling
+        let Some(compute_pipeline) = pipeline_cache.get_compute_pipeline(pipeline.pipeline) else {
+            return;
+        };
+        let mut pass =
+            render_context
+                .command_encoder()
+                .begin_compute_pass(&ComputePassDescriptor {
+                    label: Some(LABEL),
+                    ..Default::default()
+                });
+        pass.set_pipeline(compute_pipeline);
+        for (bind_group, size) in &bakes.0 {
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.dispatch_workgroups(
+                size.x.div_ceil(WORKGROUP_SIZE),
+                size.y.div_ceil(WORKGROUP_SIZE),
+                1,
+            );
+        }
+    }
+}
+
+#[cfg(all(feature = "gpu", feature = "image"))]
+pub use compute::*;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simplex_noise_2d;
+
+    #[test]
+    fn flat_heights_point_up() {
+        let normals = normal_map(NormalMapSettings::default(), |_| 0.5);
+        assert!(normals.iter().all(|n| *n == Vec3::Z));
+    }
+
+    #[test]
+    fn slopes_tilt_downhill() {
+        // rising to the right and down the image
+        let settings = NormalMapSettings {
+            size: UVec2::splat(8),
+            ..Default::default()
+        };
+        let normals = normal_map(settings, |texel| (texel.x + texel.y) as f32 * 0.1);
+        let n = normals[3 * 8 + 3];
+        assert!(n.x < 0.0 && n.y > 0.0 && n.z > 0.0, "{n}");
+        assert!((n - normal_from_gradient(Vec2::splat(0.1), 1.0)).length() < 1e-6);
+    }
+
+    #[test]
+    fn tileable_periodic_source_tiles() {
+        // heights repeating every 32 texels
+        let size = 32;
+        let height = |texel: UVec2| {
+            let angle = texel.as_vec2() / size as f32 * std::f32::consts::TAU;
+            simplex_noise_2d(
+                vec2(angle.x.sin(), angle.x.cos()) + vec2(angle.y.sin(), 3.0 * angle.y.cos()),
+            )
+        };
+        let settings = NormalMapSettings {
+            size: UVec2::splat(size),
+            strength: 4.0,
+            tileable: true,
+        };
+        let normals = normal_map(settings, height);
+        // the first and last column continue each other, like their neighbors
+        let wide = normal_map(
+            NormalMapSettings {
+                size: UVec2::new(size + 2, size),
+                tileable: false,
+                ..settings
+            },
+            |texel| height(UVec2::new((texel.x + size - 1) % size, texel.y)),
+        );
+        for y in 1..size - 1 {
+            for x in [0, size - 1] {
+                let tiled = normals[(y * size + x) as usize];
+                let continued = wide[(y * (size + 2) + x + 1) as usize];
+                assert!((tiled - continued).length() < 1e-6, "{x} {y}");
+            }
+        }
+    }
+}
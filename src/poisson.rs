@@ -0,0 +1,323 @@
+use std::f32::consts::SQRT_2;
+
+use bevy::{
+    math::{IVec2, Rect, Vec2, ivec2, vec2},
+    reflect::Reflect,
+};
+
+use crate::rng::{Rng, hash_2d};
+
+/// Seeded Poisson-disk sampling, points that are never closer than a minimum
+/// distance but otherwise evenly random, for placing trees, rocks and stars
+/// without clumps
+///
+/// The points only depend on the seed and their surroundings, not on the
+/// region that is sampled, so sampling neighboring chunks of an infinite world
+/// gives the same points as sampling them in one go, with no seams between
+/// them.
+///
+/// ```
+/// # use bevy::math::{Rect, Vec2};
+/// # use noisy_bevy::PoissonDisk;
+/// let trees = PoissonDisk::new(2.0).with_seed(7);
+/// let chunk = Rect::new(0.0, 0.0, 32.0, 32.0);
+/// for point in trees.sample(chunk) {
+///     // spawn a tree
+/// }
+/// ```
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct PoissonDisk {
+    /// The smallest distance between points
+    pub min_radius: f32,
+    /// The largest distance between points [`PoissonDisk::sample_variable`]
+    /// enforces, the radius function is clamped to it
+    pub max_radius: f32,
+    /// Candidate points per grid cell, more fill the gaps better but are
+    /// slower
+    pub candidates: u32,
+    /// Samplers with different seeds give unrelated points
+    pub seed: u32,
+}
+
+impl Default for PoissonDisk {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl PoissonDisk {
+    /// Points at least `radius` apart
+    pub fn new(radius: f32) -> Self {
+        Self {
+            min_radius: radius,
+            max_radius: radius,
+            candidates: 4,
+            seed: 0,
+        }
+    }
+
+    /// Returns the sampler with a different seed
+    pub fn with_seed(mut self, seed: u32) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the sampler with the given radius range for
+    /// [`PoissonDisk::sample_variable`]
+    pub fn with_radius_range(mut self, min_radius: f32, max_radius: f32) -> Self {
+        self.min_radius = min_radius;
+        self.max_radius = max_radius.max(min_radius);
+        self
+    }
+
+    /// The points in `region`, at least [`PoissonDisk::min_radius`] apart
+    ///
+    /// `region` includes its minimum edges, but not its maximum edges, so
+    /// chunks sharing an edge don't share points.
+    ///
+    /// # Panics
+    ///
+    /// If [`PoissonDisk::min_radius`] isn't positive.
+    pub fn sample(&self, region: Rect) -> Vec<Vec2> {
+        let radius = self.min_radius;
+        self.sample_variable(region, |_| radius)
+    }
+
+    /// The points in `region`, with a spacing that varies with position
+    ///
+    /// Two points `a` and `b` are at least `radius(a).max(radius(b))` apart,
+    /// with the radius clamped between [`PoissonDisk::min_radius`] and
+    /// [`PoissonDisk::max_radius`]. Drive it with noise to vary the density
+    /// smoothly. The function must always return the same radius at the same
+    /// position for neighboring chunks to match.
+    ///
+    /// The cost grows quickly with `max_radius / min_radius`: each candidate is
+    /// checked against the candidates within `max_radius`, on a grid sized for
+    /// `min_radius`, and the margin sampled around `region` to match
+    /// neighboring chunks is 9 times `max_radius` wide. Keep the range narrow,
+    /// within a factor of 4 or so.
+    ///
+    /// # Panics
+    ///
+    /// If [`PoissonDisk::min_radius`] isn't positive.
+    ///
+    /// ```
+    /// # use bevy::math::{Rect, Vec2};
+    /// # use noisy_bevy::{PoissonDisk, simplex_noise_2d};
+    /// let rocks = PoissonDisk::default().with_radius_range(1.0, 4.0);
+    /// let points = rocks.sample_variable(Rect::new(0.0, 0.0, 64.0, 64.0), |p| {
+    ///     // sparse where the noise is high
+    ///     2.5 + 1.5 * simplex_noise_2d(p * 0.05)
+    /// });
+    /// ```
+    pub fn sample_variable(&self, region: Rect, radius: impl Fn(Vec2) -> f32) -> Vec<Vec2> {
+        assert!(
+            self.min_radius > 0.0,
+            "PoissonDisk::min_radius must be positive"
+        );
+        let min_radius = self.min_radius;
+        let max_radius = self.max_radius.max(min_radius);
+        let cell_size = min_radius / SQRT_2;
+        let cell_of = |p: Vec2| (p / cell_size).floor().as_ivec2();
+
+        // how many cells apart conflicting candidates can be
+        let reach = (max_radius / cell_size).ceil() as i32;
+        // whether a candidate is kept depends on candidates up to two reaches
+        // further away each round
+        let margin = reach * (2 * ROUNDS as i32 + 1);
+        let cells_min = cell_of(region.min) - margin;
+        let cells_max = cell_of(region.max) + margin;
+        let mut cells = Vec::new();
+        for y in cells_min.y..=cells_max.y {
+            for x in cells_min.x..=cells_max.x {
+                cells.push(self.cell_candidates(ivec2(x, y), cell_size, &radius));
+            }
+        }
+        let mut grid = Grid {
+            min: cells_min,
+            size: cells_max - cells_min + 1,
+            reach,
+            cells,
+        };
+
+        // keep candidates that beat every undecided candidate they conflict
+        // with and drop the candidates they conflict with, like Luby's
+        // maximal independent set algorithm, so no two kept candidates
+        // conflict
+        for _ in 0..ROUNDS {
+            let mut kept = Vec::new();
+            for (i, cell) in grid.cells.iter().enumerate() {
+                for (j, candidate) in cell.iter().enumerate() {
+                    if candidate.state != State::Undecided {
+                        continue;
+                    }
+                    let wins = grid.conflicts(i, candidate).all(|(i, j)| {
+                        let other = &grid.cells[i][j];
+                        other.state != State::Undecided || other.priority <= candidate.priority
+                    });
+                    if wins {
+                        kept.push((i, j));
+                    }
+                }
+            }
+            for (i, j) in kept {
+                grid.cells[i][j].state = State::Kept;
+                let dropped: Vec<_> = grid
+                    .conflicts(i, &grid.cells[i][j])
+                    .filter(|(i, j)| grid.cells[*i][*j].state == State::Undecided)
+                    .collect();
+                for (i, j) in dropped {
+                    grid.cells[i][j].state = State::Dropped;
+                }
+            }
+        }
+
+        let region_cells = (cell_of(region.min), cell_of(region.max));
+        (region_cells.0.y..=region_cells.1.y)
+            .flat_map(|y| (region_cells.0.x..=region_cells.1.x).map(move |x| ivec2(x, y)))
+            .flat_map(|cell| &grid.cells[grid.index(cell).unwrap()])
+            .filter(|candidate| candidate.state == State::Kept)
+            .map(|candidate| candidate.position)
+            .filter(|p| p.cmpge(region.min).all() && p.cmplt(region.max).all())
+            .collect()
+    }
+
+    fn cell_candidates(
+        &self,
+        cell: IVec2,
+        cell_size: f32,
+        radius: &impl Fn(Vec2) -> f32,
+    ) -> Vec<Candidate> {
+        let mut rng = Rng::new(hash_2d(cell, self.seed));
+        (0..self.candidates)
+            .map(|i| {
+                let offset = vec2(rng.next_f32(), rng.next_f32());
+                let position = (cell.as_vec2() + offset) * cell_size;
+                Candidate {
+                    position,
+                    radius: radius(position).clamp(self.min_radius, self.max_radius),
+                    priority: (rng.next_u32(), cell.x, cell.y, i),
+                    state: State::Undecided,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Rounds of picking candidates, each round fills most of the gaps left by
+/// the previous ones
+const ROUNDS: usize = 4;
+
+struct Candidate {
+    position: Vec2,
+    radius: f32,
+    /// Unique among all candidates, so conflicts always have a winner
+    priority: (u32, i32, i32, u32),
+    state: State,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Undecided,
+    Kept,
+    Dropped,
+}
+
+/// Candidates of a rectangle of grid cells, row by row
+struct Grid {
+    min: IVec2,
+    size: IVec2,
+    /// How many cells apart conflicting candidates can be
+    reach: i32,
+    cells: Vec<Vec<Candidate>>,
+}
+
+impl Grid {
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        let offset = cell - self.min;
+        (offset.cmpge(IVec2::ZERO).all() && offset.cmplt(self.size).all())
+            .then(|| (offset.y * self.size.x + offset.x) as usize)
+    }
+
+    /// The cell and candidate indices of the candidates closer to `candidate`
+    /// than the larger of their radii, including itself
+    fn conflicts(
+        &self,
+        cell: usize,
+        candidate: &Candidate,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let cell = self.min + ivec2(cell as i32 % self.size.x, cell as i32 / self.size.x);
+        let (p, r, reach) = (candidate.position, candidate.radius, self.reach);
+        (-reach..=reach)
+            .flat_map(move |dy| (-reach..=reach).map(move |dx| cell + ivec2(dx, dy)))
+            .filter_map(|neighbor| self.index(neighbor))
+            .flat_map(move |i| {
+                self.cells[i]
+                    .iter()
+                    .enumerate()
+                    .filter(move |(_, other)| other.position.distance(p) < r.max(other.radius))
+                    .map(move |(j, _)| (i, j))
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simplex_noise_2d;
+
+    fn assert_spaced(points: &[Vec2], radius: impl Fn(Vec2) -> f32) {
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[i + 1..] {
+                let min = radius(*a).max(radius(*b));
+                assert!(a.distance(*b) >= min, "{a} and {b} closer than {min}");
+            }
+        }
+    }
+
+    #[test]
+    fn points_are_spaced() {
+        let sampler = PoissonDisk::new(1.5).with_seed(3);
+        let points = sampler.sample(Rect::new(-10.0, -10.0, 10.0, 10.0));
+        assert_spaced(&points, |_| 1.5);
+        // close to the densest packing, not just spaced
+        assert!(points.len() > 95, "{}", points.len());
+    }
+
+    #[test]
+    fn chunks_match_whole() {
+        let sampler = PoissonDisk::new(1.0).with_seed(11);
+        let mut whole = sampler.sample(Rect::new(0.0, 0.0, 16.0, 8.0));
+        let mut chunks = sampler.sample(Rect::new(0.0, 0.0, 8.0, 8.0));
+        chunks.extend(sampler.sample(Rect::new(8.0, 0.0, 16.0, 8.0)));
+        let by_position = |a: &Vec2, b: &Vec2| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y));
+        whole.sort_by(by_position);
+        chunks.sort_by(by_position);
+        assert_eq!(whole, chunks);
+    }
+
+    #[test]
+    fn variable_radius_is_respected() {
+        let sampler = PoissonDisk::default()
+            .with_radius_range(0.5, 2.0)
+            .with_seed(5);
+        let radius = |p: Vec2| (1.25 + 0.75 * simplex_noise_2d(p * 0.1)).clamp(0.5, 2.0);
+        let points = sampler.sample_variable(Rect::new(0.0, 0.0, 20.0, 20.0), radius);
+        assert_spaced(&points, radius);
+    }
+
+    #[test]
+    #[should_panic(expected = "min_radius must be positive")]
+    fn rejects_zero_radius() {
+        PoissonDisk::new(0.0).sample(Rect::new(0.0, 0.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn seeds_differ() {
+        let region = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let a = PoissonDisk::new(1.0).with_seed(1).sample(region);
+        let b = PoissonDisk::new(1.0).with_seed(2).sample(region);
+        assert_eq!(a, PoissonDisk::new(1.0).with_seed(1).sample(region));
+        assert_ne!(a, b);
+    }
+}
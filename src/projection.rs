@@ -0,0 +1,134 @@
+use bevy::math::{Vec2, Vec3, Vec3Swizzles, vec3};
+
+use crate::{fbm_simplex_2d, fbm_simplex_3d, simplex_noise_2d, simplex_noise_3d};
+
+/// How much each of the x, y and z projections contribute to triplanar
+/// sampling at a surface with the given unit normal, summing to 1
+///
+/// Higher `sharpness` gives narrower transitions between the projections. A
+/// zero normal weights all projections equally.
+pub fn triplanar_weights(normal: Vec3, sharpness: f32) -> Vec3 {
+    let weights = normal.abs().powf(sharpness);
+    let sum = weights.element_sum();
+    if sum == 0.0 {
+        return Vec3::splat(1.0 / 3.0);
+    }
+    weights / sum
+}
+
+/// Samples 2d noise along the x, y and z axes and blends the results by how
+/// much the surface faces each axis
+///
+/// Unlike sampling 2d noise with the mesh UVs or a single plane, this doesn't
+/// stretch on steep or curved surfaces. The projections sample the `yz`, `zx`
+/// and `xy` planes.
+pub fn triplanar_2d(
+    pos: Vec3,
+    normal: Vec3,
+    sharpness: f32,
+    mut noise: impl FnMut(Vec2) -> f32,
+) -> f32 {
+    let weights = triplanar_weights(normal, sharpness);
+    weights.x * noise(pos.yz()) + weights.y * noise(pos.zx()) + weights.z * noise(pos.xy())
+}
+
+/// Triplanar [`simplex_noise_2d`], see [`triplanar_2d`]
+pub fn triplanar_simplex_2d(pos: Vec3, normal: Vec3, sharpness: f32) -> f32 {
+    triplanar_2d(pos, normal, sharpness, simplex_noise_2d)
+}
+
+/// Triplanar [`fbm_simplex_2d`], see [`triplanar_2d`]
+pub fn triplanar_fbm_2d(
+    pos: Vec3,
+    normal: Vec3,
+    sharpness: f32,
+    octaves: usize,
+    lacunarity: f32,
+    gain: f32,
+) -> f32 {
+    triplanar_2d(pos, normal, sharpness, |p| {
+        fbm_simplex_2d(p, octaves, lacunarity, gain)
+    })
+}
+
+/// The point on a sphere at a latitude and longitude, in radians
+///
+/// Latitude goes from -π/2 at the south pole (-y) to π/2 at the north pole
+/// (+y). Longitude 0 is +x, and π/2 is +z.
+pub fn sphere_position(lat_long: Vec2, radius: f32) -> Vec3 {
+    let (lat_sin, lat_cos) = lat_long.x.sin_cos();
+    let (long_sin, long_cos) = lat_long.y.sin_cos();
+    radius * vec3(lat_cos * long_cos, lat_sin, lat_cos * long_sin)
+}
+
+/// [`simplex_noise_3d`] on the surface of a sphere, see [`sphere_position`]
+///
+/// Sampling 3d noise on the sphere instead of 2d noise on the latitude and
+/// longitude has no seam where the longitude wraps around, and no pinching at
+/// the poles. `radius` sets the scale of the features.
+pub fn sphere_simplex_3d(lat_long: Vec2, radius: f32) -> f32 {
+    simplex_noise_3d(sphere_position(lat_long, radius))
+}
+
+/// [`fbm_simplex_3d`] on the surface of a sphere, see [`sphere_simplex_3d`]
+pub fn sphere_fbm_3d(
+    lat_long: Vec2,
+    radius: f32,
+    octaves: usize,
+    lacunarity: f32,
+    gain: f32,
+) -> f32 {
+    fbm_simplex_3d(sphere_position(lat_long, radius), octaves, lacunarity, gain)
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use bevy::math::vec2;
+
+    use super::*;
+
+    #[test]
+    fn triplanar_facing_an_axis_uses_one_projection() {
+        let pos = vec3(0.3, -1.2, 2.7);
+        assert_eq!(
+            triplanar_simplex_2d(pos, Vec3::Y, 4.0),
+            simplex_noise_2d(pos.zx())
+        );
+        assert_eq!(
+            triplanar_fbm_2d(pos, Vec3::NEG_Z, 4.0, 3, 2.0, 0.5),
+            fbm_simplex_2d(pos.xy(), 3, 2.0, 0.5)
+        );
+    }
+
+    #[test]
+    fn triplanar_weights_sum_to_one() {
+        let normal = vec3(0.2, -0.7, 0.4).normalize();
+        for sharpness in [1.0, 4.0, 16.0] {
+            let weights = triplanar_weights(normal, sharpness);
+            assert!((weights.element_sum() - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn triplanar_weights_of_zero_normal_are_equal() {
+        assert_eq!(triplanar_weights(Vec3::ZERO, 4.0), Vec3::splat(1.0 / 3.0));
+    }
+
+    #[test]
+    fn sphere_has_no_seam() {
+        for lat in [-1.2, 0.0, 0.7] {
+            let west = sphere_fbm_3d(vec2(lat, -PI), 3.0, 4, 2.0, 0.5);
+            let east = sphere_fbm_3d(vec2(lat, PI), 3.0, 4, 2.0, 0.5);
+            assert!((west - east).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn sphere_poles_are_single_points() {
+        let a = sphere_simplex_3d(vec2(FRAC_PI_2, 0.0), 2.0);
+        let b = sphere_simplex_3d(vec2(FRAC_PI_2, 2.0), 2.0);
+        assert!((a - b).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,45 @@
+//! Integer hashing and a small random number generator for the CPU-only
+//! helpers, so results only depend on the seed, not on the platform or on the
+//! version of a third party crate
+
+use bevy::math::IVec2;
+
+/// Mixes the bits of `x`, see <https://nullprogram.com/blog/2018/07/31/>
+pub(crate) fn hash(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^= x >> 16;
+    x
+}
+
+/// A hash of a grid cell, different for each seed
+pub(crate) fn hash_2d(cell: IVec2, seed: u32) -> u32 {
+    hash(hash(hash(seed) ^ cell.x as u32) ^ cell.y as u32)
+}
+
+/// A PCG style random number generator
+#[derive(Clone, Debug)]
+pub(crate) struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u32) -> Self {
+        Self { state: hash(seed) }
+    }
+
+    pub(crate) fn next_u32(&mut self) -> u32 {
+        self.state = self
+            .state
+            .wrapping_mul(747_796_405)
+            .wrapping_add(2_891_336_453);
+        hash(self.state)
+    }
+
+    /// Uniform in `[0, 1)`
+    pub(crate) fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
+}
@@ -0,0 +1,386 @@
+use std::{f32::consts::TAU, ops::Range};
+
+use bevy::{
+    app::{App, Plugin, Update},
+    ecs::{
+        component::Component,
+        entity::Entity,
+        entity_disabling::Disabled,
+        hierarchy::ChildOf,
+        query::{Allow, Changed},
+        reflect::ReflectComponent,
+        system::{Commands, Query},
+    },
+    math::{IVec2, Quat, Rect, Vec2, ivec2},
+    platform::collections::HashMap,
+    reflect::Reflect,
+    transform::components::Transform,
+};
+
+use crate::{
+    NoiseLayer, PoissonDisk,
+    rng::{Rng, hash_2d},
+};
+
+/// Adds the system that spawns the instances of [`NoiseScatter`]s
+pub struct NoiseScatterPlugin;
+
+impl Plugin for NoiseScatterPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<NoiseScatter>()
+            .add_systems(Update, update_noise_scatter);
+    }
+}
+
+/// Scatters copies of template entities as children of this entity, spaced
+/// out with [`PoissonDisk`] sampling and filtered by noise
+///
+/// The world is split into square chunks. Chunks overlapping
+/// [`NoiseScatter::area`] are filled, and emptied again when the area moves
+/// away, so following the camera with the area streams instances in and out.
+/// The instances of a chunk only depend on the settings and the seed, so they
+/// come back the same, and [`NoiseScatter::points_in_chunk`] tells gameplay
+/// where they are without looking at the entities.
+///
+/// Templates are regular entities, for instance with a `SceneRoot`, a mesh and
+/// material, or a sprite. Spawn them with [`Disabled`] so they aren't shown
+/// themselves. The instances are clones without `Disabled`, and the template
+/// transform is applied on top of the placement.
+///
+/// ```
+/// # use bevy::{prelude::*, ecs::entity_disabling::Disabled};
+/// # use noisy_bevy::{NoiseLayer, NoiseScatter};
+/// fn setup(mut commands: Commands) {
+///     let tree = commands
+///         .spawn((Transform::from_scale(Vec3::splat(0.5)), Disabled))
+///         .id();
+///     commands.spawn((
+///         Transform::default(),
+///         NoiseScatter {
+///             area: Rect::new(-100.0, -100.0, 100.0, 100.0),
+///             min_spacing: 3.0,
+///             density: NoiseLayer::new(0.02).with_fbm(3, 2.0, 0.5),
+///             height: NoiseLayer::new(0.005).with_range(-10.0, 30.0),
+///             height_range: 0.0..20.0,
+///             instances: vec![tree],
+///             ..default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component)]
+#[require(Transform, ScatterChunks)]
+pub struct NoiseScatter {
+    /// The region to fill, in the plane of [`NoiseScatter::plane`] in the
+    /// local space of the entity
+    pub area: Rect,
+    /// Width and height of the chunks, at least [`MIN_SCATTER_SIZE`]
+    pub chunk_size: f32,
+    /// The plane the instances are placed in
+    pub plane: ScatterPlane,
+    /// The smallest distance between instances, at least
+    /// [`MIN_SCATTER_SIZE`]
+    pub min_spacing: f32,
+    /// The chance of keeping an instance, between 0 and 1
+    pub density: NoiseLayer,
+    /// The heightfield the instances are placed on, see [`ScatterPlane`]
+    pub height: NoiseLayer,
+    /// Heights instances are allowed at
+    pub height_range: Range<f32>,
+    /// Slopes instances are allowed on, in height units per world unit
+    pub slope_range: Range<f32>,
+    /// Whether instances are rotated randomly around the up axis
+    pub random_rotation: bool,
+    /// Template entities to clone, one is picked at random for each instance
+    pub instances: Vec<Entity>,
+    /// Scatters with different seeds place instances differently
+    pub seed: u32,
+}
+
+impl Default for NoiseScatter {
+    /// Instances a unit apart everywhere, on flat ground
+    fn default() -> Self {
+        Self {
+            area: Rect::default(),
+            chunk_size: 32.0,
+            plane: ScatterPlane::default(),
+            min_spacing: 1.0,
+            density: NoiseLayer::constant(1.0),
+            height: NoiseLayer::constant(0.0),
+            height_range: f32::NEG_INFINITY..f32::INFINITY,
+            slope_range: 0.0..f32::INFINITY,
+            random_rotation: true,
+            instances: Vec::new(),
+            seed: 0,
+        }
+    }
+}
+
+/// Smaller, zero and negative [`NoiseScatter::chunk_size`] and
+/// [`NoiseScatter::min_spacing`] values are clamped to this
+pub const MIN_SCATTER_SIZE: f32 = 0.01;
+
+/// The plane a [`NoiseScatter`] places instances in
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScatterPlane {
+    /// For 3d, on the heightfield along +y
+    #[default]
+    Xz,
+    /// For 2d, at z 0. The heightfield is only used for filtering.
+    Xy,
+}
+
+/// An instance placed by a [`NoiseScatter`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScatterPoint {
+    /// Position in the plane
+    pub position: Vec2,
+    /// The height of the heightfield at the position
+    pub height: f32,
+    /// Rotation around the up axis, in radians
+    pub rotation: f32,
+    /// Index into [`NoiseScatter::instances`]
+    pub instance: usize,
+}
+
+impl ScatterPoint {
+    /// The local transform of the instance
+    pub fn transform(&self, plane: ScatterPlane) -> Transform {
+        match plane {
+            ScatterPlane::Xz => Transform::from_xyz(self.position.x, self.height, self.position.y)
+                .with_rotation(Quat::from_rotation_y(self.rotation)),
+            ScatterPlane::Xy => Transform::from_xyz(self.position.x, self.position.y, 0.0)
+                .with_rotation(Quat::from_rotation_z(self.rotation)),
+        }
+    }
+}
+
+impl NoiseScatter {
+    /// The chunks overlapping [`NoiseScatter::area`]
+    pub fn chunks(&self) -> impl Iterator<Item = IVec2> + use<> {
+        let chunk_size = self.chunk_size();
+        let min = (self.area.min / chunk_size).floor().as_ivec2();
+        let max = (self.area.max / chunk_size).ceil().as_ivec2();
+        (min.y..max.y).flat_map(move |y| (min.x..max.x).map(move |x| ivec2(x, y)))
+    }
+
+    /// The instances in a chunk, regardless of [`NoiseScatter::area`]
+    ///
+    /// Also works without templates, then [`ScatterPoint::instance`] is
+    /// always 0.
+    pub fn points_in_chunk(&self, chunk: IVec2) -> Vec<ScatterPoint> {
+        let chunk_size = self.chunk_size();
+        let min = chunk.as_vec2() * chunk_size;
+        let region = Rect::from_corners(min, min + chunk_size);
+        PoissonDisk::new(self.min_spacing.max(MIN_SCATTER_SIZE))
+            .with_seed(self.seed)
+            .sample(region)
+            .into_iter()
+            .filter_map(|position| {
+                // a generator per point, so points don't depend on their
+                // neighbors being kept
+                let bits = position.to_array().map(|c| c.to_bits() as i32);
+                let mut rng = Rng::new(hash_2d(IVec2::from(bits), self.seed));
+                if rng.next_f32() >= self.density.sample(position) {
+                    return None;
+                }
+                let height = self.height.sample(position);
+                let slope = self.height.gradient(position).length();
+                if !self.height_range.contains(&height) || !self.slope_range.contains(&slope) {
+                    return None;
+                }
+                let rotation = if self.random_rotation {
+                    rng.next_f32() * TAU
+                } else {
+                    0.0
+                };
+                let instance = (rng.next_u32() as usize)
+                    .checked_rem(self.instances.len())
+                    .unwrap_or(0);
+                Some(ScatterPoint {
+                    position,
+                    height,
+                    rotation,
+                    instance,
+                })
+            })
+            .collect()
+    }
+
+    fn chunk_size(&self) -> f32 {
+        self.chunk_size.max(MIN_SCATTER_SIZE)
+    }
+}
+
+/// The instances spawned by a [`NoiseScatter`], per chunk
+#[derive(Component, Default)]
+struct ScatterChunks {
+    /// The settings the chunks were spawned with, without the area
+    settings: Option<NoiseScatter>,
+    chunks: HashMap<IVec2, Vec<Entity>>,
+}
+
+fn update_noise_scatter(
+    mut commands: Commands,
+    mut scatters: Query<(Entity, &NoiseScatter, &mut ScatterChunks), Changed<NoiseScatter>>,
+    templates: Query<Option<&Transform>, Allow<Disabled>>,
+) {
+    for (entity, scatter, mut spawned) in &mut scatters {
+        let settings = NoiseScatter {
+            area: Rect::default(),
+            ..scatter.clone()
+        };
+        // anything but the area changed, start over
+        if spawned.settings.as_ref() != Some(&settings) {
+            for entity in spawned.chunks.drain().flat_map(|(_, entities)| entities) {
+                commands.entity(entity).try_despawn();
+            }
+            spawned.settings = Some(settings);
+        }
+
+        let wanted: Vec<IVec2> = scatter.chunks().collect();
+        spawned.chunks.retain(|chunk, entities| {
+            let keep = wanted.contains(chunk);
+            if !keep {
+                for entity in entities.drain(..) {
+                    commands.entity(entity).try_despawn();
+                }
+            }
+            keep
+        });
+
+        if scatter.instances.is_empty() {
+            continue;
+        }
+        for chunk in wanted {
+            if spawned.chunks.contains_key(&chunk) {
+                continue;
+            }
+            let instances = scatter
+                .points_in_chunk(chunk)
+                .into_iter()
+                .map(|point| {
+                    let template = scatter.instances[point.instance];
+                    let local = templates.get(template).ok().flatten();
+                    let transform =
+                        point.transform(scatter.plane) * local.copied().unwrap_or_default();
+                    commands
+                        .entity(template)
+                        .clone_and_spawn_with_opt_out(|builder| {
+                            builder.deny::<Disabled>();
+                        })
+                        .insert((transform, ChildOf(entity)))
+                        .id()
+                })
+                .collect();
+            spawned.chunks.insert(chunk, instances);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::{
+        ecs::{hierarchy::Children, world::World},
+        math::Vec3,
+    };
+
+    #[derive(Component, Clone)]
+    struct Tree;
+
+    fn scatter() -> NoiseScatter {
+        NoiseScatter {
+            area: Rect::new(0.0, 0.0, 16.0, 16.0),
+            chunk_size: 8.0,
+            min_spacing: 1.5,
+            density: NoiseLayer::new(0.2),
+            height: NoiseLayer::new(0.05).with_range(-5.0, 5.0),
+            height_range: -1.0..5.0,
+            seed: 4,
+            ..Default::default()
+        }
+    }
+
+    fn children(world: &World, entity: Entity) -> Vec<Vec3> {
+        world
+            .get::<Children>(entity)
+            .into_iter()
+            .flatten()
+            .map(|child| world.get::<Transform>(*child).unwrap().translation)
+            .collect()
+    }
+
+    #[test]
+    fn points_are_deterministic_and_filtered() {
+        let scatter = scatter();
+        let points = scatter.points_in_chunk(ivec2(1, -2));
+        assert!(!points.is_empty());
+        assert_eq!(points, scatter.points_in_chunk(ivec2(1, -2)));
+        for point in points {
+            assert!(scatter.height_range.contains(&point.height));
+        }
+    }
+
+    #[test]
+    fn clamps_sizes() {
+        let scatter = NoiseScatter {
+            area: Rect::new(0.0, 0.0, 0.025, 0.01),
+            chunk_size: 0.0,
+            min_spacing: -1.0,
+            ..Default::default()
+        };
+        assert_eq!(scatter.chunks().count(), 3);
+        scatter.points_in_chunk(IVec2::ZERO);
+    }
+
+    #[test]
+    fn spawns_and_despawns_per_chunk() {
+        let mut app = App::new();
+        app.add_plugins(NoiseScatterPlugin);
+        let template = app.world_mut().spawn((Tree, Disabled)).id();
+        let entity = app
+            .world_mut()
+            .spawn(NoiseScatter {
+                instances: vec![template],
+                ..scatter()
+            })
+            .id();
+
+        app.update();
+        let first = children(app.world(), entity);
+        let expected: usize = scatter()
+            .chunks()
+            .map(|c| scatter().points_in_chunk(c).len())
+            .sum();
+        assert_eq!(first.len(), expected);
+        assert!(app.world().get::<Disabled>(template).is_some());
+        let mut trees = app.world_mut().query::<&Tree>();
+        assert_eq!(trees.iter(app.world()).count(), expected);
+
+        // move a chunk to the right, the overlapping chunks stay
+        app.world_mut()
+            .get_mut::<NoiseScatter>(entity)
+            .unwrap()
+            .area = Rect::new(8.0, 0.0, 24.0, 16.0);
+        app.update();
+        let moved = children(app.world(), entity);
+        let kept = first.iter().filter(|p| p.x >= 8.0).count();
+        assert_eq!(moved.iter().filter(|p| p.x < 16.0).count(), kept);
+        assert!(moved.iter().all(|p| p.x >= 8.0));
+
+        // and the chunks come back the same
+        app.world_mut()
+            .get_mut::<NoiseScatter>(entity)
+            .unwrap()
+            .area = Rect::new(0.0, 0.0, 16.0, 16.0);
+        app.update();
+        let mut back = children(app.world(), entity);
+        let mut first = first;
+        let by_position = |a: &Vec3, b: &Vec3| a.x.total_cmp(&b.x).then(a.z.total_cmp(&b.z));
+        back.sort_by(by_position);
+        first.sort_by(by_position);
+        assert_eq!(back, first);
+    }
+}
@@ -0,0 +1,302 @@
+use bevy::{
+    app::{App, Plugin, PostUpdate, PreUpdate},
+    ecs::{
+        change_detection::DetectChangesMut,
+        component::Component,
+        lifecycle::Remove,
+        observer::On,
+        reflect::ReflectComponent,
+        schedule::IntoScheduleConfigs,
+        system::{Query, Res},
+    },
+    math::{EulerRot, Quat, Vec3, vec3},
+    reflect::Reflect,
+    time::Time,
+    transform::{TransformSystems, components::Transform},
+};
+
+use crate::NoiseSignal;
+
+/// Adds systems that shake the [`Transform`] of entities with a [`NoiseShake`] component
+///
+/// The offset is removed again at the start of each frame, so gameplay systems
+/// in `Update` always see the unshaken transform, and when the component is
+/// removed.
+pub struct NoiseShakePlugin;
+
+impl Plugin for NoiseShakePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<NoiseShake>()
+            .add_observer(undo_removed_noise_shake)
+            .add_systems(PreUpdate, remove_noise_shake)
+            .add_systems(
+                PostUpdate,
+                apply_noise_shake.before(TransformSystems::Propagate),
+            );
+    }
+}
+
+/// Trauma based shake, as described in Squirrel Eiserloh's "Juicing your cameras with math".
+///
+/// Add trauma with [`NoiseShake::add_trauma`] when something happens, it will
+/// decay over time. The shake amount is `trauma²`, so small amounts of trauma
+/// are barely noticeable.
+///
+/// The offset is deterministic for a given seed and time, see [`NoiseShake::offset_at`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use noisy_bevy::NoiseShake;
+/// // a 3d camera, shaking a little along every axis
+/// let shake = NoiseShake::new(Vec3::splat(0.1), Vec3::splat(0.02)).with_decay(1.5);
+/// ```
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct NoiseShake {
+    /// Current trauma, between 0 and 1
+    pub trauma: f32,
+    /// How much trauma is removed per second
+    pub decay: f32,
+    /// How fast the shake changes, in noise periods per second
+    pub frequency: f32,
+    /// Translation offset at full trauma
+    pub max_translation: Vec3,
+    /// Rotation offset at full trauma, as XYZ euler angles in radians
+    pub max_rotation: Vec3,
+    /// Shakes with different seeds are uncorrelated
+    pub seed: f32,
+    #[reflect(ignore)]
+    applied: (Vec3, Quat),
+}
+
+impl Default for NoiseShake {
+    /// Defaults suitable for a 2d camera where one world unit is roughly one
+    /// tile: translation in x and y, rotation around z
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            decay: 0.8,
+            frequency: 15.0,
+            max_translation: vec3(0.5, 0.5, 0.0),
+            max_rotation: vec3(0.0, 0.0, 0.1),
+            seed: 0.0,
+            applied: NO_OFFSET,
+        }
+    }
+}
+
+impl NoiseShake {
+    /// A shake with the given offsets at full trauma, and no trauma yet
+    pub fn new(max_translation: Vec3, max_rotation: Vec3) -> Self {
+        Self {
+            max_translation,
+            max_rotation,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the shake with a different [`NoiseShake::decay`]
+    pub fn with_decay(mut self, decay: f32) -> Self {
+        self.decay = decay;
+        self
+    }
+
+    /// Returns the shake with a different [`NoiseShake::frequency`]
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Returns the shake with a different seed
+    pub fn with_seed(mut self, seed: f32) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the shake with some initial trauma, clamped to 1
+    pub fn with_trauma(mut self, trauma: f32) -> Self {
+        self.trauma = trauma.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Adds trauma, clamping the total to 1
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// The shake amount, derived from the current trauma
+    pub fn shake(&self) -> f32 {
+        self.trauma * self.trauma
+    }
+
+    /// Translation and rotation offset at `t` seconds, for the current trauma
+    pub fn offset_at(&self, t: f32) -> (Vec3, Quat) {
+        let shake = self.shake();
+        let signal = NoiseSignal::new(self.frequency).with_seed(self.seed);
+        let translation = self.max_translation * shake * signal.sample_vec3(t);
+        let angles = self.max_rotation * shake * signal.with_seed(self.seed + 1.0).sample_vec3(t);
+        let rotation = Quat::from_euler(EulerRot::XYZ, angles.x, angles.y, angles.z);
+        (translation, rotation)
+    }
+}
+
+const NO_OFFSET: (Vec3, Quat) = (Vec3::ZERO, Quat::IDENTITY);
+
+fn remove_noise_shake(mut shakes: Query<(&mut NoiseShake, &mut Transform)>) {
+    for (mut shake, mut transform) in &mut shakes {
+        // don't mark resting transforms as changed every frame
+        if shake.applied == NO_OFFSET {
+            continue;
+        }
+        let (translation, rotation) = shake.applied;
+        transform.translation -= translation;
+        transform.rotation *= rotation.inverse();
+        shake.bypass_change_detection().applied = NO_OFFSET;
+    }
+}
+
+fn undo_removed_noise_shake(
+    remove: On<Remove, NoiseShake>,
+    mut shakes: Query<(&NoiseShake, &mut Transform)>,
+) {
+    if let Ok((shake, mut transform)) = shakes.get_mut(remove.entity)
+        && shake.applied != NO_OFFSET
+    {
+        let (translation, rotation) = shake.applied;
+        transform.translation -= translation;
+        transform.rotation *= rotation.inverse();
+    }
+}
+
+fn apply_noise_shake(time: Res<Time>, mut shakes: Query<(&mut NoiseShake, &mut Transform)>) {
+    for (mut shake, mut transform) in &mut shakes {
+        if shake.trauma == 0.0 {
+            continue;
+        }
+        shake.trauma = (shake.trauma - shake.decay * time.delta_secs()).max(0.0);
+        let (translation, rotation) = shake.offset_at(time.elapsed_secs());
+        transform.translation += translation;
+        transform.rotation *= rotation;
+        shake.bypass_change_detection().applied = (translation, rotation);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::{
+        ecs::change_detection::DetectChanges,
+        time::{TimePlugin, TimeUpdateStrategy},
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn offset_is_deterministic() {
+        let shake = NoiseShake {
+            trauma: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(shake.offset_at(1.3), shake.offset_at(1.3));
+        assert_ne!(shake.offset_at(1.3).0, shake.offset_at(1.4).0);
+
+        let other_seed = NoiseShake {
+            seed: 1.0,
+            ..shake.clone()
+        };
+        assert_ne!(shake.offset_at(1.3).0, other_seed.offset_at(1.3).0);
+    }
+
+    #[test]
+    fn no_trauma_no_offset() {
+        let (translation, rotation) = NoiseShake::default().offset_at(1.3);
+        assert_eq!(translation, Vec3::ZERO);
+        assert_eq!(rotation, Quat::IDENTITY);
+    }
+
+    #[test]
+    fn shakes_and_restores_transform() {
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, NoiseShakePlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+
+        let base = Transform::from_xyz(1.0, 2.0, 3.0);
+        let entity = app
+            .world_mut()
+            .spawn((
+                base,
+                NoiseShake {
+                    trauma: 1.0,
+                    decay: 1.0,
+                    ..Default::default()
+                },
+            ))
+            .id();
+
+        app.update();
+        app.update();
+
+        let t = app.world().resource::<Time>().elapsed_secs();
+        let shake = app.world().get::<NoiseShake>(entity).unwrap();
+        assert!(shake.trauma < 1.0);
+        let (translation, _) = shake.offset_at(t);
+        let transform = app.world().get::<Transform>(entity).unwrap();
+        assert_ne!(translation, Vec3::ZERO);
+        assert!(
+            transform
+                .translation
+                .abs_diff_eq(base.translation + translation, 1e-5)
+        );
+
+        // let the trauma decay completely
+        for _ in 0..20 {
+            app.update();
+        }
+
+        let transform = app.world().get::<Transform>(entity).unwrap();
+        assert!(transform.translation.abs_diff_eq(base.translation, 1e-5));
+        assert!(transform.rotation.abs_diff_eq(base.rotation, 1e-5));
+    }
+
+    #[test]
+    fn removing_restores_transform() {
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, NoiseShakePlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+
+        let base = Transform::from_xyz(1.0, 2.0, 3.0);
+        let entity = app
+            .world_mut()
+            .spawn((base, NoiseShake::default().with_trauma(1.0)))
+            .id();
+        app.update();
+        app.update();
+        let transform = app.world().get::<Transform>(entity).unwrap();
+        assert!(!transform.translation.abs_diff_eq(base.translation, 1e-5));
+
+        app.world_mut().entity_mut(entity).remove::<NoiseShake>();
+        let transform = app.world().get::<Transform>(entity).unwrap();
+        assert!(transform.translation.abs_diff_eq(base.translation, 1e-5));
+        assert!(transform.rotation.abs_diff_eq(base.rotation, 1e-5));
+    }
+
+    #[test]
+    fn resting_transform_is_not_changed() {
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, NoiseShakePlugin));
+        let entity = app
+            .world_mut()
+            .spawn((Transform::default(), NoiseShake::default()))
+            .id();
+        app.update();
+        app.world_mut().clear_trackers();
+        app.update();
+
+        let entity = app.world().entity(entity);
+        assert!(!entity.get_ref::<Transform>().unwrap().is_changed());
+        assert!(!entity.get_ref::<NoiseShake>().unwrap().is_changed());
+    }
+}
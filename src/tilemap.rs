@@ -0,0 +1,352 @@
+use std::ops::Range;
+
+use bevy::{
+    ecs::{bundle::Bundle, entity::Entity, system::Commands},
+    math::{IVec2, UVec2, Vec2, ivec2, vec2},
+    reflect::Reflect,
+    transform::components::Transform,
+};
+
+/// Identifies a kind of tile, like an index into a tileset
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TileId(pub u16);
+
+/// A dense rectangle of tiles at integer coordinates, row by row from `origin`
+///
+/// Usually tile ids, but other per-tile values like the solid flags of a cave
+/// or the masks from [`TileGrid::marching_squares`] work too.
+///
+/// ```
+/// # use bevy::math::{IVec2, UVec2};
+/// # use noisy_bevy::{TileGrid, TileId, TileRules, simplex_noise_2d_seeded};
+/// let rules = TileRules::thresholds(TileId(0), [(-0.2, TileId(1)), (0.4, TileId(2))]);
+/// let grid = TileGrid::from_fn(IVec2::new(-32, -32), UVec2::splat(64), |tile| {
+///     rules.classify(&[simplex_noise_2d_seeded(tile.as_vec2() * 0.1, 7.0)])
+/// });
+/// assert_eq!(grid.tiles().len(), 64 * 64);
+/// ```
+#[derive(Reflect, Clone, Debug, PartialEq)]
+pub struct TileGrid<T = TileId> {
+    origin: IVec2,
+    size: UVec2,
+    tiles: Vec<T>,
+}
+
+impl<T> TileGrid<T> {
+    /// Evaluates `tile` at every tile coordinate from `origin` to
+    /// `origin + size`, row by row
+    pub fn from_fn(origin: IVec2, size: UVec2, mut tile: impl FnMut(IVec2) -> T) -> Self {
+        let tiles = (0..size.y as i32)
+            .flat_map(|y| (0..size.x as i32).map(move |x| origin + ivec2(x, y)))
+            .map(&mut tile)
+            .collect();
+        Self {
+            origin,
+            size,
+            tiles,
+        }
+    }
+
+    /// The coordinate of the first tile
+    pub fn origin(&self) -> IVec2 {
+        self.origin
+    }
+
+    /// Number of tiles along x and y
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// The tiles, row by row
+    pub fn tiles(&self) -> &[T] {
+        &self.tiles
+    }
+
+    /// The tiles, row by row
+    pub fn tiles_mut(&mut self) -> &mut [T] {
+        &mut self.tiles
+    }
+
+    /// Unwraps the dense tiles, row by row
+    pub fn into_tiles(self) -> Vec<T> {
+        self.tiles
+    }
+
+    /// Whether `tile` is in the grid
+    pub fn contains(&self, tile: IVec2) -> bool {
+        let offset = tile - self.origin;
+        offset.cmpge(IVec2::ZERO).all() && offset.cmplt(self.size.as_ivec2()).all()
+    }
+
+    fn index(&self, tile: IVec2) -> Option<usize> {
+        self.contains(tile).then(|| {
+            let offset = (tile - self.origin).as_uvec2();
+            (offset.y * self.size.x + offset.x) as usize
+        })
+    }
+
+    /// The tile at a coordinate, if it's in the grid
+    pub fn get(&self, tile: IVec2) -> Option<&T> {
+        self.index(tile).map(|i| &self.tiles[i])
+    }
+
+    /// The tile at a coordinate, if it's in the grid
+    pub fn get_mut(&mut self, tile: IVec2) -> Option<&mut T> {
+        self.index(tile).map(|i| &mut self.tiles[i])
+    }
+
+    /// The coordinates and tiles, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, &T)> {
+        let (origin, width) = (self.origin, self.size.x as i32);
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(move |(i, tile)| (origin + ivec2(i as i32 % width, i as i32 / width), tile))
+    }
+
+    /// A grid of the same size with `f` applied to every tile
+    pub fn map<U>(&self, mut f: impl FnMut(IVec2, &T) -> U) -> TileGrid<U> {
+        TileGrid::from_fn(self.origin, self.size, |tile| {
+            f(tile, self.get(tile).expect("the tile is in the grid"))
+        })
+    }
+
+    /// Marching squares masks, for autotiling smooth borders between solid
+    /// and empty tiles
+    ///
+    /// The masks live on the dual grid, between tile centers: the mask at `c`
+    /// covers the square between the centers of the tiles at `c`, `c + x`,
+    /// `c + y` and `c + x + y`, so the grid is one tile smaller along both
+    /// axes. Bit 1 is set if the `c` corner is solid, 2 for `c + x`, 4 for
+    /// `c + x + y` and 8 for `c + y`, counter-clockwise from the bottom left
+    /// with y up. Mask 0 is fully empty, 15 fully solid, see
+    /// [`marching_squares_segments`] for the borders of the others.
+    pub fn marching_squares(&self, mut solid: impl FnMut(&T) -> bool) -> TileGrid<u8> {
+        let solid = self.map(|_, tile| solid(tile));
+        let size = self.size.saturating_sub(UVec2::ONE);
+        TileGrid::from_fn(self.origin, size, |c| {
+            [(0, 0), (1, 0), (1, 1), (0, 1)]
+                .into_iter()
+                .enumerate()
+                .filter(|(_, (x, y))| solid.get(c + ivec2(*x, *y)) == Some(&true))
+                .fold(0, |mask, (bit, _)| mask | 1 << bit)
+        })
+    }
+
+    /// Spawns an entity for every tile `bundle` returns a bundle for, with a
+    /// [`Transform`] at the tile center, `tile_size` world units apart in the
+    /// xy plane
+    ///
+    /// Returns the spawned entities.
+    pub fn spawn<B: Bundle>(
+        &self,
+        commands: &mut Commands,
+        tile_size: f32,
+        mut bundle: impl FnMut(IVec2, &T) -> Option<B>,
+    ) -> Vec<Entity> {
+        self.iter()
+            .filter_map(|(tile, value)| {
+                let bundle = bundle(tile, value)?;
+                let center = tile.as_vec2() * tile_size;
+                Some(
+                    commands
+                        .spawn((bundle, Transform::from_xyz(center.x, center.y, 0.0)))
+                        .id(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// The borders through a marching squares cell with the given mask, see
+/// [`TileGrid::marching_squares`]
+///
+/// Segments go between edge midpoints of the unit square, with the bottom
+/// left corner at the origin, and run counter-clockwise around the solid
+/// corners. The ambiguous saddles, 5 and 10, keep the solid corners apart.
+pub fn marching_squares_segments(mask: u8) -> &'static [[Vec2; 2]] {
+    const BOTTOM: Vec2 = vec2(0.5, 0.0);
+    const RIGHT: Vec2 = vec2(1.0, 0.5);
+    const TOP: Vec2 = vec2(0.5, 1.0);
+    const LEFT: Vec2 = vec2(0.0, 0.5);
+    match mask & 15 {
+        1 => &[[BOTTOM, LEFT]],
+        2 => &[[RIGHT, BOTTOM]],
+        3 => &[[RIGHT, LEFT]],
+        4 => &[[TOP, RIGHT]],
+        5 => &[[BOTTOM, LEFT], [TOP, RIGHT]],
+        6 => &[[TOP, BOTTOM]],
+        7 => &[[TOP, LEFT]],
+        8 => &[[LEFT, TOP]],
+        9 => &[[BOTTOM, TOP]],
+        10 => &[[RIGHT, BOTTOM], [LEFT, TOP]],
+        11 => &[[RIGHT, TOP]],
+        12 => &[[LEFT, RIGHT]],
+        13 => &[[BOTTOM, RIGHT]],
+        14 => &[[LEFT, BOTTOM]],
+        _ => &[],
+    }
+}
+
+/// A condition on the noise values of a tile, see [`TileRules`]
+#[derive(Reflect, Clone, Debug, PartialEq)]
+pub struct TileRule {
+    /// The range each noise value must be in, in the order the values are
+    /// passed to [`TileRules::classify`]. Missing ranges match anything,
+    /// missing values match nothing.
+    pub ranges: Vec<Range<f32>>,
+    /// The tile when the rule matches
+    pub tile: TileId,
+}
+
+/// Turns the noise values of a tile into a [`TileId`] with a rule table, the
+/// first matching rule wins
+///
+/// ```
+/// # use noisy_bevy::{TileRule, TileRules, TileId};
+/// const WATER: TileId = TileId(0);
+/// const SAND: TileId = TileId(1);
+/// const GRASS: TileId = TileId(2);
+/// const FOREST: TileId = TileId(3);
+///
+/// // height and moisture
+/// let rules = TileRules::new(GRASS)
+///     .with_rule([-1.0..0.0], WATER)
+///     .with_rule([0.0..0.1], SAND)
+///     .with_rule([0.1..1.0, 0.5..1.0], FOREST);
+/// assert_eq!(rules.classify(&[0.05, 0.9]), SAND);
+/// assert_eq!(rules.classify(&[0.3, 0.9]), FOREST);
+/// assert_eq!(rules.classify(&[0.3, 0.2]), GRASS);
+/// ```
+#[derive(Reflect, Clone, Debug, PartialEq)]
+pub struct TileRules {
+    /// Rules in priority order
+    pub rules: Vec<TileRule>,
+    /// The tile when no rule matches
+    pub default: TileId,
+}
+
+impl TileRules {
+    /// Rules that always give `default`
+    pub fn new(default: TileId) -> Self {
+        Self {
+            rules: Vec::new(),
+            default,
+        }
+    }
+
+    /// Rules for a single noise value, giving the tile of the highest
+    /// threshold at or below the value, or `default` below all thresholds
+    pub fn thresholds(
+        default: TileId,
+        thresholds: impl IntoIterator<Item = (f32, TileId)>,
+    ) -> Self {
+        let mut thresholds: Vec<_> = thresholds.into_iter().collect();
+        thresholds.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let mut rules = Self::new(default);
+        for (threshold, tile) in thresholds {
+            rules = rules.with_rule([threshold..f32::INFINITY], tile);
+        }
+        rules
+    }
+
+    /// Returns the rules with another rule, tried after the existing ones
+    pub fn with_rule(mut self, ranges: impl IntoIterator<Item = Range<f32>>, tile: TileId) -> Self {
+        self.rules.push(TileRule {
+            ranges: ranges.into_iter().collect(),
+            tile,
+        });
+        self
+    }
+
+    /// The tile of the first rule matching `values`
+    ///
+    /// Rules with more ranges than there are values don't match.
+    pub fn classify(&self, values: &[f32]) -> TileId {
+        self.rules
+            .iter()
+            .find(|rule| {
+                rule.ranges.len() <= values.len()
+                    && rule
+                        .ranges
+                        .iter()
+                        .zip(values)
+                        .all(|(range, value)| range.contains(value))
+            })
+            .map_or(self.default, |rule| rule.tile)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn thresholds_pick_highest_below() {
+        let rules = TileRules::thresholds(TileId(0), [(0.5, TileId(2)), (0.0, TileId(1))]);
+        assert_eq!(rules.classify(&[-0.1]), TileId(0));
+        assert_eq!(rules.classify(&[0.0]), TileId(1));
+        assert_eq!(rules.classify(&[0.7]), TileId(2));
+    }
+
+    #[test]
+    fn missing_values_do_not_match() {
+        let rules = TileRules::new(TileId(0)).with_rule([0.0..1.0, 0.0..1.0], TileId(1));
+        assert_eq!(rules.classify(&[0.5, 0.5]), TileId(1));
+        assert_eq!(rules.classify(&[0.5]), TileId(0));
+        // extra values are ignored
+        assert_eq!(rules.classify(&[0.5, 0.5, 7.0]), TileId(1));
+    }
+
+    #[test]
+    fn grid_indexing() {
+        let grid = TileGrid::from_fn(ivec2(-2, 3), UVec2::new(4, 2), |tile| tile);
+        assert_eq!(grid.get(ivec2(-2, 3)), Some(&ivec2(-2, 3)));
+        assert_eq!(grid.get(ivec2(1, 4)), Some(&ivec2(1, 4)));
+        assert_eq!(grid.get(ivec2(2, 4)), None);
+        assert!(grid.iter().all(|(tile, value)| tile == *value));
+    }
+
+    #[test]
+    fn marching_squares_masks() {
+        // a single solid tile in the middle of a 3x3 grid
+        let grid = TileGrid::from_fn(IVec2::ZERO, UVec2::splat(3), |tile| tile == IVec2::ONE);
+        let masks = grid.marching_squares(|solid| *solid);
+        assert_eq!(masks.size(), UVec2::splat(2));
+        // the solid tile is the top right corner of the bottom left cell, and
+        // so on counter-clockwise
+        assert_eq!(masks.tiles(), [4, 8, 2, 1]);
+    }
+
+    #[test]
+    fn segments_separate_solid_corners() {
+        let corners = [
+            vec2(0.0, 0.0),
+            vec2(1.0, 0.0),
+            vec2(1.0, 1.0),
+            vec2(0.0, 1.0),
+        ];
+        let left_of = |[a, b]: [Vec2; 2], corner: Vec2| (b - a).perp_dot(corner - a) > 0.0;
+        // a fully solid cell has no borders
+        for mask in 0..15u8 {
+            let segments = marching_squares_segments(mask);
+            for (bit, corner) in corners.into_iter().enumerate() {
+                // solid corners are to the left of a segment direction, empty
+                // ones to the right of all of them
+                let solid = mask & (1 << bit) != 0;
+                let left = segments.iter().any(|segment| left_of(*segment, corner));
+                assert_eq!(left, solid, "mask {mask} corner {bit}");
+            }
+        }
+
+        // the saddles cut off each solid corner on its own
+        for mask in [5, 10] {
+            let segments = marching_squares_segments(mask);
+            assert_eq!(segments.len(), 2);
+            for segment in segments {
+                let cut_off = corners.iter().filter(|corner| left_of(*segment, **corner));
+                assert_eq!(cut_off.count(), 1, "mask {mask}");
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Shader composition shared by the shader tests
+
+use std::collections::HashMap;
+
+use naga_oil::compose::{
+    ComposableModuleDescriptor, Composer, NagaModuleDescriptor, ShaderDefValue,
+};
+
+/// File path and source of a wgsl file
+pub type Source = (&'static str, &'static str);
+
+/// The modules registered by `NoisyShaderPlugin`, dependencies first
+pub const NOISY_BEVY_MODULES: [Source; 13] = [
+    (
+        "noisy_bevy/hash.wgsl",
+        include_str!("../../assets/noisy_bevy/hash.wgsl"),
+    ),
+    (
+        "noisy_bevy/simplex.wgsl",
+        include_str!("../../assets/noisy_bevy/simplex.wgsl"),
+    ),
+    (
+        "noisy_bevy/fbm.wgsl",
+        include_str!("../../assets/noisy_bevy/fbm.wgsl"),
+    ),
+    (
+        "noisy_bevy/warp.wgsl",
+        include_str!("../../assets/noisy_bevy/warp.wgsl"),
+    ),
+    (
+        "noisy_bevy/worley.wgsl",
+        include_str!("../../assets/noisy_bevy/worley.wgsl"),
+    ),
+    (
+        "noisy_bevy/gradient.wgsl",
+        include_str!("../../assets/noisy_bevy/gradient.wgsl"),
+    ),
+    (
+        "noisy_bevy/projection.wgsl",
+        include_str!("../../assets/noisy_bevy/projection.wgsl"),
+    ),
+    (
+        "noisy_bevy/normal_map.wgsl",
+        include_str!("../../assets/noisy_bevy/normal_map.wgsl"),
+    ),
+    (
+        "noisy_bevy/cloud.wgsl",
+        include_str!("../../assets/noisy_bevy/cloud.wgsl"),
+    ),
+    (
+        "noisy_bevy/animated.wgsl",
+        include_str!("../../assets/noisy_bevy/animated.wgsl"),
+    ),
+    (
+        "noisy_bevy/gabor.wgsl",
+        include_str!("../../assets/noisy_bevy/gabor.wgsl"),
+    ),
+    (
+        "noisy_bevy.wgsl",
+        include_str!("../../assets/noisy_bevy.wgsl"),
+    ),
+    (
+        "noisy_bevy/material.wgsl",
+        include_str!("../../assets/noisy_bevy/material.wgsl"),
+    ),
+];
+
+pub fn composer(modules: &[Source]) -> Composer {
+    let mut composer = Composer::default();
+    for (file_path, source) in modules {
+        let result = composer.add_composable_module(ComposableModuleDescriptor {
+            source,
+            file_path,
+            ..Default::default()
+        });
+        if let Err(e) = result {
+            panic!("{}", e.emit_to_string(&composer));
+        }
+    }
+    composer
+}
+
+#[track_caller]
+pub fn compose(
+    composer: &mut Composer,
+    (file_path, source): Source,
+    shader_defs: HashMap<String, ShaderDefValue>,
+) -> naga::Module {
+    composer
+        .make_naga_module(NagaModuleDescriptor {
+            source,
+            file_path,
+            shader_defs,
+            ..Default::default()
+        })
+        .unwrap_or_else(|e| panic!("{}", e.emit_to_string(composer)))
+}
@@ -0,0 +1,729 @@
+//! A tiny interpreter for the subset of naga IR used by the noise functions.
+//!
+//! This lets the parity tests run the WGSL implementation on the CPU, without
+//! a GPU. Only scalars, vectors, arrays and structs of `f32`, `i32`, `u32` and
+//! `bool` are supported, and only function-local pointers. Anything else
+//! panics, so unsupported shader code fails loudly instead of silently.
+
+use naga::{
+    BinaryOperator, Block, Expression, Function, Handle, Literal, MathFunction, Module,
+    RelationalFunction, ScalarKind, Statement, SwitchValue, TypeInner, UnaryOperator,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    F32(f32),
+    I32(i32),
+    U32(u32),
+    Bool(bool),
+    /// Vectors, arrays and structs
+    Composite(Vec<Value>),
+    Pointer(Pointer),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pointer {
+    frame: usize,
+    local: usize,
+    path: Vec<usize>,
+}
+
+impl Value {
+    pub fn f32(&self) -> f32 {
+        match self {
+            Value::F32(v) => *v,
+            other => panic!("expected f32, got {other:?}"),
+        }
+    }
+
+    pub fn i32(&self) -> i32 {
+        match self {
+            Value::I32(v) => *v,
+            other => panic!("expected i32, got {other:?}"),
+        }
+    }
+
+    pub fn bool(&self) -> bool {
+        match self {
+            Value::Bool(v) => *v,
+            other => panic!("expected bool, got {other:?}"),
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Value::I32(v) => *v as usize,
+            Value::U32(v) => *v as usize,
+            other => panic!("expected an index, got {other:?}"),
+        }
+    }
+
+    pub fn components(&self) -> &[Value] {
+        match self {
+            Value::Composite(c) => c,
+            other => panic!("expected a composite, got {other:?}"),
+        }
+    }
+
+    /// Struct field, array element or vector component
+    pub fn field(&self, index: usize) -> &Value {
+        &self.components()[index]
+    }
+
+    fn pointer(&self) -> &Pointer {
+        match self {
+            Value::Pointer(p) => p,
+            other => panic!("expected a pointer, got {other:?}"),
+        }
+    }
+
+    fn map(&self, f: &impl Fn(&Value) -> Value) -> Value {
+        match self {
+            Value::Composite(c) => Value::Composite(c.iter().map(|v| v.map(f)).collect()),
+            scalar => f(scalar),
+        }
+    }
+
+    /// Component-wise binary operation, broadcasting scalars to vectors
+    fn zip(&self, other: &Value, f: &impl Fn(&Value, &Value) -> Value) -> Value {
+        use Value::Composite;
+        match (self, other) {
+            (Composite(a), Composite(b)) => {
+                assert_eq!(a.len(), b.len(), "component count mismatch");
+                Composite(a.iter().zip(b).map(|(a, b)| a.zip(b, f)).collect())
+            }
+            (Composite(a), b) => Composite(a.iter().map(|a| a.zip(b, f)).collect()),
+            (a, Composite(b)) => Composite(b.iter().map(|b| a.zip(b, f)).collect()),
+            (a, b) => f(a, b),
+        }
+    }
+
+    fn zip3(&self, b: &Value, c: &Value, f: &impl Fn(&Value, &Value, &Value) -> Value) -> Value {
+        let len = [self, b, c].iter().find_map(|v| match v {
+            Value::Composite(c) => Some(c.len()),
+            _ => None,
+        });
+        match len {
+            None => f(self, b, c),
+            Some(len) => {
+                let at = |v: &Value, i: usize| match v {
+                    Value::Composite(c) => c[i].clone(),
+                    scalar => scalar.clone(),
+                };
+                Value::Composite(
+                    (0..len)
+                        .map(|i| at(self, i).zip3(&at(b, i), &at(c, i), f))
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    fn float(&self, f: impl Fn(f32) -> f32) -> Value {
+        self.map(&|v| Value::F32(f(v.f32())))
+    }
+
+    fn float2(&self, other: &Value, f: impl Fn(f32, f32) -> f32) -> Value {
+        self.zip(other, &|a, b| Value::F32(f(a.f32(), b.f32())))
+    }
+
+    fn float3(&self, b: &Value, c: &Value, f: impl Fn(f32, f32, f32) -> f32) -> Value {
+        self.zip3(b, c, &|a, b, c| Value::F32(f(a.f32(), b.f32(), c.f32())))
+    }
+
+    fn flatten(&self) -> Vec<Value> {
+        match self {
+            Value::Composite(c) => c.iter().flat_map(|v| v.flatten()).collect(),
+            scalar => vec![scalar.clone()],
+        }
+    }
+}
+
+impl From<f32> for Value {
+    fn from(v: f32) -> Self {
+        Value::F32(v)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(v: i32) -> Self {
+        Value::I32(v)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(v: u32) -> Self {
+        Value::U32(v)
+    }
+}
+
+impl From<bevy::math::Vec2> for Value {
+    fn from(v: bevy::math::Vec2) -> Self {
+        Value::Composite(vec![v.x.into(), v.y.into()])
+    }
+}
+
+impl From<bevy::math::Vec3> for Value {
+    fn from(v: bevy::math::Vec3) -> Self {
+        Value::Composite(vec![v.x.into(), v.y.into(), v.z.into()])
+    }
+}
+
+impl From<bevy::math::Vec4> for Value {
+    fn from(v: bevy::math::Vec4) -> Self {
+        Value::Composite(vec![v.x.into(), v.y.into(), v.z.into(), v.w.into()])
+    }
+}
+
+impl From<Value> for bevy::math::Vec2 {
+    fn from(v: Value) -> Self {
+        bevy::math::vec2(v.field(0).f32(), v.field(1).f32())
+    }
+}
+
+impl From<Value> for bevy::math::Vec3 {
+    fn from(v: Value) -> Self {
+        bevy::math::vec3(v.field(0).f32(), v.field(1).f32(), v.field(2).f32())
+    }
+}
+
+impl From<Value> for bevy::math::Vec4 {
+    fn from(v: Value) -> Self {
+        bevy::math::vec4(
+            v.field(0).f32(),
+            v.field(1).f32(),
+            v.field(2).f32(),
+            v.field(3).f32(),
+        )
+    }
+}
+
+enum Flow {
+    Normal,
+    Break,
+    Continue,
+    Return(Option<Value>),
+}
+
+struct Frame {
+    args: Vec<Value>,
+    locals: Vec<Value>,
+    values: Vec<Option<Value>>,
+}
+
+pub struct Interpreter<'a> {
+    module: &'a Module,
+    frames: Vec<Frame>,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(module: &'a Module) -> Self {
+        Self {
+            module,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Calls the function or entry point with the given (undecorated) name
+    pub fn call(&mut self, name: &str, args: &[Value]) -> Value {
+        let module = self.module;
+        let function = module
+            .functions
+            .iter()
+            .map(|(_, f)| f)
+            .chain(module.entry_points.iter().map(|e| &e.function))
+            .find(|f| f.name.as_deref() == Some(name))
+            .unwrap_or_else(|| panic!("no function named {name}"));
+        self.run_function(function, args.to_vec())
+            .unwrap_or_else(|| panic!("{name} returned nothing"))
+    }
+
+    fn run_function(&mut self, function: &'a Function, args: Vec<Value>) -> Option<Value> {
+        let locals = function
+            .local_variables
+            .iter()
+            .map(|(_, local)| self.zero_value(local.ty))
+            .collect();
+        self.frames.push(Frame {
+            args,
+            locals,
+            values: vec![None; function.expressions.len()],
+        });
+        for (local, variable) in function.local_variables.iter() {
+            if let Some(init) = variable.init {
+                let value = self.eval(function, init);
+                self.frame().locals[local.index()] = value;
+            }
+        }
+        let flow = self.run_block(function, &function.body);
+        self.frames.pop();
+        match flow {
+            Flow::Return(value) => value,
+            _ => None,
+        }
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().unwrap()
+    }
+
+    fn run_block(&mut self, function: &'a Function, block: &'a Block) -> Flow {
+        for statement in block.iter() {
+            let flow = match statement {
+                Statement::Emit(range) => {
+                    for expression in range.clone() {
+                        let value = self.compute(function, expression);
+                        self.frame().values[expression.index()] = Some(value);
+                    }
+                    Flow::Normal
+                }
+                Statement::Block(block) => self.run_block(function, block),
+                Statement::If {
+                    condition,
+                    accept,
+                    reject,
+                } => {
+                    if self.eval(function, *condition).bool() {
+                        self.run_block(function, accept)
+                    } else {
+                        self.run_block(function, reject)
+                    }
+                }
+                Statement::Switch { selector, cases } => {
+                    let selector = self.eval(function, *selector);
+                    let matches = |value: &SwitchValue| match value {
+                        SwitchValue::I32(v) => Value::I32(*v) == selector,
+                        SwitchValue::U32(v) => Value::U32(*v) == selector,
+                        SwitchValue::Default => false,
+                    };
+                    let start = cases
+                        .iter()
+                        .position(|case| matches(&case.value))
+                        .or_else(|| {
+                            cases
+                                .iter()
+                                .position(|case| case.value == SwitchValue::Default)
+                        })
+                        .expect("switch without default");
+                    let mut flow = Flow::Normal;
+                    for case in &cases[start..] {
+                        flow = self.run_block(function, &case.body);
+                        if !matches!(flow, Flow::Normal) || !case.fall_through {
+                            break;
+                        }
+                    }
+                    match flow {
+                        Flow::Break => Flow::Normal,
+                        flow => flow,
+                    }
+                }
+                Statement::Loop {
+                    body,
+                    continuing,
+                    break_if,
+                } => loop {
+                    match self.run_block(function, body) {
+                        Flow::Break => break Flow::Normal,
+                        Flow::Return(value) => break Flow::Return(value),
+                        Flow::Continue | Flow::Normal => {}
+                    }
+                    if let Flow::Return(value) = self.run_block(function, continuing) {
+                        break Flow::Return(value);
+                    }
+                    if let Some(condition) = break_if
+                        && self.eval(function, *condition).bool()
+                    {
+                        break Flow::Normal;
+                    }
+                },
+                Statement::Break => Flow::Break,
+                Statement::Continue => Flow::Continue,
+                Statement::Return { value } => {
+                    Flow::Return(value.map(|value| self.eval(function, value)))
+                }
+                Statement::Store { pointer, value } => {
+                    let pointer = self.eval(function, *pointer).pointer().clone();
+                    let value = self.eval(function, *value);
+                    *self.resolve(&pointer) = value;
+                    Flow::Normal
+                }
+                Statement::Call {
+                    function: callee,
+                    arguments,
+                    result,
+                } => {
+                    let args = arguments
+                        .iter()
+                        .map(|arg| self.eval(function, *arg))
+                        .collect();
+                    let value = self.run_function(&self.module.functions[*callee], args);
+                    if let Some(result) = result {
+                        self.frame().values[result.index()] = value;
+                    }
+                    Flow::Normal
+                }
+                other => panic!("unsupported statement {other:?}"),
+            };
+            if !matches!(flow, Flow::Normal) {
+                return flow;
+            }
+        }
+        Flow::Normal
+    }
+
+    fn resolve(&mut self, pointer: &Pointer) -> &mut Value {
+        let mut value = &mut self.frames[pointer.frame].locals[pointer.local];
+        for index in &pointer.path {
+            value = match value {
+                Value::Composite(c) => &mut c[*index],
+                other => panic!("can't index into {other:?}"),
+            };
+        }
+        value
+    }
+
+    fn eval(&mut self, function: &'a Function, expression: Handle<Expression>) -> Value {
+        match &self.frame().values[expression.index()] {
+            Some(value) => value.clone(),
+            None => self.compute(function, expression),
+        }
+    }
+
+    fn compute(&mut self, function: &'a Function, expression: Handle<Expression>) -> Value {
+        match &function.expressions[expression] {
+            Expression::Literal(literal) => literal_value(literal),
+            Expression::Constant(constant) => {
+                self.eval_const(self.module.constants[*constant].init)
+            }
+            Expression::ZeroValue(ty) => self.zero_value(*ty),
+            Expression::Compose { ty, components } => {
+                let components = components.iter().map(|c| self.eval(function, *c)).collect();
+                self.compose(*ty, components)
+            }
+            Expression::Access { base, index } => {
+                let index = self.eval(function, *index).index();
+                access(self.eval(function, *base), index)
+            }
+            Expression::AccessIndex { base, index } => {
+                access(self.eval(function, *base), *index as usize)
+            }
+            Expression::Splat { size, value } => {
+                Value::Composite(vec![self.eval(function, *value); *size as usize])
+            }
+            Expression::Swizzle {
+                size,
+                vector,
+                pattern,
+            } => {
+                let vector = self.eval(function, *vector);
+                Value::Composite(
+                    pattern[..*size as usize]
+                        .iter()
+                        .map(|component| vector.field(*component as usize).clone())
+                        .collect(),
+                )
+            }
+            Expression::FunctionArgument(index) => self.frame().args[*index as usize].clone(),
+            Expression::LocalVariable(local) => Value::Pointer(Pointer {
+                frame: self.frames.len() - 1,
+                local: local.index(),
+                path: Vec::new(),
+            }),
+            Expression::Load { pointer } => {
+                let pointer = self.eval(function, *pointer).pointer().clone();
+                self.resolve(&pointer).clone()
+            }
+            Expression::Unary { op, expr } => {
+                let value = self.eval(function, *expr);
+                value.map(&|v| match (op, v) {
+                    (UnaryOperator::Negate, Value::F32(v)) => Value::F32(-v),
+                    (UnaryOperator::Negate, Value::I32(v)) => Value::I32(v.wrapping_neg()),
+                    (UnaryOperator::LogicalNot, Value::Bool(v)) => Value::Bool(!v),
+                    (UnaryOperator::BitwiseNot, Value::I32(v)) => Value::I32(!v),
+                    (UnaryOperator::BitwiseNot, Value::U32(v)) => Value::U32(!v),
+                    (op, v) => panic!("unsupported unary {op:?} on {v:?}"),
+                })
+            }
+            Expression::Binary { op, left, right } => {
+                let left = self.eval(function, *left);
+                let right = self.eval(function, *right);
+                binary(*op, &left, &right)
+            }
+            Expression::Select {
+                condition,
+                accept,
+                reject,
+            } => {
+                let condition = self.eval(function, *condition);
+                let accept = self.eval(function, *accept);
+                let reject = self.eval(function, *reject);
+                match condition {
+                    Value::Bool(true) => accept,
+                    Value::Bool(false) => reject,
+                    condition => condition.zip3(&accept, &reject, &|c, a, r| {
+                        if c.bool() { a.clone() } else { r.clone() }
+                    }),
+                }
+            }
+            Expression::Relational { fun, argument } => {
+                let argument = self.eval(function, *argument).flatten();
+                Value::Bool(match fun {
+                    RelationalFunction::All => argument.iter().all(Value::bool),
+                    RelationalFunction::Any => argument.iter().any(Value::bool),
+                    other => panic!("unsupported relational function {other:?}"),
+                })
+            }
+            Expression::Math {
+                fun,
+                arg,
+                arg1,
+                arg2,
+                arg3: _,
+            } => {
+                let arg = self.eval(function, *arg);
+                let arg1 = arg1.map(|a| self.eval(function, a));
+                let arg2 = arg2.map(|a| self.eval(function, a));
+                math(*fun, &arg, arg1.as_ref(), arg2.as_ref())
+            }
+            Expression::As {
+                expr,
+                kind,
+                convert,
+            } => {
+                assert!(convert.is_some(), "bitcasts are not supported");
+                self.eval(function, *expr)
+                    .map(&|v| convert_scalar(v, *kind))
+            }
+            Expression::CallResult(_) => panic!("call result read before the call"),
+            other => panic!("unsupported expression {other:?}"),
+        }
+    }
+
+    fn compose(&self, ty: Handle<naga::Type>, components: Vec<Value>) -> Value {
+        match self.module.types[ty].inner {
+            // vectors can be composed from smaller vectors
+            TypeInner::Vector { .. } => {
+                Value::Composite(components.iter().flat_map(Value::flatten).collect())
+            }
+            _ => Value::Composite(components),
+        }
+    }
+
+    fn eval_const(&self, expression: Handle<Expression>) -> Value {
+        match &self.module.global_expressions[expression] {
+            Expression::Literal(literal) => literal_value(literal),
+            Expression::Constant(constant) => {
+                self.eval_const(self.module.constants[*constant].init)
+            }
+            Expression::ZeroValue(ty) => self.zero_value(*ty),
+            Expression::Compose { ty, components } => self.compose(
+                *ty,
+                components.iter().map(|c| self.eval_const(*c)).collect(),
+            ),
+            Expression::Splat { size, value } => {
+                Value::Composite(vec![self.eval_const(*value); *size as usize])
+            }
+            other => panic!("unsupported constant expression {other:?}"),
+        }
+    }
+
+    fn zero_value(&self, ty: Handle<naga::Type>) -> Value {
+        let scalar = |kind| match kind {
+            ScalarKind::Float | ScalarKind::AbstractFloat => Value::F32(0.),
+            ScalarKind::Sint | ScalarKind::AbstractInt => Value::I32(0),
+            ScalarKind::Uint => Value::U32(0),
+            ScalarKind::Bool => Value::Bool(false),
+        };
+        match &self.module.types[ty].inner {
+            TypeInner::Scalar(s) => scalar(s.kind),
+            TypeInner::Vector { size, scalar: s } => {
+                Value::Composite(vec![scalar(s.kind); *size as usize])
+            }
+            TypeInner::Array {
+                base,
+                size: naga::ArraySize::Constant(size),
+                ..
+            } => Value::Composite(vec![self.zero_value(*base); size.get() as usize]),
+            TypeInner::Struct { members, .. } => {
+                Value::Composite(members.iter().map(|m| self.zero_value(m.ty)).collect())
+            }
+            other => panic!("unsupported type {other:?}"),
+        }
+    }
+}
+
+/// Indexing a pointer gives a pointer to the element, like in wgsl
+fn access(base: Value, index: usize) -> Value {
+    match base {
+        Value::Pointer(mut pointer) => {
+            pointer.path.push(index);
+            Value::Pointer(pointer)
+        }
+        base => base.field(index).clone(),
+    }
+}
+
+fn literal_value(literal: &Literal) -> Value {
+    match *literal {
+        Literal::F32(v) => Value::F32(v),
+        Literal::I32(v) => Value::I32(v),
+        Literal::U32(v) => Value::U32(v),
+        Literal::Bool(v) => Value::Bool(v),
+        Literal::AbstractInt(v) => Value::I32(v as i32),
+        Literal::AbstractFloat(v) => Value::F32(v as f32),
+        other => panic!("unsupported literal {other:?}"),
+    }
+}
+
+fn convert_scalar(value: &Value, kind: ScalarKind) -> Value {
+    match (value, kind) {
+        (Value::F32(v), ScalarKind::Float) => Value::F32(*v),
+        (Value::F32(v), ScalarKind::Sint) => Value::I32(*v as i32),
+        (Value::F32(v), ScalarKind::Uint) => Value::U32(*v as u32),
+        (Value::I32(v), ScalarKind::Float) => Value::F32(*v as f32),
+        (Value::I32(v), ScalarKind::Sint) => Value::I32(*v),
+        (Value::I32(v), ScalarKind::Uint) => Value::U32(*v as u32),
+        (Value::U32(v), ScalarKind::Float) => Value::F32(*v as f32),
+        (Value::U32(v), ScalarKind::Sint) => Value::I32(*v as i32),
+        (Value::U32(v), ScalarKind::Uint) => Value::U32(*v),
+        (Value::Bool(v), ScalarKind::Float) => Value::F32(if *v { 1. } else { 0. }),
+        (Value::Bool(v), ScalarKind::Sint) => Value::I32(*v as i32),
+        (Value::Bool(v), ScalarKind::Uint) => Value::U32(*v as u32),
+        (value, kind) => panic!("unsupported conversion of {value:?} to {kind:?}"),
+    }
+}
+
+fn binary(op: BinaryOperator, left: &Value, right: &Value) -> Value {
+    use BinaryOperator as Op;
+    use Value::*;
+    left.zip(right, &|l, r| match (op, l, r) {
+        (Op::Add, F32(l), F32(r)) => F32(l + r),
+        (Op::Subtract, F32(l), F32(r)) => F32(l - r),
+        (Op::Multiply, F32(l), F32(r)) => F32(l * r),
+        (Op::Divide, F32(l), F32(r)) => F32(l / r),
+        // wgsl's float remainder truncates, just like rust's
+        (Op::Modulo, F32(l), F32(r)) => F32(l % r),
+        (Op::Add, I32(l), I32(r)) => I32(l.wrapping_add(*r)),
+        (Op::Subtract, I32(l), I32(r)) => I32(l.wrapping_sub(*r)),
+        (Op::Multiply, I32(l), I32(r)) => I32(l.wrapping_mul(*r)),
+        (Op::Divide, I32(l), I32(r)) => I32(l.checked_div(*r).unwrap_or(*l)),
+        (Op::Modulo, I32(l), I32(r)) => I32(l.checked_rem(*r).unwrap_or(0)),
+        (Op::Add, U32(l), U32(r)) => U32(l.wrapping_add(*r)),
+        (Op::Subtract, U32(l), U32(r)) => U32(l.wrapping_sub(*r)),
+        (Op::Multiply, U32(l), U32(r)) => U32(l.wrapping_mul(*r)),
+        (Op::Divide, U32(l), U32(r)) => U32(l.checked_div(*r).unwrap_or(*l)),
+        (Op::Modulo, U32(l), U32(r)) => U32(l.checked_rem(*r).unwrap_or(0)),
+        (Op::And, U32(l), U32(r)) => U32(l & r),
+        (Op::InclusiveOr, U32(l), U32(r)) => U32(l | r),
+        (Op::ExclusiveOr, U32(l), U32(r)) => U32(l ^ r),
+        (Op::ShiftLeft, U32(l), U32(r)) => U32(l.wrapping_shl(*r)),
+        (Op::ShiftRight, U32(l), U32(r)) => U32(l.wrapping_shr(*r)),
+        (Op::And, I32(l), I32(r)) => I32(l & r),
+        (Op::InclusiveOr, I32(l), I32(r)) => I32(l | r),
+        (Op::ExclusiveOr, I32(l), I32(r)) => I32(l ^ r),
+        (Op::ShiftLeft, I32(l), U32(r)) => I32(l.wrapping_shl(*r)),
+        (Op::ShiftRight, I32(l), U32(r)) => I32(l.wrapping_shr(*r)),
+        (Op::LogicalAnd | Op::And, Bool(l), Bool(r)) => Bool(*l && *r),
+        (Op::LogicalOr | Op::InclusiveOr, Bool(l), Bool(r)) => Bool(*l || *r),
+        (Op::Equal, l, r) => Bool(l == r),
+        (Op::NotEqual, l, r) => Bool(l != r),
+        (Op::Less, F32(l), F32(r)) => Bool(l < r),
+        (Op::LessEqual, F32(l), F32(r)) => Bool(l <= r),
+        (Op::Greater, F32(l), F32(r)) => Bool(l > r),
+        (Op::GreaterEqual, F32(l), F32(r)) => Bool(l >= r),
+        (Op::Less, I32(l), I32(r)) => Bool(l < r),
+        (Op::LessEqual, I32(l), I32(r)) => Bool(l <= r),
+        (Op::Greater, I32(l), I32(r)) => Bool(l > r),
+        (Op::GreaterEqual, I32(l), I32(r)) => Bool(l >= r),
+        (Op::Less, U32(l), U32(r)) => Bool(l < r),
+        (Op::LessEqual, U32(l), U32(r)) => Bool(l <= r),
+        (Op::Greater, U32(l), U32(r)) => Bool(l > r),
+        (Op::GreaterEqual, U32(l), U32(r)) => Bool(l >= r),
+        (op, l, r) => panic!("unsupported binary {op:?} on {l:?} and {r:?}"),
+    })
+}
+
+fn math(fun: MathFunction, arg: &Value, arg1: Option<&Value>, arg2: Option<&Value>) -> Value {
+    use MathFunction as M;
+    let a1 = || arg1.expect("missing argument");
+    let a2 = || arg2.expect("missing argument");
+    let dot = |a: &Value, b: &Value| -> Value {
+        let products = binary(BinaryOperator::Multiply, a, b).flatten();
+        products
+            .into_iter()
+            .reduce(|sum, v| binary(BinaryOperator::Add, &sum, &v))
+            .unwrap()
+    };
+    let length = |v: &Value| dot(v, v).f32().sqrt();
+    match fun {
+        M::Abs => arg.map(&|v| match v {
+            Value::F32(v) => Value::F32(v.abs()),
+            Value::I32(v) => Value::I32(v.wrapping_abs()),
+            v => v.clone(),
+        }),
+        M::Min => arg.zip(a1(), &|a, b| match (a, b) {
+            (Value::F32(a), Value::F32(b)) => Value::F32(a.min(*b)),
+            (Value::I32(a), Value::I32(b)) => Value::I32(*a.min(b)),
+            (Value::U32(a), Value::U32(b)) => Value::U32(*a.min(b)),
+            (a, b) => panic!("unsupported min of {a:?} and {b:?}"),
+        }),
+        M::Max => arg.zip(a1(), &|a, b| match (a, b) {
+            (Value::F32(a), Value::F32(b)) => Value::F32(a.max(*b)),
+            (Value::I32(a), Value::I32(b)) => Value::I32(*a.max(b)),
+            (Value::U32(a), Value::U32(b)) => Value::U32(*a.max(b)),
+            (a, b) => panic!("unsupported max of {a:?} and {b:?}"),
+        }),
+        M::Clamp => arg.zip3(a1(), a2(), &|v, low, high| match (v, low, high) {
+            (Value::F32(v), Value::F32(l), Value::F32(h)) => Value::F32(v.max(*l).min(*h)),
+            (Value::I32(v), Value::I32(l), Value::I32(h)) => Value::I32(*v.max(l).min(h)),
+            (Value::U32(v), Value::U32(l), Value::U32(h)) => Value::U32(*v.max(l).min(h)),
+            (v, l, h) => panic!("unsupported clamp of {v:?} {l:?} {h:?}"),
+        }),
+        M::Saturate => arg.float(|v| v.clamp(0., 1.)),
+        M::Cos => arg.float(f32::cos),
+        M::Sin => arg.float(f32::sin),
+        M::Tan => arg.float(f32::tan),
+        M::Acos => arg.float(f32::acos),
+        M::Asin => arg.float(f32::asin),
+        M::Atan => arg.float(f32::atan),
+        M::Atan2 => arg.float2(a1(), f32::atan2),
+        M::Ceil => arg.float(f32::ceil),
+        M::Floor => arg.float(f32::floor),
+        M::Round => arg.float(f32::round_ties_even),
+        M::Fract => arg.float(|v| v - v.floor()),
+        M::Trunc => arg.float(f32::trunc),
+        M::Exp => arg.float(f32::exp),
+        M::Exp2 => arg.float(f32::exp2),
+        M::Log => arg.float(f32::ln),
+        M::Log2 => arg.float(f32::log2),
+        M::Pow => arg.float2(a1(), f32::powf),
+        M::Sqrt => arg.float(f32::sqrt),
+        M::InverseSqrt => arg.float(|v| 1. / v.sqrt()),
+        M::Sign => arg.map(&|v| match v {
+            Value::F32(v) if *v == 0. => Value::F32(0.),
+            Value::F32(v) => Value::F32(v.signum()),
+            Value::I32(v) => Value::I32(v.signum()),
+            v => panic!("unsupported sign of {v:?}"),
+        }),
+        M::Step => arg.float2(a1(), |edge, x| if x >= edge { 1. } else { 0. }),
+        M::SmoothStep => arg.float3(a1(), a2(), |low, high, x| {
+            let t = ((x - low) / (high - low)).clamp(0., 1.);
+            t * t * (3. - 2. * t)
+        }),
+        M::Mix => arg.float3(a1(), a2(), |a, b, t| a * (1. - t) + b * t),
+        M::Fma => arg.float3(a1(), a2(), |a, b, c| a * b + c),
+        M::Dot => dot(arg, a1()),
+        M::Length => Value::F32(length(arg)),
+        M::Distance => Value::F32(length(&binary(BinaryOperator::Subtract, arg, a1()))),
+        M::Normalize => {
+            let length = length(arg);
+            arg.float(|v| v / length)
+        }
+        M::Cross => {
+            let a: bevy::math::Vec3 = arg.clone().into();
+            let b: bevy::math::Vec3 = a1().clone().into();
+            a.cross(b).into()
+        }
+        other => panic!("unsupported math function {other:?}"),
+    }
+}
@@ -0,0 +1,669 @@
+//! Checks that the WGSL noise functions return the same values as the CPU ones.
+//!
+//! The shader modules are composed with naga_oil, just like bevy does,
+//! and the resulting naga module is run through a small interpreter, so no GPU
+//! is needed.
+
+mod common;
+mod interpreter;
+
+use std::collections::HashMap;
+
+use bevy::math::{Vec2, Vec3, Vec4, vec2, vec3};
+use common::{NOISY_BEVY_MODULES, compose, composer};
+use interpreter::{Interpreter, Value};
+use naga_oil::compose::ShaderDefValue;
+use noisy_bevy::*;
+
+// floats on the gpu and cpu may differ in the last bits, and fbm adds up a few
+// of those differences
+const EPSILON: f32 = 1e-4;
+
+fn parity_module() -> naga::Module {
+    let mut composer = composer(&NOISY_BEVY_MODULES);
+    let entry = ("parity.wgsl", include_str!("shaders/parity.wgsl"));
+    compose(&mut composer, entry, shader_defs())
+}
+
+/// Shader defs matching the enabled cpu features
+fn shader_defs() -> HashMap<String, ShaderDefValue> {
+    let mut shader_defs = HashMap::new();
+    if cfg!(feature = "high-quality-gradients") {
+        shader_defs.insert(
+            "NOISY_HIGH_QUALITY_GRADIENTS".to_string(),
+            ShaderDefValue::Bool(true),
+        );
+    }
+    shader_defs
+}
+
+#[track_caller]
+fn assert_close(cpu: f32, gpu: f32, at: impl std::fmt::Debug) {
+    assert!(
+        (cpu - gpu).abs() <= EPSILON,
+        "cpu {cpu} != gpu {gpu} at {at:?}"
+    );
+}
+
+#[track_caller]
+fn assert_close_2d(cpu: Vec2, gpu: Vec2, at: impl std::fmt::Debug) {
+    assert!(
+        cpu.abs_diff_eq(gpu, EPSILON),
+        "cpu {cpu} != gpu {gpu} at {at:?}"
+    );
+}
+
+#[track_caller]
+fn assert_close_3d(cpu: Vec3, gpu: Vec3, at: impl std::fmt::Debug) {
+    assert!(
+        cpu.abs_diff_eq(gpu, EPSILON),
+        "cpu {cpu} != gpu {gpu} at {at:?}"
+    );
+}
+
+// same range as the snapshot tests, plus some large coordinates where
+// precision problems would show up
+fn points_1d() -> impl Iterator<Item = f32> {
+    (-20..20)
+        .map(|x| x as f32 * 0.1)
+        .chain([123.45, -987.6, 4321.1])
+}
+
+fn points_2d() -> impl Iterator<Item = Vec2> {
+    (-10..10)
+        .flat_map(|y| (-10..10).map(move |x| vec2(x as f32, y as f32) * 0.2))
+        .chain([vec2(123.45, -67.8), vec2(-987.6, 54.3)])
+}
+
+fn points_3d() -> impl Iterator<Item = Vec3> {
+    (-4..4)
+        .flat_map(|z| {
+            (-4..4).flat_map(move |y| (-4..4).map(move |x| vec3(x as f32, y as f32, z as f32)))
+        })
+        .map(|p| p * 0.45 + vec3(0.1, 0.2, 0.3))
+        .chain([vec3(123.45, -67.8, 9.1), vec3(-987.6, 54.3, -21.0)])
+}
+
+fn points_4d() -> impl Iterator<Item = Vec4> {
+    (-2..2)
+        .flat_map(|w| points_3d().map(move |p| p.extend(w as f32 * 0.55 + 0.15)))
+        .chain([Vec4::new(123.45, -67.8, 9.1, -42.0)])
+}
+
+fn assert_warp_2d(cpu: WarpResult, gpu: Value, at: Vec2) {
+    assert_close(cpu.noise_value, gpu.field(0).f32(), at);
+    for (i, position) in cpu.positions.iter().enumerate() {
+        assert_close_2d(*position, gpu.field(1).field(i).clone().into(), (at, i));
+    }
+}
+
+fn assert_warp_3d(cpu: WarpResult3d, gpu: Value, at: Vec3) {
+    assert_close(cpu.noise_value, gpu.field(0).f32(), at);
+    for (i, position) in cpu.positions.iter().enumerate() {
+        assert_close_3d(*position, gpu.field(1).field(i).clone().into(), (at, i));
+    }
+}
+
+#[test]
+fn simplex_1d() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    for p in points_1d() {
+        let expected = simplex_noise_1d(p);
+        assert_close(expected, gpu.call("simplex_noise_1d", &[p.into()]).f32(), p);
+        let expected = simplex_noise_1d_seeded(p, 3.7);
+        let actual = gpu.call("simplex_noise_1d_seeded", &[p.into(), 3.7.into()]);
+        assert_close(expected, actual.f32(), p);
+    }
+}
+
+#[test]
+fn simplex_2d() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    for p in points_2d() {
+        let expected = simplex_noise_2d(p);
+        assert_close(expected, gpu.call("simplex_noise_2d", &[p.into()]).f32(), p);
+        let expected = simplex_noise_2d_seeded(p, 3.7);
+        let actual = gpu.call("simplex_noise_2d_seeded", &[p.into(), 3.7.into()]);
+        assert_close(expected, actual.f32(), p);
+    }
+}
+
+#[test]
+fn simplex_3d() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    let seed = vec3(3.7, -1.2, 8.0);
+    for p in points_3d() {
+        let expected = simplex_noise_3d(p);
+        assert_close(expected, gpu.call("simplex_noise_3d", &[p.into()]).f32(), p);
+        let expected = simplex_noise_3d_seeded(p, seed);
+        let actual = gpu.call("simplex_noise_3d_seeded", &[p.into(), seed.into()]);
+        assert_close(expected, actual.f32(), p);
+    }
+}
+
+#[test]
+fn simplex_4d() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    for p in points_4d() {
+        let expected = simplex_noise_4d(p);
+        assert_close(expected, gpu.call("simplex_noise_4d", &[p.into()]).f32(), p);
+    }
+}
+
+#[test]
+fn flow() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    for (i, p) in points_3d().enumerate() {
+        let rotation = i as f32 * 0.37;
+        let expected = simplex_noise_2d_flow(p.truncate(), rotation);
+        let actual = gpu.call(
+            "simplex_noise_2d_flow",
+            &[p.truncate().into(), rotation.into()],
+        );
+        assert_close(expected, actual.f32(), p);
+        let expected = simplex_noise_3d_flow(p, rotation);
+        let actual = gpu.call("simplex_noise_3d_flow", &[p.into(), rotation.into()]);
+        assert_close(expected, actual.f32(), p);
+    }
+
+    let fbm_args = [
+        Value::I32(4),
+        2.1.into(),
+        0.45.into(),
+        0.8.into(),
+        1.7.into(),
+    ];
+    for p in points_3d().step_by(5) {
+        let args = [&[p.truncate().into()][..], &fbm_args].concat();
+        let expected = fbm_simplex_2d_flow(p.truncate(), 4, 2.1, 0.45, 0.8, 1.7);
+        assert_close(expected, gpu.call("fbm_simplex_2d_flow", &args).f32(), p);
+        let args = [&[p.into()][..], &fbm_args].concat();
+        let expected = fbm_simplex_3d_flow(p, 4, 2.1, 0.45, 0.8, 1.7);
+        assert_close(expected, gpu.call("fbm_simplex_3d_flow", &args).f32(), p);
+    }
+}
+
+#[test]
+fn fbm() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    let fbm_args = |octaves: i32| [Value::I32(octaves), 2.1.into(), 0.45.into()];
+
+    for p in points_1d() {
+        let args = [&[p.into()][..], &fbm_args(5)].concat();
+        let expected = fbm_simplex_1d(p, 5, 2.1, 0.45);
+        assert_close(expected, gpu.call("fbm_simplex_1d", &args).f32(), p);
+        let args = [&args[..], &[1.5.into()]].concat();
+        let expected = fbm_simplex_1d_seeded(p, 5, 2.1, 0.45, 1.5);
+        assert_close(expected, gpu.call("fbm_simplex_1d_seeded", &args).f32(), p);
+    }
+
+    for p in points_2d() {
+        let args = [&[p.into()][..], &fbm_args(5)].concat();
+        let expected = fbm_simplex_2d(p, 5, 2.1, 0.45);
+        assert_close(expected, gpu.call("fbm_simplex_2d", &args).f32(), p);
+        let args = [&args[..], &[1.5.into()]].concat();
+        let expected = fbm_simplex_2d_seeded(p, 5, 2.1, 0.45, 1.5);
+        assert_close(expected, gpu.call("fbm_simplex_2d_seeded", &args).f32(), p);
+    }
+
+    let seed = vec3(1.5, 2.5, -3.0);
+    for p in points_3d() {
+        let args = [&[p.into()][..], &fbm_args(3)].concat();
+        let expected = fbm_simplex_3d(p, 3, 2.1, 0.45);
+        assert_close(expected, gpu.call("fbm_simplex_3d", &args).f32(), p);
+        let args = [&args[..], &[seed.into()]].concat();
+        let expected = fbm_simplex_3d_seeded(p, 3, 2.1, 0.45, seed);
+        assert_close(expected, gpu.call("fbm_simplex_3d_seeded", &args).f32(), p);
+    }
+
+    for p in points_4d().step_by(7) {
+        let args = [&[p.into()][..], &fbm_args(3)].concat();
+        let expected = fbm_simplex_4d(p, 3, 2.1, 0.45);
+        assert_close(expected, gpu.call("fbm_simplex_4d", &args).f32(), p);
+    }
+}
+
+#[test]
+fn warp_2d_default_noise() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    let fbm = |p| fbm_simplex_2d(p, 4, 2.0, 0.5);
+    let scale = vec2(0.4, 0.3);
+    // more iterations than the max to check clamping too
+    for iterations in [0, 2, 5] {
+        for p in points_2d().step_by(7) {
+            let cpu = warp_2d(p, iterations, scale, 0.5, fbm, fbm);
+            let args = [
+                p.into(),
+                (iterations as i32).into(),
+                scale.into(),
+                0.5.into(),
+            ];
+            assert_warp_2d(cpu, gpu.call("warp_2d", &args), p);
+        }
+    }
+}
+
+#[test]
+fn fbm_2d_warp_seeded() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    let scale = vec2(0.4, 0.3);
+    for p in points_2d().step_by(7) {
+        let cpu = fbm_simplex_2d_warp_seeded(p, 4, 2.0, 0.5, 2.5, 3, scale, 0.5);
+        let args = [
+            p.into(),
+            4.into(),
+            2.0.into(),
+            0.5.into(),
+            2.5.into(),
+            3.into(),
+            scale.into(),
+            0.5.into(),
+        ];
+        assert_warp_2d(cpu, gpu.call("fbm_simplex_2d_warp_seeded", &args), p);
+    }
+}
+
+#[test]
+fn fbm_2d_warp() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    let warp = DomainWarp2d {
+        seed: 1.5,
+        iterations: 3,
+        frequency_step: 1.5,
+        octave_step: -1,
+        ..Default::default()
+    };
+    let gpu_warp = Value::Composite(vec![
+        (warp.octaves as i32).into(),
+        warp.lacunarity.into(),
+        warp.gain.into(),
+        warp.seed.into(),
+        (warp.iterations as i32).into(),
+        warp.scale.into(),
+        warp.falloff.into(),
+        warp.axis_seed_offsets.into(),
+        warp.frequency_step.into(),
+        warp.octave_step.into(),
+    ]);
+    for p in points_2d().step_by(7) {
+        let cpu = fbm_simplex_2d_warp(p, &warp);
+        let actual = gpu.call("fbm_simplex_2d_warp", &[p.into(), gpu_warp.clone()]);
+        assert_warp_2d(cpu, actual, p);
+    }
+}
+
+#[test]
+fn warp_3d_default_noise() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    let fbm = |p| fbm_simplex_3d(p, 4, 2.0, 0.5);
+    let scale = vec3(0.4, 0.3, 0.2);
+    for p in points_3d().step_by(31) {
+        let cpu = warp_3d(p, 2, scale, 0.5, fbm, fbm);
+        let args = [p.into(), 2.into(), scale.into(), 0.5.into()];
+        assert_warp_3d(cpu, gpu.call("warp_3d", &args), p);
+    }
+}
+
+#[test]
+fn fbm_3d_warp_seeded() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    let seed = vec3(1.5, 2.5, -3.0);
+    let scale = vec3(0.4, 0.3, 0.2);
+    for p in points_3d().step_by(31) {
+        let cpu = fbm_simplex_3d_warp_seeded(p, 3, 2.0, 0.5, seed, 2, scale, 0.5);
+        let args = [
+            p.into(),
+            3.into(),
+            2.0.into(),
+            0.5.into(),
+            seed.into(),
+            2.into(),
+            scale.into(),
+            0.5.into(),
+        ];
+        assert_warp_3d(cpu, gpu.call("fbm_simplex_3d_warp_seeded", &args), p);
+    }
+}
+
+#[test]
+fn worley() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    for jitter in [0.0, 0.8, 1.0] {
+        for p in points_2d() {
+            let cpu = worley_2d(p, jitter);
+            let actual = gpu.call("worley_2d", &[p.into(), jitter.into()]);
+            assert_close_2d(cpu, actual.into(), (p, jitter));
+        }
+    }
+}
+
+#[test]
+fn triplanar() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    for p in points_3d().step_by(3) {
+        let normal = (p - vec3(0.1, 0.2, 0.3)).normalize_or(Vec3::Y);
+        let expected = triplanar_simplex_2d(p, normal, 4.0);
+        let args = [p.into(), normal.into(), 4.0.into()];
+        let actual = gpu.call("triplanar_simplex_2d", &args).f32();
+        assert_close(expected, actual, (p, normal));
+
+        let expected = triplanar_fbm_2d(p, normal, 2.0, 4, 2.0, 0.5);
+        let args = [
+            p.into(),
+            normal.into(),
+            2.0.into(),
+            4.into(),
+            2.0.into(),
+            0.5.into(),
+        ];
+        let actual = gpu.call("triplanar_fbm_2d", &args).f32();
+        assert_close(expected, actual, (p, normal));
+    }
+
+    let p = vec3(0.3, -1.2, 2.7);
+    let expected = triplanar_simplex_2d(p, Vec3::ZERO, 4.0);
+    let args = [p.into(), Vec3::ZERO.into(), 4.0.into()];
+    let actual = gpu.call("triplanar_simplex_2d", &args).f32();
+    assert_close(expected, actual, (p, Vec3::ZERO));
+}
+
+#[test]
+fn sphere() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    for lat_long in points_2d() {
+        let expected = sphere_simplex_3d(lat_long, 2.5);
+        let args = [lat_long.into(), 2.5.into()];
+        let actual = gpu.call("sphere_simplex_3d", &args).f32();
+        assert_close(expected, actual, lat_long);
+
+        let expected = sphere_fbm_3d(lat_long, 2.5, 4, 2.0, 0.5);
+        let args = [
+            lat_long.into(),
+            2.5.into(),
+            4.into(),
+            2.0.into(),
+            0.5.into(),
+        ];
+        let actual = gpu.call("sphere_fbm_3d", &args).f32();
+        assert_close(expected, actual, lat_long);
+    }
+}
+
+#[test]
+fn normal_from_gradient() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    for gradient in points_2d() {
+        let expected = noisy_bevy::normal_from_gradient(gradient, 1.5);
+        let args = [gradient.into(), 1.5.into()];
+        let actual = gpu.call("normal_from_gradient", &args).into();
+        assert_close_3d(expected, actual, gradient);
+    }
+}
+
+#[test]
+fn warp_2d_overridden_noise() {
+    let mut composer = composer(&NOISY_BEVY_MODULES);
+    let entry = (
+        "warp_override.wgsl",
+        include_str!("shaders/warp_override.wgsl"),
+    );
+    let module = compose(&mut composer, entry, shader_defs());
+    let mut gpu = Interpreter::new(&module);
+    let offset_noise = |p| fbm_simplex_2d(p, 4, 2.0, 0.5);
+    let noise = |p| worley_2d(p, 1.0).x;
+    for p in points_2d().step_by(7) {
+        let cpu = warp_2d(p, 2, Vec2::splat(0.4), 0.5, offset_noise, noise);
+        let actual = gpu.call("fragment", &[p.into()]);
+        assert_close(cpu.noise_value, actual.field(0).f32(), p);
+    }
+}
+
+#[cfg(feature = "image")]
+fn gpu_gradient(uniform: &NoiseGradientUniform) -> Value {
+    Value::Composite(vec![
+        Value::Composite(uniform.colors.map(Value::from).to_vec()),
+        Value::Composite(uniform.positions.map(Value::from).to_vec()),
+        uniform.count.into(),
+        uniform.interpolation.into(),
+    ])
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn gradient() {
+    use bevy::{
+        color::{Color, ColorToComponents, palettes::css},
+        math::Vec4,
+    };
+
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    let stops = [
+        (-0.2, css::NAVY),
+        (0.3, css::SANDY_BROWN),
+        (0.3, css::OLIVE),
+        (0.5, css::FOREST_GREEN),
+        (0.9, css::WHITE),
+    ];
+    for gradient in [
+        NoiseGradient::linear(stops),
+        NoiseGradient::step(stops),
+        NoiseGradient::linear([(0.5, css::RED)]),
+        NoiseGradient::linear(Vec::<(f32, Color)>::new()),
+    ] {
+        let gpu_gradient = gpu_gradient(&NoiseGradientUniform::from(&gradient));
+        for t in points_1d() {
+            let cpu = gradient.sample(t).to_vec4();
+            let args = [gpu_gradient.clone(), t.into()];
+            let actual: Vec4 = gpu.call("sample_gradient", &args).into();
+            assert!(
+                cpu.abs_diff_eq(actual, EPSILON),
+                "cpu {cpu} != gpu {actual} at {t}"
+            );
+        }
+    }
+}
+
+#[cfg(any(feature = "material-2d", feature = "material-3d"))]
+#[test]
+fn material() {
+    use bevy::{
+        color::{ColorToComponents, LinearRgba, palettes::css},
+        math::Vec4,
+    };
+
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    for (i, noise_type) in [
+        NoiseType::Simplex,
+        NoiseType::Fbm,
+        NoiseType::Worley,
+        NoiseType::Warp,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let params = NoiseParams {
+            noise_type,
+            frequency: 0.7,
+            octaves: 3,
+            seed: i as f32,
+            offset: vec3(0.5, -1.0, 2.0),
+            scroll: vec3(0.1, 0.2, 0.0),
+            gradient: NoiseGradient::linear([(0.0, css::NAVY), (0.6, css::TEAL), (1.0, css::GOLD)]),
+            ..Default::default()
+        };
+        let uniform = NoiseParamsUniform::from(&params);
+        let gpu_params = Value::Composite(vec![
+            gpu_gradient(&uniform.gradient),
+            uniform.offset.into(),
+            uniform.frequency.into(),
+            uniform.scroll.into(),
+            uniform.seed.into(),
+            uniform.noise_type.into(),
+            uniform.octaves.into(),
+            uniform.lacunarity.into(),
+            uniform.gain.into(),
+        ]);
+        for p in points_3d().step_by(13) {
+            let time = 2.5;
+            let cpu = params.sample(p, time);
+            let args = [gpu_params.clone(), p.into(), time.into()];
+            let value = gpu.call("material_sample_noise", &args).f32();
+            assert_close(cpu, value, (noise_type, p));
+
+            let normal = (p - vec3(0.1, 0.2, 0.3)).normalize_or(Vec3::Y);
+            let cpu = params.sample_triplanar(p, normal, 4.0, time);
+            let args = [
+                gpu_params.clone(),
+                p.into(),
+                normal.into(),
+                4.0.into(),
+                time.into(),
+            ];
+            let actual = gpu.call("material_sample_noise_triplanar", &args).f32();
+            assert_close(cpu, actual, (noise_type, p, normal));
+
+            let cpu = LinearRgba::from(params.color(p, time)).to_vec4();
+            let args = [gpu_params.clone(), value.into()];
+            let color: Vec4 = gpu.call("material_noise_color", &args).into();
+            assert!(
+                cpu.abs_diff_eq(color, EPSILON),
+                "cpu {cpu} != gpu {color} at {p}"
+            );
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn clouds() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    let layer = CloudLayer {
+        bottom: 2.0,
+        top: 6.0,
+        coverage: 0.7,
+        density: 0.5,
+        frequency: 0.3,
+        octaves: 3,
+        seed: 1.5,
+        wind: vec3(0.5, 0.0, 0.2),
+        ..Default::default()
+    };
+    let uniform = CloudLayerUniform::from(&layer);
+    let gpu_layer = Value::Composite(vec![
+        uniform.wind.into(),
+        uniform.bottom.into(),
+        uniform.top.into(),
+        uniform.coverage.into(),
+        uniform.density.into(),
+        uniform.frequency.into(),
+        uniform.octaves.into(),
+        uniform.lacunarity.into(),
+        uniform.gain.into(),
+        uniform.seed.into(),
+    ]);
+    let time = 1.5;
+    for p in points_3d().step_by(7) {
+        let p = p + vec3(0.0, 4.0, 0.0);
+        let cpu = layer.density(p, time);
+        let args = [gpu_layer.clone(), p.into(), time.into()];
+        let actual = gpu.call("cloud_density", &args).f32();
+        assert_close(cpu, actual, p);
+
+        let to = p + vec3(3.0, 5.0, -2.0);
+        let cpu = layer.transmittance(p, to, time, 8);
+        let args = [
+            gpu_layer.clone(),
+            p.into(),
+            to.into(),
+            time.into(),
+            8u32.into(),
+        ];
+        let actual = gpu.call("cloud_transmittance", &args).f32();
+        assert_close(cpu, actual, (p, to));
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn animated() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    let noise = AnimatedNoise {
+        frequency: 0.7,
+        speed: 0.4,
+        octaves: 3,
+        seed: 2.0,
+        ..Default::default()
+    };
+    let uniform = AnimatedNoiseUniform::from(&noise);
+    let gpu_noise = Value::Composite(vec![
+        uniform.frequency.into(),
+        uniform.speed.into(),
+        uniform.octaves.into(),
+        uniform.lacunarity.into(),
+        uniform.gain.into(),
+        uniform.seed.into(),
+    ]);
+    for (i, p) in points_3d().step_by(5).enumerate() {
+        let time = i as f32 * 0.3;
+        let expected = noise.sample_2d(p.truncate(), time);
+        let args = [gpu_noise.clone(), p.truncate().into(), time.into()];
+        assert_close(expected, gpu.call("animated_noise_2d", &args).f32(), p);
+        let expected = noise.sample_3d(p, time);
+        let args = [gpu_noise.clone(), p.into(), time.into()];
+        assert_close(expected, gpu.call("animated_noise_3d", &args).f32(), p);
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn gabor() {
+    let module = parity_module();
+    let mut gpu = Interpreter::new(&module);
+    let noise = GaborNoise {
+        orientation: 0.6,
+        frequency: 2.5,
+        bandwidth: 0.8,
+        impulse_density: 5.5,
+        seed: 9,
+    };
+    // out of range settings are clamped the same way
+    let clamped = GaborNoise {
+        bandwidth: -1.0,
+        impulse_density: 100.0,
+        ..noise
+    };
+    for (noise, step) in [(noise, 3), (clamped, 29)] {
+        let uniform = GaborNoiseUniform::from(&noise);
+        let gpu_noise = Value::Composite(vec![
+            uniform.orientation.into(),
+            uniform.frequency.into(),
+            uniform.bandwidth.into(),
+            uniform.impulse_density.into(),
+            uniform.seed.into(),
+        ]);
+        for p in points_2d().step_by(step) {
+            let expected = noise.sample(p);
+            let actual = gpu.call("gabor_noise_2d", &[gpu_noise.clone(), p.into()]);
+            assert_close(expected, actual.f32(), p);
+        }
+    }
+}
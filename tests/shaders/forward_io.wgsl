@@ -0,0 +1,13 @@
+// Stand-in for bevy's bevy_pbr::forward_io, see mesh2d_functions.wgsl
+#define_import_path bevy_pbr::forward_io
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) world_position: vec4<f32>,
+    @location(1) world_normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+}
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+}
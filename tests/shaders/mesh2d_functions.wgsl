@@ -0,0 +1,17 @@
+// Stand-in for bevy's bevy_sprite::mesh2d_functions, so the example shaders
+// can be composed without bevy's render pipeline. Only the functions the
+// examples use, with the same signatures.
+#define_import_path bevy_sprite::mesh2d_functions
+
+fn get_world_from_local(instance_index: u32) -> mat4x4<f32> {
+    return mat4x4<f32>(
+        vec4<f32>(1.0, 0.0, 0.0, 0.0),
+        vec4<f32>(0.0, 1.0, 0.0, 0.0),
+        vec4<f32>(0.0, 0.0, 1.0, 0.0),
+        vec4<f32>(0.0, 0.0, 0.0, 1.0),
+    );
+}
+
+fn mesh2d_position_local_to_clip(world_from_local: mat4x4<f32>, vertex_position: vec4<f32>) -> vec4<f32> {
+    return world_from_local * vertex_position;
+}
@@ -0,0 +1,9 @@
+// Stand-in for bevy's bevy_sprite::mesh2d_vertex_output, see mesh2d_functions.wgsl
+#define_import_path bevy_sprite::mesh2d_vertex_output
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) world_position: vec4<f32>,
+    @location(1) world_normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+}
@@ -0,0 +1,10 @@
+// Stand-in for bevy's bevy_sprite::mesh2d_view_bindings, see mesh2d_functions.wgsl
+#define_import_path bevy_sprite::mesh2d_view_bindings
+
+struct Globals {
+    time: f32,
+    delta_time: f32,
+    frame_count: u32,
+}
+
+@group(0) @binding(1) var<uniform> globals: Globals;
@@ -0,0 +1,15 @@
+// Stand-in for bevy's bevy_pbr::mesh_view_bindings, see mesh2d_functions.wgsl
+#define_import_path bevy_pbr::mesh_view_bindings
+
+struct Globals {
+    time: f32,
+    delta_time: f32,
+    frame_count: u32,
+}
+
+struct View {
+    world_position: vec3<f32>,
+}
+
+@group(0) @binding(0) var<uniform> view: View;
+@group(0) @binding(1) var<uniform> globals: Globals;
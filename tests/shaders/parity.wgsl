@@ -0,0 +1,166 @@
+// Entry points for the parity tests.
+//
+// naga_oil removes imported functions that aren't used and decorates the names
+// of the ones that are, so each function under test is wrapped in an
+// undecorated function with the same name.
+
+#import noisy_bevy
+#import noisy_bevy::gradient
+#import noisy_bevy::projection
+#import noisy_bevy::normal_map
+#import noisy_bevy::material
+#import noisy_bevy::cloud
+#import noisy_bevy::animated
+#import noisy_bevy::gabor
+
+fn simplex_noise_1d(v: f32) -> f32 {
+    return noisy_bevy::simplex_noise_1d(v);
+}
+
+fn simplex_noise_1d_seeded(v: f32, seed: f32) -> f32 {
+    return noisy_bevy::simplex_noise_1d_seeded(v, seed);
+}
+
+fn simplex_noise_2d(v: vec2<f32>) -> f32 {
+    return noisy_bevy::simplex_noise_2d(v);
+}
+
+fn simplex_noise_2d_seeded(v: vec2<f32>, seed: f32) -> f32 {
+    return noisy_bevy::simplex_noise_2d_seeded(v, seed);
+}
+
+fn simplex_noise_3d(v: vec3<f32>) -> f32 {
+    return noisy_bevy::simplex_noise_3d(v);
+}
+
+fn simplex_noise_3d_seeded(v: vec3<f32>, seed: vec3<f32>) -> f32 {
+    return noisy_bevy::simplex_noise_3d_seeded(v, seed);
+}
+
+fn simplex_noise_2d_flow(v: vec2<f32>, rotation: f32) -> f32 {
+    return noisy_bevy::simplex_noise_2d_flow(v, rotation);
+}
+
+fn simplex_noise_3d_flow(v: vec3<f32>, rotation: f32) -> f32 {
+    return noisy_bevy::simplex_noise_3d_flow(v, rotation);
+}
+
+fn simplex_noise_4d(v: vec4<f32>) -> f32 {
+    return noisy_bevy::simplex_noise_4d(v);
+}
+
+fn fbm_simplex_1d(pos: f32, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    return noisy_bevy::fbm_simplex_1d(pos, octaves, lacunarity, gain);
+}
+
+fn fbm_simplex_1d_seeded(pos: f32, octaves: i32, lacunarity: f32, gain: f32, seed: f32) -> f32 {
+    return noisy_bevy::fbm_simplex_1d_seeded(pos, octaves, lacunarity, gain, seed);
+}
+
+fn fbm_simplex_2d(pos: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    return noisy_bevy::fbm_simplex_2d(pos, octaves, lacunarity, gain);
+}
+
+fn fbm_simplex_2d_seeded(pos: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: f32) -> f32 {
+    return noisy_bevy::fbm_simplex_2d_seeded(pos, octaves, lacunarity, gain, seed);
+}
+
+fn fbm_simplex_3d(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    return noisy_bevy::fbm_simplex_3d(pos, octaves, lacunarity, gain);
+}
+
+fn fbm_simplex_3d_seeded(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: vec3<f32>) -> f32 {
+    return noisy_bevy::fbm_simplex_3d_seeded(pos, octaves, lacunarity, gain, seed);
+}
+
+fn fbm_simplex_4d(pos: vec4<f32>, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    return noisy_bevy::fbm_simplex_4d(pos, octaves, lacunarity, gain);
+}
+
+fn fbm_simplex_2d_flow(pos: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32, rotation: f32, rotation_gain: f32) -> f32 {
+    return noisy_bevy::fbm_simplex_2d_flow(pos, octaves, lacunarity, gain, rotation, rotation_gain);
+}
+
+fn fbm_simplex_3d_flow(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32, rotation: f32, rotation_gain: f32) -> f32 {
+    return noisy_bevy::fbm_simplex_3d_flow(pos, octaves, lacunarity, gain, rotation, rotation_gain);
+}
+
+fn fbm_simplex_2d_warp_seeded(pos: vec2<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: f32, warp_iterations: i32, warp_scale: vec2<f32>, falloff: f32) -> noisy_bevy::WarpResult {
+    return noisy_bevy::fbm_simplex_2d_warp_seeded(pos, octaves, lacunarity, gain, seed, warp_iterations, warp_scale, falloff);
+}
+
+fn fbm_simplex_2d_warp(pos: vec2<f32>, warp: noisy_bevy::DomainWarp2d) -> noisy_bevy::WarpResult {
+    return noisy_bevy::fbm_simplex_2d_warp(pos, warp);
+}
+
+fn warp_2d(pos: vec2<f32>, warp_iterations: i32, warp_scale: vec2<f32>, falloff: f32) -> noisy_bevy::WarpResult {
+    return noisy_bevy::warp_2d(pos, warp_iterations, warp_scale, falloff);
+}
+
+fn fbm_simplex_3d_warp_seeded(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32, seed: vec3<f32>, warp_iterations: i32, warp_scale: vec3<f32>, falloff: f32) -> noisy_bevy::WarpResult3d {
+    return noisy_bevy::fbm_simplex_3d_warp_seeded(pos, octaves, lacunarity, gain, seed, warp_iterations, warp_scale, falloff);
+}
+
+fn warp_3d(pos: vec3<f32>, warp_iterations: i32, warp_scale: vec3<f32>, falloff: f32) -> noisy_bevy::WarpResult3d {
+    return noisy_bevy::warp_3d(pos, warp_iterations, warp_scale, falloff);
+}
+
+fn worley_2d(pos: vec2<f32>, jitter: f32) -> vec2<f32> {
+    return noisy_bevy::worley_2d(pos, jitter);
+}
+
+fn material_sample_noise(params: material::NoiseParams, pos: vec3<f32>, time: f32) -> f32 {
+    return material::sample_noise(params, pos, time);
+}
+
+fn material_noise_color(params: material::NoiseParams, value: f32) -> vec4<f32> {
+    return material::noise_color(params, value);
+}
+
+fn sample_gradient(gradient: gradient::NoiseGradient, t: f32) -> vec4<f32> {
+    return gradient::sample_gradient(gradient, t);
+}
+
+fn material_sample_noise_triplanar(params: material::NoiseParams, pos: vec3<f32>, normal: vec3<f32>, sharpness: f32, time: f32) -> f32 {
+    return material::sample_noise_triplanar(params, pos, normal, sharpness, time);
+}
+
+fn triplanar_simplex_2d(pos: vec3<f32>, normal: vec3<f32>, sharpness: f32) -> f32 {
+    return projection::triplanar_simplex_2d(pos, normal, sharpness);
+}
+
+fn triplanar_fbm_2d(pos: vec3<f32>, normal: vec3<f32>, sharpness: f32, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    return projection::triplanar_fbm_2d(pos, normal, sharpness, octaves, lacunarity, gain);
+}
+
+fn sphere_simplex_3d(lat_long: vec2<f32>, radius: f32) -> f32 {
+    return projection::sphere_simplex_3d(lat_long, radius);
+}
+
+fn sphere_fbm_3d(lat_long: vec2<f32>, radius: f32, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    return projection::sphere_fbm_3d(lat_long, radius, octaves, lacunarity, gain);
+}
+
+fn normal_from_gradient(gradient: vec2<f32>, strength: f32) -> vec3<f32> {
+    return normal_map::normal_from_gradient(gradient, strength);
+}
+
+fn cloud_density(layer: cloud::CloudLayer, pos: vec3<f32>, time: f32) -> f32 {
+    return cloud::cloud_density(layer, pos, time);
+}
+
+fn cloud_transmittance(layer: cloud::CloudLayer, start: vec3<f32>, end: vec3<f32>, time: f32, steps: u32) -> f32 {
+    return cloud::cloud_transmittance(layer, start, end, time, steps);
+}
+
+fn animated_noise_2d(noise: animated::AnimatedNoise, pos: vec2<f32>, time: f32) -> f32 {
+    return animated::animated_noise_2d(noise, pos, time);
+}
+
+fn animated_noise_3d(noise: animated::AnimatedNoise, pos: vec3<f32>, time: f32) -> f32 {
+    return animated::animated_noise_3d(noise, pos, time);
+}
+
+fn gabor_noise_2d(noise: gabor::GaborNoise, pos: vec2<f32>) -> f32 {
+    return gabor::gabor_noise_2d(noise, pos);
+}
@@ -0,0 +1,12 @@
+// Stand-in for bevy's bevy_pbr::pbr_fragment, see mesh2d_functions.wgsl
+#define_import_path bevy_pbr::pbr_fragment
+
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    pbr_types::{PbrInput, StandardMaterial},
+}
+
+fn pbr_input_from_standard_material(in: VertexOutput, is_front: bool) -> PbrInput {
+    let normal = normalize(in.world_normal);
+    return PbrInput(StandardMaterial(vec4(1.0), 0.5), in.world_position, normal, normal);
+}
@@ -0,0 +1,16 @@
+// Stand-in for bevy's bevy_pbr::pbr_functions, see mesh2d_functions.wgsl
+#define_import_path bevy_pbr::pbr_functions
+
+#import bevy_pbr::pbr_types::{PbrInput, StandardMaterial}
+
+fn alpha_discard(material: StandardMaterial, output_color: vec4<f32>) -> vec4<f32> {
+    return output_color;
+}
+
+fn apply_pbr_lighting(in: PbrInput) -> vec4<f32> {
+    return in.material.base_color;
+}
+
+fn main_pass_post_lighting_processing(pbr_input: PbrInput, input_color: vec4<f32>) -> vec4<f32> {
+    return input_color;
+}
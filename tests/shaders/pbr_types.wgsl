@@ -0,0 +1,15 @@
+// Stand-in for bevy's bevy_pbr::pbr_types, see mesh2d_functions.wgsl. Only
+// the fields noise_extension.wgsl uses.
+#define_import_path bevy_pbr::pbr_types
+
+struct StandardMaterial {
+    base_color: vec4<f32>,
+    perceptual_roughness: f32,
+}
+
+struct PbrInput {
+    material: StandardMaterial,
+    world_position: vec4<f32>,
+    world_normal: vec3<f32>,
+    N: vec3<f32>,
+}
@@ -0,0 +1,13 @@
+// The override example from examples/README.md
+#import noisy_bevy::warp::warp_2d
+#import noisy_bevy::worley::worley_2d
+
+override fn noisy_bevy::warp::warp_noise_2d(pos: vec2<f32>) -> f32 {
+    return worley_2d(pos, 1.0).x;
+}
+
+@fragment
+fn fragment(@location(0) pos: vec2<f32>) -> @location(0) vec4<f32> {
+    let warped = warp_2d(pos, 2, vec2(0.4), 0.5);
+    return vec4(vec3(warped.noise_value), 1.0);
+}
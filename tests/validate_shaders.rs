@@ -0,0 +1,162 @@
+//! Composes and validates the noisy_bevy shader modules and the example
+//! shaders the same way bevy does, so shader errors fail `cargo test` instead
+//! of showing up at runtime.
+//!
+//! bevy_sprite's and bevy_pbr's shader modules are replaced by the stand-ins
+//! in `tests/shaders`, since they need the render pipeline.
+
+mod common;
+
+use std::collections::HashMap;
+
+use common::{NOISY_BEVY_MODULES, Source, compose, composer};
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+use naga_oil::compose::{Composer, ShaderDefValue};
+
+const BEVY_STUBS: [Source; 8] = [
+    (
+        "mesh2d_functions.wgsl",
+        include_str!("shaders/mesh2d_functions.wgsl"),
+    ),
+    (
+        "mesh2d_view_bindings.wgsl",
+        include_str!("shaders/mesh2d_view_bindings.wgsl"),
+    ),
+    (
+        "mesh2d_vertex_output.wgsl",
+        include_str!("shaders/mesh2d_vertex_output.wgsl"),
+    ),
+    ("forward_io.wgsl", include_str!("shaders/forward_io.wgsl")),
+    (
+        "mesh_view_bindings.wgsl",
+        include_str!("shaders/mesh_view_bindings.wgsl"),
+    ),
+    ("pbr_types.wgsl", include_str!("shaders/pbr_types.wgsl")),
+    (
+        "pbr_fragment.wgsl",
+        include_str!("shaders/pbr_fragment.wgsl"),
+    ),
+    (
+        "pbr_functions.wgsl",
+        include_str!("shaders/pbr_functions.wgsl"),
+    ),
+];
+
+#[track_caller]
+fn validate(composer: &mut Composer, entry: Source, shader_defs: HashMap<String, ShaderDefValue>) {
+    let module = compose(composer, entry, shader_defs);
+    if let Err(e) =
+        Validator::new(ValidationFlags::all(), Capabilities::default()).validate(&module)
+    {
+        panic!("{}", e.emit_to_string(entry.1));
+    }
+}
+
+fn max_warp_iterations(n: i32) -> HashMap<String, ShaderDefValue> {
+    HashMap::from([(
+        "NOISY_MAX_WARP_ITERATIONS".to_string(),
+        ShaderDefValue::Int(n),
+    )])
+}
+
+fn high_quality_gradients() -> HashMap<String, ShaderDefValue> {
+    HashMap::from([(
+        "NOISY_HIGH_QUALITY_GRADIENTS".to_string(),
+        ShaderDefValue::Bool(true),
+    )])
+}
+
+/// Imported functions that aren't used are removed during composition, so
+/// compose each module on its own to check all of it
+#[test]
+fn noisy_bevy_modules_are_valid() {
+    for (i, module) in NOISY_BEVY_MODULES.into_iter().enumerate() {
+        let mut composer = composer(&NOISY_BEVY_MODULES[..i]);
+        validate(&mut composer, module, HashMap::new());
+        validate(&mut composer, module, max_warp_iterations(8));
+        validate(&mut composer, module, high_quality_gradients());
+    }
+}
+
+#[test]
+fn noisy_bevy_can_be_imported() {
+    let mut composer = composer(&NOISY_BEVY_MODULES);
+    let entry = ("parity.wgsl", include_str!("shaders/parity.wgsl"));
+    validate(&mut composer, entry, HashMap::new());
+}
+
+#[test]
+fn warp_noise_can_be_overridden() {
+    let mut composer = composer(&NOISY_BEVY_MODULES);
+    let entry = (
+        "warp_override.wgsl",
+        include_str!("shaders/warp_override.wgsl"),
+    );
+    validate(&mut composer, entry, HashMap::new());
+}
+
+#[test]
+fn example_shaders_are_valid() {
+    let mut composer = composer(&[&NOISY_BEVY_MODULES[..], &BEVY_STUBS].concat());
+    for example in [
+        (
+            "asteroid_background.wgsl",
+            include_str!("../assets/examples/asteroid_background.wgsl"),
+        ),
+        (
+            "domain_warping.wgsl",
+            include_str!("../assets/examples/domain_warping.wgsl"),
+        ),
+        (
+            "worley.wgsl",
+            include_str!("../assets/examples/worley.wgsl"),
+        ),
+    ] {
+        validate(&mut composer, example, HashMap::new());
+        validate(&mut composer, example, max_warp_iterations(8));
+    }
+}
+
+#[test]
+fn normal_map_compute_shader_is_valid() {
+    let mut composer = composer(&NOISY_BEVY_MODULES);
+    let entry = (
+        "noisy_bevy/normal_map_compute.wgsl",
+        include_str!("../assets/noisy_bevy/normal_map_compute.wgsl"),
+    );
+    validate(&mut composer, entry, HashMap::new());
+}
+
+/// Only the forward path, the deferred path of the extension needs bevy's
+/// prepass modules
+///
+/// The stand-ins in `tests/shaders` only declare what the materials import,
+/// copied from bevy's modules. This catches mistakes in the materials, but
+/// not changes in bevy's modules, like a renamed function or struct field
+/// after a bevy upgrade. Run the `noise_material`, `noise_material_3d` and
+/// `clouds` examples to check those, and update the stand-ins to match.
+#[test]
+fn material_shaders_are_valid() {
+    let mut composer = composer(&[&NOISY_BEVY_MODULES[..], &BEVY_STUBS].concat());
+    let shader_defs = HashMap::from([("MATERIAL_BIND_GROUP".to_string(), ShaderDefValue::UInt(2))]);
+    for material in [
+        (
+            "noisy_bevy/noise_material_2d.wgsl",
+            include_str!("../assets/noisy_bevy/noise_material_2d.wgsl"),
+        ),
+        (
+            "noisy_bevy/noise_material_3d.wgsl",
+            include_str!("../assets/noisy_bevy/noise_material_3d.wgsl"),
+        ),
+        (
+            "noisy_bevy/noise_extension.wgsl",
+            include_str!("../assets/noisy_bevy/noise_extension.wgsl"),
+        ),
+        (
+            "noisy_bevy/cloud_material.wgsl",
+            include_str!("../assets/noisy_bevy/cloud_material.wgsl"),
+        ),
+    ] {
+        validate(&mut composer, material, shader_defs.clone());
+    }
+}